@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// Bookmarked object ids, stored one per line as `bookmarks.txt` in the data dir.
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    /// Where changes are written; `None` keeps bookmarks in memory only.
+    path: Option<PathBuf>,
+    ids: Vec<String>,
+}
+
+impl Bookmarks {
+    /// Loads bookmarks from `path`, starting empty if the file is missing.
+    pub fn load(path: PathBuf) -> Self {
+        let ids = fs::read_to_string(&path)
+            .map(|content| {
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            ids,
+        }
+    }
+
+    /// Bookmarked ids, oldest first.
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.iter().any(|bookmark| bookmark == id)
+    }
+
+    /// Adds `id`, or removes it if already bookmarked, and saves.
+    /// Returns whether `id` is bookmarked afterwards.
+    pub fn toggle(&mut self, id: &str) -> bool {
+        let bookmarked = if self.contains(id) {
+            self.ids.retain(|bookmark| bookmark != id);
+            false
+        } else {
+            self.ids.push(id.to_string());
+            true
+        };
+        self.save();
+        bookmarked
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, self.ids.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bookmarks_toggle_and_persist() {
+        let path = crate::test_dir("bookmarks").join("nested/bookmarks.txt");
+
+        let mut bookmarks = Bookmarks::load(path.clone());
+        assert!(bookmarks.ids().is_empty());
+
+        assert!(bookmarks.toggle("rifle"));
+        assert!(bookmarks.toggle("mon_zombie"));
+        assert!(bookmarks.contains("rifle"));
+        assert_eq!(Bookmarks::load(path.clone()).ids(), ["rifle", "mon_zombie"]);
+
+        assert!(!bookmarks.toggle("rifle"));
+        assert!(!bookmarks.contains("rifle"));
+        assert_eq!(Bookmarks::load(path.clone()).ids(), ["mon_zombie"]);
+    }
+
+    #[test]
+    fn test_in_memory_bookmarks_are_not_saved() {
+        let mut bookmarks = Bookmarks::default();
+        assert!(bookmarks.toggle("rifle"));
+        assert_eq!(bookmarks.ids(), ["rifle"]);
+    }
+}
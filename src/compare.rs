@@ -0,0 +1,228 @@
+//! Matching the objects of one dataset against another version of it.
+
+use crate::data::IndexedItem;
+use crate::search_index::SearchIndex;
+
+/// A second dataset loaded for comparison, e.g. stable next to nightly.
+pub struct CompareDataset {
+    /// Version key the dataset was loaded for.
+    pub version: String,
+    pub items: Vec<IndexedItem>,
+    pub index: SearchIndex,
+}
+
+impl CompareDataset {
+    /// The object in this dataset with the same type and id (or abstract name) as `item`.
+    pub fn counterpart(&self, item: &IndexedItem) -> Option<&IndexedItem> {
+        find_counterpart(item, &self.items, &self.index).map(|idx| &self.items[idx])
+    }
+}
+
+/// Which dataset the filter is evaluated against while a second one is loaded.
+/// Results always list objects of the main dataset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchTarget {
+    /// Objects matching in the main dataset.
+    #[default]
+    This,
+    /// Objects whose counterpart matches in the other dataset.
+    Other,
+    /// Objects matching in the main dataset whose counterpart matches too.
+    Both,
+}
+
+impl SearchTarget {
+    /// The target after this one when cycling through them.
+    pub fn next(self) -> Self {
+        match self {
+            Self::This => Self::Other,
+            Self::Other => Self::Both,
+            Self::Both => Self::This,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::This => "this version",
+            Self::Other => "other version",
+            Self::Both => "both versions",
+        }
+    }
+}
+
+/// How an object relates to its counterpart in the other dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareStatus {
+    /// Present with an identical definition.
+    Same,
+    /// Present, but the definition differs.
+    Changed,
+    /// No object with that type and id.
+    Missing,
+}
+
+impl CompareStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Same => "same",
+            Self::Changed => "changed",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+/// Index in `other_items` of the object with the same type and id (or abstract
+/// name) as `item`. Objects with neither have no counterpart.
+pub fn find_counterpart(
+    item: &IndexedItem,
+    other_items: &[IndexedItem],
+    other_index: &SearchIndex,
+) -> Option<usize> {
+    let key = item.id_or_abstract();
+    if key.is_empty() {
+        return None;
+    }
+    let mut candidates: Vec<usize> = other_index
+        .by_id
+        .get(&key.to_lowercase())?
+        .iter()
+        .copied()
+        .filter(|&idx| {
+            let other = &other_items[idx];
+            other.item_type == item.item_type && other.id_or_abstract() == key
+        })
+        .collect();
+    // Duplicates shadow earlier definitions, like in the source loader.
+    candidates.sort_unstable();
+    candidates.last().copied()
+}
+
+/// Keeps the indices (into `items`) whose counterpart in `other` is one of
+/// `other_matches`, which index into `other.items`.
+pub fn retain_with_matching_counterpart(
+    indices: &mut Vec<usize>,
+    items: &[IndexedItem],
+    other: &CompareDataset,
+    other_matches: &[usize],
+) {
+    let other_matches: foldhash::HashSet<usize> = other_matches.iter().copied().collect();
+    indices.retain(|&idx| {
+        find_counterpart(&items[idx], &other.items, &other.index)
+            .is_some_and(|other_idx| other_matches.contains(&other_idx))
+    });
+}
+
+/// Status of every object in `items` against `other`. Objects with neither id
+/// nor abstract name can't be matched up and get `None`.
+pub fn compare_statuses(
+    items: &[IndexedItem],
+    other: &CompareDataset,
+) -> Vec<Option<CompareStatus>> {
+    items
+        .iter()
+        .map(|item| {
+            if item.id_or_abstract().is_empty() {
+                return None;
+            }
+            Some(match other.counterpart(item) {
+                None => CompareStatus::Missing,
+                Some(counterpart) if counterpart.value == item.value => CompareStatus::Same,
+                Some(_) => CompareStatus::Changed,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dataset(values: &[serde_json::Value]) -> CompareDataset {
+        let (items, index) = crate::indexed(values);
+        CompareDataset {
+            version: "stable".to_string(),
+            items,
+            index,
+        }
+    }
+
+    #[test]
+    fn test_counterpart_needs_same_type_and_id() {
+        let other = dataset(&[
+            json!({"type": "MONSTER", "id": "rifle"}),
+            json!({"type": "GUN", "id": "Rifle"}),
+            json!({"type": "GUN", "id": "rifle", "range": 20}),
+            json!({"type": "GUN", "abstract": "rifle_base"}),
+        ]);
+        let (items, _) = crate::indexed(&[
+            json!({"type": "GUN", "id": "rifle", "range": 25}),
+            json!({"type": "GUN", "abstract": "rifle_base"}),
+            json!({"type": "AMMO", "id": "rifle"}),
+            json!({"type": "recipe", "result": "rifle"}),
+        ]);
+
+        assert_eq!(
+            find_counterpart(&items[0], &other.items, &other.index),
+            Some(2)
+        );
+        assert_eq!(
+            find_counterpart(&items[1], &other.items, &other.index),
+            Some(3)
+        );
+        assert_eq!(other.counterpart(&items[2]).map(|i| &i.id), None);
+        assert_eq!(
+            find_counterpart(&items[3], &other.items, &other.index),
+            None
+        );
+    }
+
+    #[test]
+    fn test_compare_statuses() {
+        let other = dataset(&[
+            json!({"type": "GUN", "id": "rifle", "range": 20}),
+            json!({"type": "GUN", "id": "pistol", "range": 10}),
+        ]);
+        let (items, _) = crate::indexed(&[
+            json!({"type": "GUN", "id": "rifle", "range": 25}),
+            json!({"type": "GUN", "id": "pistol", "range": 10}),
+            json!({"type": "GUN", "id": "laser"}),
+            json!({"type": "recipe", "result": "laser"}),
+        ]);
+
+        assert_eq!(
+            compare_statuses(&items, &other),
+            vec![
+                Some(CompareStatus::Changed),
+                Some(CompareStatus::Same),
+                Some(CompareStatus::Missing),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retain_with_matching_counterpart() {
+        let other = dataset(&[
+            json!({"type": "GUN", "id": "pistol"}),
+            json!({"type": "GUN", "id": "rifle"}),
+        ]);
+        let (items, _) = crate::indexed(&[
+            json!({"type": "GUN", "id": "rifle"}),
+            json!({"type": "GUN", "id": "pistol"}),
+            json!({"type": "GUN", "id": "laser"}),
+        ]);
+
+        let mut indices = vec![0, 1, 2];
+        retain_with_matching_counterpart(&mut indices, &items, &other, &[1]);
+        assert_eq!(indices, vec![0]);
+    }
+
+    #[test]
+    fn test_search_target_cycles() {
+        assert_eq!(SearchTarget::default(), SearchTarget::This);
+        assert_eq!(SearchTarget::This.next(), SearchTarget::Other);
+        assert_eq!(SearchTarget::Other.next(), SearchTarget::Both);
+        assert_eq!(SearchTarget::Both.next(), SearchTarget::This);
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use cbn_tui::data::DataSource;
+use cbn_tui::matcher::ClassifierAliases;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Top-level fields whose values aren't links in the details pane, unless configured otherwise.
+pub const DEFAULT_EXCLUDED_FIELDS: &[&str] = &[
+    "id",
+    "abstract",
+    "description",
+    "name",
+    "__filename",
+    "//",
+    "//2",
+    "rows",
+];
+
+/// User settings, read from `config.toml` in the config dir.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Classifier shorthands for field paths, e.g. `str = "bash.str_min"`.
+    /// Names of built-in classifiers (`id`, `t`, ...) are ignored here.
+    pub aliases: BTreeMap<String, String>,
+    /// Like `aliases`, but allowed to redefine built-in classifiers.
+    pub alias_overrides: BTreeMap<String, String>,
+    /// Field paths shown after name and type in the list's columns view, e.g. `["volume", "weight"]`.
+    pub columns: Vec<String>,
+    /// Show numbers in the details pane with thousands separators, e.g. `1,500,000`.
+    pub group_digits: bool,
+    /// Show long strings in the details pane without quotes, wrapped at word boundaries.
+    pub prose_strings: bool,
+    /// Spaces per nesting level of the JSON in the details pane, 1 to 4.
+    pub indent_width: usize,
+    /// Give every other list row a slightly different background.
+    pub zebra_stripes: bool,
+    /// Extra field paths shown in the details header per object type, e.g. `GUN = ["range"]`.
+    pub header_fields: BTreeMap<String, Vec<String>>,
+    /// Top-level fields whose values can't be clicked or followed in the details pane.
+    pub excluded_fields: Vec<String>,
+    /// Whether typing a letter outside the filter starts filtering; when off only `/` does.
+    pub auto_filter: bool,
+    /// Whether Up on the first list entry goes to the last one, and Down on the last to the first.
+    pub wrap_navigation: bool,
+    /// Lines moved per mouse wheel step, and per arrow key in the details pane.
+    pub scroll_lines: u16,
+    /// Lines of the previous page kept in view by PageUp/PageDown.
+    pub page_overlap: u16,
+    /// Where game versions are downloaded from; `--source-url` overrides it.
+    pub data_source: DataSource,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            aliases: BTreeMap::new(),
+            alias_overrides: BTreeMap::new(),
+            columns: Vec::new(),
+            group_digits: false,
+            prose_strings: false,
+            indent_width: 2,
+            zebra_stripes: false,
+            header_fields: BTreeMap::new(),
+            excluded_fields: DEFAULT_EXCLUDED_FIELDS
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+            auto_filter: true,
+            wrap_navigation: false,
+            scroll_lines: 1,
+            page_overlap: 0,
+            data_source: DataSource::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config; a missing file gives the defaults, an invalid one is an error.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err).with_context(|| format!("Reading {}", path.display())),
+        };
+        toml::from_str(&content).with_context(|| format!("Invalid config {}", path.display()))
+    }
+
+    /// Builds the alias table used by the matcher.
+    pub fn classifier_aliases(&self) -> ClassifierAliases {
+        let mut aliases = ClassifierAliases::default();
+        for (alias, path) in &self.aliases {
+            aliases.insert(alias, path);
+        }
+        for (alias, path) in &self.alias_overrides {
+            aliases.override_builtin(alias, path);
+        }
+        aliases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_load() {
+        let path = crate::test_dir("config").join("config.toml");
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        fs::write(
+            &path,
+            "[aliases]\nstr = \"bash.str_min\"\nt = \"color\"\n\n[alias_overrides]\nc = \"color\"\n\n\
+             [header_fields]\nGUN = [\"range\"]\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        let mut expected = ClassifierAliases::default();
+        expected.insert("str", "bash.str_min");
+        expected.override_builtin("c", "color");
+        assert_eq!(config.classifier_aliases(), expected);
+        assert_eq!(config.header_fields["GUN"], ["range"]);
+        assert!(config.auto_filter);
+
+        assert_eq!(config.excluded_fields, DEFAULT_EXCLUDED_FIELDS);
+
+        fs::write(&path, "auto_filter = false").unwrap();
+        assert!(!Config::load(&path).unwrap().auto_filter);
+
+        fs::write(&path, "excluded_fields = [\"id\"]").unwrap();
+        assert_eq!(Config::load(&path).unwrap().excluded_fields, ["id"]);
+
+        fs::write(
+            &path,
+            "[data_source]\nname = \"dda\"\nbase_url = \"https://dda.example.org\"\n",
+        )
+        .unwrap();
+        let source = Config::load(&path).unwrap().data_source;
+        assert_eq!(source.name, "dda");
+        assert_eq!(source.builds_url(), "https://dda.example.org/builds.json");
+        assert_eq!(source.item_url, DataSource::default().item_url);
+
+        fs::write(
+            &path,
+            "[data_source]\nitem_url = \"https://dda.example.org/{type}/{id}\"\n",
+        )
+        .unwrap();
+        let source = Config::load(&path).unwrap().data_source;
+        assert_eq!(source.item_url, "https://dda.example.org/{type}/{id}");
+
+        fs::write(&path, "aliases = 3").unwrap();
+        assert!(Config::load(&path).is_err());
+    }
+}
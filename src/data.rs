@@ -1,8 +1,15 @@
 use anyhow::Result;
+use flate2::read::MultiGzDecoder;
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
+use serde_json::value::RawValue;
+use std::cell::Cell;
+use std::fmt;
 use std::fs;
-use std::io::{self, Read, Write};
+use std::io::{self, BufRead, Read, Write};
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::rc::Rc;
 use std::time::Duration;
 use walkdir::WalkDir;
 
@@ -28,6 +35,48 @@ pub struct IndexedItem {
     pub id: String,
     /// The resolved type string of the item.
     pub item_type: String,
+    /// The item's JSON text exactly as it appeared in the source, if it was captured during load.
+    pub raw: Option<Box<str>>,
+    /// Where the item was defined, for items loaded from a source directory.
+    pub source: Option<SourceLocation>,
+}
+
+/// The file and 1-based line an object starts at in a source directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    pub line: usize,
+}
+
+impl IndexedItem {
+    /// Wraps a parsed JSON object, resolving its id and type (no source text is kept).
+    pub fn from_value(value: Value) -> Self {
+        let id = primary_str(value.get("id")).unwrap_or("").to_string();
+        let item_type = primary_str(value.get("type")).unwrap_or("").to_string();
+        Self {
+            value,
+            id,
+            item_type,
+            raw: None,
+            source: None,
+        }
+    }
+
+    /// Returns true for abstract templates (an `abstract` key instead of an `id`).
+    pub fn is_abstract(&self) -> bool {
+        self.id.is_empty() && self.value.get("abstract").is_some_and(Value::is_string)
+    }
+
+    /// The name other objects refer to this one by: its `id`, or `abstract` for templates.
+    pub fn id_or_abstract(&self) -> &str {
+        if !self.id.is_empty() {
+            return &self.id;
+        }
+        self.value
+            .get("abstract")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    }
 }
 
 /// The root structure of the game data JSON (`all.json`).
@@ -36,8 +85,16 @@ pub struct Root {
     /// Flattened build metadata.
     #[serde(flatten)]
     pub build: BuildInfo,
-    /// The actual game data items.
-    pub data: Vec<Value>,
+    /// The actual game data items, kept as unparsed source text so the original
+    /// formatting can be shown alongside the parsed value.
+    pub data: Vec<Box<RawValue>>,
+    /// Location of each entry of `data`; empty unless loaded from a source directory.
+    #[serde(skip)]
+    pub sources: Vec<SourceLocation>,
+    /// Checksum of the file's bytes, taken while parsing it; `None` unless read
+    /// from a file. Keys the saved search index of downloaded data.
+    #[serde(skip)]
+    pub checksum: Option<u64>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -89,6 +146,152 @@ impl<'de> Deserialize<'de> for BuildInfo {
     }
 }
 
+/// Name of the built-in download source, the key for settings kept per source.
+pub const DEFAULT_SOURCE_NAME: &str = "cbn";
+
+/// Where the built-in source, the online C:BN guide, publishes its data.
+pub const DEFAULT_DATA_BASE_URL: &str = "https://data.cataclysmbn-guide.com";
+
+/// Where game versions are downloaded from: a base URL and the paths of the
+/// build list and of each version's `all.json` under it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DataSource {
+    /// Short name; keys the download cache and settings kept per source.
+    pub name: String,
+    pub base_url: String,
+    /// Path of the build list, relative to `base_url`.
+    pub builds_path: String,
+    /// Path of a version's `all.json`, relative to `base_url`; `{version}` is replaced.
+    pub data_path: String,
+    /// Template for an object's online page, as taken by [`item_url`].
+    pub item_url: String,
+}
+
+impl Default for DataSource {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_SOURCE_NAME.to_string(),
+            base_url: DEFAULT_DATA_BASE_URL.to_string(),
+            builds_path: "builds.json".to_string(),
+            data_path: "data/{version}/all.json".to_string(),
+            item_url: DEFAULT_ITEM_URL_TEMPLATE.to_string(),
+        }
+    }
+}
+
+impl DataSource {
+    /// The built-in source's layout served from `base_url`, e.g. a mirror,
+    /// named after the URL so its downloads are cached apart.
+    pub fn with_base_url(base_url: &str) -> Self {
+        let address = base_url
+            .split_once("://")
+            .map_or(base_url, |(_, address)| address);
+        Self {
+            name: cache_dir_name(address.trim_end_matches('/')),
+            base_url: base_url.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// URL of the build list.
+    pub fn builds_url(&self) -> String {
+        join_url(&self.base_url, &self.builds_path)
+    }
+
+    /// URL of the `all.json` of `version`.
+    pub fn data_url(&self, version: &str) -> String {
+        join_url(
+            &self.base_url,
+            &self.data_path.replace("{version}", version),
+        )
+    }
+
+    /// Where this source's downloads are cached: the cache dir itself for the
+    /// built-in source, a subdirectory of it for any other.
+    pub fn cache_dir(&self) -> Result<PathBuf> {
+        let cache_dir = get_cache_dir()?;
+        if self.name == DEFAULT_SOURCE_NAME {
+            return Ok(cache_dir);
+        }
+        let source_dir = cache_dir.join("sources").join(cache_dir_name(&self.name));
+        fs::create_dir_all(&source_dir)?;
+        Ok(source_dir)
+    }
+}
+
+/// `path` under `base`, with exactly one slash between them.
+fn join_url(base: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+/// `name` with anything but ASCII letters, digits, `-` and `_` replaced by `_`,
+/// safe to use as a single path component.
+fn cache_dir_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Default template for an item's page on the online C:BN guide.
+/// Placeholders: `{version}`, `{type}` and `{id}`.
+pub const DEFAULT_ITEM_URL_TEMPLATE: &str = "https://cataclysmbn-guide.com/{version}/{type}/{id}";
+
+/// JSON types the guide groups under its generic `item` pages.
+const ITEM_TYPES: &[&str] = &[
+    "AMMO",
+    "ARMOR",
+    "BATTERY",
+    "BIONIC_ITEM",
+    "BOOK",
+    "COMESTIBLE",
+    "CONTAINER",
+    "ENGINE",
+    "GENERIC",
+    "GUN",
+    "GUNMOD",
+    "MAGAZINE",
+    "PET_ARMOR",
+    "TOOL",
+    "TOOLMOD",
+    "TOOL_ARMOR",
+    "WHEEL",
+];
+
+/// Builds the online page URL for an item from `template`.
+/// Returns None for items without an id (e.g. abstracts).
+pub fn item_url(template: &str, version: &str, item_type: &str, id: &str) -> Option<String> {
+    if id.is_empty() {
+        return None;
+    }
+    let type_segment = if ITEM_TYPES.contains(&item_type) {
+        "item"
+    } else {
+        item_type
+    };
+    // Local sources have no published version; point at the latest data instead.
+    let version = match version {
+        "local" | "loading" | "" => "nightly",
+        other => other,
+    };
+    Some(
+        template
+            .replace("{version}", version)
+            .replace("{type}", type_segment)
+            .replace("{id}", id),
+    )
+}
+
 pub fn get_cache_dir() -> Result<std::path::PathBuf> {
     let project_dirs = directories::ProjectDirs::from("com", "cataclysmbn", "cbn-tui")
         .ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?;
@@ -105,16 +308,37 @@ pub fn get_data_dir() -> Result<std::path::PathBuf> {
     Ok(data_dir)
 }
 
-pub fn fetch_builds(force: bool) -> Result<Vec<BuildInfo>> {
-    fetch_builds_with_progress(force, |_| {})
+pub fn get_config_dir() -> Result<std::path::PathBuf> {
+    let project_dirs = directories::ProjectDirs::from("com", "cataclysmbn", "cbn-tui")
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+    Ok(project_dirs.config_dir().to_path_buf())
 }
 
-pub fn fetch_builds_with_progress<F>(force: bool, mut on_progress: F) -> Result<Vec<BuildInfo>>
+/// Error returned when the user aborts a download or indexing run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cancelled by user")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+pub fn fetch_builds(source: &DataSource, force: bool) -> Result<Vec<BuildInfo>> {
+    fetch_builds_with_progress(source, force, |_| ControlFlow::Continue(()))
+}
+
+pub fn fetch_builds_with_progress<F>(
+    source: &DataSource,
+    force: bool,
+    mut on_progress: F,
+) -> Result<Vec<BuildInfo>>
 where
-    F: FnMut(DownloadProgress),
+    F: FnMut(DownloadProgress) -> ControlFlow<()>,
 {
-    let cache_dir = get_cache_dir()?;
-    let builds_path = cache_dir.join("builds.json");
+    let builds_path = source.cache_dir()?.join("builds.json");
 
     let mut should_download = force || !builds_path.exists();
     if !should_download
@@ -128,14 +352,22 @@ where
 
     let content = if should_download {
         let client = http_client()?;
-        let url = "https://data.cataclysmbn-guide.com/builds.json";
-        download_to_path(&client, url, &builds_path, Some(&mut on_progress))?;
+        download_to_path(
+            &client,
+            &source.builds_url(),
+            &builds_path,
+            Some(&mut on_progress),
+        )?;
         fs::read_to_string(&builds_path)?
     } else {
-        on_progress(DownloadProgress {
+        if on_progress(DownloadProgress {
             downloaded: 1,
             total: Some(1),
-        });
+        })
+        .is_break()
+        {
+            return Err(Cancelled.into());
+        }
         fs::read_to_string(&builds_path)?
     };
 
@@ -144,16 +376,50 @@ where
     Ok(builds)
 }
 
-pub fn fetch_game_data_with_progress<F>(
+/// Loads the `all.json` of `version` from `source`, downloading it unless a fresh copy is cached.
+/// A cached copy that no longer parses is deleted and downloaded again; the
+/// returned message says so, for showing to the user.
+/// Returning `ControlFlow::Break` from `on_progress` aborts the download with [`Cancelled`].
+pub fn load_game_data_with_progress<F>(
+    source: &DataSource,
     version: &str,
     force: bool,
     mut on_progress: F,
-) -> Result<std::path::PathBuf>
+) -> Result<(Root, Option<String>)>
 where
-    F: FnMut(DownloadProgress),
+    F: FnMut(DownloadProgress) -> ControlFlow<()>,
 {
-    let cache_dir = get_cache_dir()?;
-    let version_cache_dir = cache_dir.join(version);
+    let (path, downloaded) = fetch_game_data(source, version, force, &mut on_progress)?;
+    let err = match load_root(&path.to_string_lossy()) {
+        Ok(root) => return Ok((root, None)),
+        Err(err) if !cache_needs_refetch(&err, downloaded) => return Err(err),
+        Err(err) => err,
+    };
+    let warning = format!(
+        "Cached data for {} was corrupt and has been downloaded again ({})",
+        version, err
+    );
+    fs::remove_file(&path)?;
+    let (path, _) = fetch_game_data(source, version, true, &mut on_progress)?;
+    Ok((load_root(&path.to_string_lossy())?, Some(warning)))
+}
+
+/// Whether failing to load the cached file with `err` calls for downloading it
+/// again. A file that was just downloaded isn't retried, so a bad server
+/// response fails once instead of looping.
+fn cache_needs_refetch(err: &anyhow::Error, downloaded: bool) -> bool {
+    !downloaded && !err.is::<Cancelled>()
+}
+
+/// Downloads (or reuses the cached) `all.json` for `version`. Returns its path
+/// and whether it was downloaded just now.
+fn fetch_game_data(
+    source: &DataSource,
+    version: &str,
+    force: bool,
+    on_progress: &mut dyn FnMut(DownloadProgress) -> ControlFlow<()>,
+) -> Result<(std::path::PathBuf, bool)> {
+    let version_cache_dir = source.cache_dir()?.join(version);
     fs::create_dir_all(&version_cache_dir)?;
 
     let target_path = version_cache_dir.join("all.json");
@@ -179,60 +445,263 @@ where
 
     if should_download {
         let client = http_client()?;
-        let url = format!(
-            "https://data.cataclysmbn-guide.com/data/{}/all.json",
+        download_to_path(
+            &client,
+            &source.data_url(version),
+            &target_path,
+            Some(on_progress),
+        )?;
+    } else if on_progress(DownloadProgress {
+        downloaded: 1,
+        total: Some(1),
+    })
+    .is_break()
+    {
+        return Err(Cancelled.into());
+    }
+
+    Ok((target_path, should_download))
+}
+
+/// Lists versions that have a downloaded `all.json` in `cache_dir`:
+/// stable and nightly first, then tagged builds newest first.
+pub fn list_cached_versions(cache_dir: &std::path::Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("all.json").is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    versions.sort_by(|a, b| {
+        let rank = |v: &str| match v {
+            "stable" => 0,
+            "nightly" => 1,
+            _ => 2,
+        };
+        rank(a).cmp(&rank(b)).then_with(|| b.cmp(a))
+    });
+    versions
+}
+
+/// Total size in bytes of the files under `path`, skipping entries that can't be read.
+/// Symlinks are not followed.
+pub fn dir_size(path: &std::path::Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Size of each top-level entry in `cache_dir` (one directory per version, plus
+/// `builds.json`), largest first.
+pub fn cache_usage(cache_dir: &std::path::Path) -> Vec<(String, u64)> {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+    let mut usage: Vec<(String, u64)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            (name, dir_size(&entry.path()))
+        })
+        .collect();
+    usage.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    usage
+}
+
+/// Deletes everything in `cache_dir`, keeping the directory itself.
+pub fn clear_cache(cache_dir: &std::path::Path) -> Result<()> {
+    for entry in fs::read_dir(cache_dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Path of the cached `all.json` for `version` of `source`, without touching the network.
+pub fn cached_game_data_path(source: &DataSource, version: &str) -> Result<std::path::PathBuf> {
+    let path = source.cache_dir()?.join(version).join("all.json");
+    if !path.is_file() {
+        anyhow::bail!(
+            "No cached data for version \"{}\". Run once without --offline to download it.",
             version
         );
-        download_to_path(&client, &url, &target_path, Some(&mut on_progress))?;
-    } else {
-        on_progress(DownloadProgress {
-            downloaded: 1,
-            total: Some(1),
-        });
     }
-
-    Ok(target_path)
+    Ok(path)
 }
 
 fn download_to_path(
     client: &reqwest::blocking::Client,
     url: &str,
     path: &std::path::Path,
-    mut on_progress: Option<&mut dyn FnMut(DownloadProgress)>,
+    on_progress: Option<&mut dyn FnMut(DownloadProgress) -> ControlFlow<()>>,
 ) -> Result<()> {
-    let mut response = client.get(url).send()?;
+    let response = client
+        .get(url)
+        .header(reqwest::header::ACCEPT_ENCODING, "gzip")
+        .send()?;
     if !response.status().is_success() {
         anyhow::bail!("Failed to download {}: {}", url, response.status());
     }
+    let partial_path = partial_download_path(path);
+    // The body is decoded here rather than by reqwest, so the length is that
+    // of the bytes on the wire and progress can be measured against it.
     let total = response.content_length();
+    let encoding = response
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_ascii_lowercase);
+    stream_to_file(
+        response,
+        encoding.as_deref(),
+        total,
+        &partial_path,
+        on_progress,
+    )?;
+    // Only a complete JSON document replaces the cached copy.
+    if let Err(err) = validate_json(&partial_path) {
+        let _ = fs::remove_file(&partial_path);
+        anyhow::bail!("Downloaded {} is not valid JSON: {}", url, err);
+    }
+    fs::rename(&partial_path, path)?;
+    Ok(())
+}
+
+/// Replaces `path` with `contents` through a temporary sibling file, so a crash
+/// or full disk mid-write leaves the old file rather than a truncated one.
+pub fn write_atomically(path: &std::path::Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    let result = fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Where a download to `path` is written until it has been validated.
+fn partial_download_path(path: &std::path::Path) -> std::path::PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    partial.into()
+}
+
+/// Checks that the file at `path` holds one well-formed JSON document, without
+/// building it in memory.
+fn validate_json(path: &std::path::Path) -> Result<()> {
+    let reader = io::BufReader::new(fs::File::open(path)?);
+    serde_json::from_reader::<_, serde::de::IgnoredAny>(reader)?;
+    Ok(())
+}
+
+/// Decodes `body` (sent with content `encoding`) into the file at `path` a
+/// buffer at a time. Progress counts the encoded bytes consumed, out of `total`.
+fn stream_to_file(
+    body: impl Read,
+    encoding: Option<&str>,
+    total: Option<u64>,
+    path: &std::path::Path,
+    mut on_progress: Option<&mut dyn FnMut(DownloadProgress) -> ControlFlow<()>>,
+) -> Result<()> {
+    let consumed = Rc::new(Cell::new(0u64));
+    let body = CountingReader {
+        inner: body,
+        count: Rc::clone(&consumed),
+    };
+    let mut reader: Box<dyn Read> = match encoding {
+        None | Some("identity") => Box::new(body),
+        Some("gzip" | "x-gzip") => Box::new(MultiGzDecoder::new(body)),
+        Some(other) => anyhow::bail!("Unsupported content encoding: {}", other),
+    };
     let mut file = fs::File::create(path)?;
-    let mut downloaded = 0u64;
     let mut buffer = [0u8; 65536];
 
-    if let Some(cb) = on_progress.as_deref_mut() {
-        cb(DownloadProgress { downloaded, total });
-    }
+    let mut report = |downloaded| {
+        on_progress
+            .as_deref_mut()
+            .is_some_and(|cb| cb(DownloadProgress { downloaded, total }).is_break())
+    };
 
-    loop {
-        let read = response.read(&mut buffer)?;
-        if read == 0 {
-            break;
-        }
-        file.write_all(&buffer[..read])?;
-        downloaded += read as u64;
-        if let Some(cb) = on_progress.as_deref_mut() {
-            cb(DownloadProgress { downloaded, total });
+    let mut cancelled = report(0);
+    let copied = (|| -> Result<()> {
+        while !cancelled {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            file.write_all(&buffer[..read])?;
+            cancelled = report(consumed.get());
         }
-    }
+        Ok(())
+    })();
 
+    if cancelled || copied.is_err() {
+        // Don't leave a truncated file behind to be picked up as a valid cache.
+        drop(file);
+        let _ = fs::remove_file(path);
+    }
+    copied?;
+    if cancelled {
+        return Err(Cancelled.into());
+    }
     Ok(())
 }
 
+/// Passes reads through, adding the number of bytes to a shared counter.
+struct CountingReader<R> {
+    inner: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count.set(self.count.get() + read as u64);
+        Ok(read)
+    }
+}
+
 fn http_client() -> Result<reqwest::blocking::Client> {
     Ok(reqwest::blocking::Client::builder().build()?)
 }
 
+/// First bytes of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// File argument meaning "read the dataset from stdin".
+pub const STDIN_PATH: &str = "-";
+
+/// Reads a whole `all.json` document from `reader`, buffering it first (used for stdin).
+pub fn read_root(mut reader: impl Read) -> Result<Root> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    serde_json::from_str(&content)
+        .map_err(|err| anyhow::anyhow!("Input is not a valid game data JSON document: {}", err))
+}
+
 pub fn load_root(file_path: &str) -> Result<Root> {
+    if file_path == STDIN_PATH {
+        return read_root(io::stdin().lock());
+    }
     if !std::path::Path::new(file_path).exists() {
         if file_path == "all.json" {
             anyhow::bail!(
@@ -243,17 +712,99 @@ pub fn load_root(file_path: &str) -> Result<Root> {
         }
     }
     let file = fs::File::open(file_path)?;
-    let reader = io::BufReader::new(file);
-    let root: Root = serde_json::from_reader(reader)?;
+    let mut reader = io::BufReader::new(ChecksumReader::new(file));
+    // Gzipped datasets (`all.json.gz`) are decompressed as they are parsed.
+    let mut root: Root = if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        serde_json::from_reader(io::BufReader::new(MultiGzDecoder::new(&mut reader)))?
+    } else {
+        serde_json::from_reader(&mut reader)?
+    };
+    // Parsing reads to the end of the file, so every byte has been counted.
+    root.checksum = Some(reader.get_ref().checksum);
     Ok(root)
 }
 
-pub fn load_from_source(source_dir: &str, warnings: &mut Vec<String>) -> Result<Root> {
+/// Passes reads through while folding each byte into a 64-bit FNV-1a checksum,
+/// so a file is checksummed in the same pass that parses it.
+struct ChecksumReader<R> {
+    inner: R,
+    checksum: u64,
+}
+
+impl<R> ChecksumReader<R> {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            checksum: Self::OFFSET_BASIS,
+        }
+    }
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        for &byte in &buf[..read] {
+            self.checksum = (self.checksum ^ u64::from(byte)).wrapping_mul(Self::PRIME);
+        }
+        Ok(read)
+    }
+}
+
+/// A problem found while loading a source directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceWarning {
+    pub message: String,
+    /// `(type, id)` of the object the warning is about, if it concerns a single object.
+    pub item: Option<(String, String)>,
+}
+
+impl SourceWarning {
+    /// A warning about a whole file rather than one object.
+    fn file(message: String) -> Self {
+        Self {
+            message,
+            item: None,
+        }
+    }
+}
+
+impl fmt::Display for SourceWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Indices of the items that a warning in `warnings` is about.
+pub fn items_with_warnings(
+    items: &[IndexedItem],
+    warnings: &[SourceWarning],
+) -> foldhash::HashSet<usize> {
+    let warned: foldhash::HashSet<(&str, &str)> = warnings
+        .iter()
+        .filter_map(|warning| warning.item.as_ref())
+        .map(|(type_, id)| (type_.as_str(), id.as_str()))
+        .collect();
+    if warned.is_empty() {
+        return foldhash::HashSet::default();
+    }
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| warned.contains(&(item.item_type.as_str(), item.id_or_abstract())))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+pub fn load_from_source(source_dir: &str, warnings: &mut Vec<SourceWarning>) -> Result<Root> {
     if !std::path::Path::new(source_dir).exists() {
         anyhow::bail!("Source directory does not exist: {}", source_dir);
     }
 
     let mut data = Vec::new();
+    let mut sources = Vec::new();
     let mut type_id_set = std::collections::HashSet::new();
 
     for entry in WalkDir::new(source_dir)
@@ -262,67 +813,90 @@ pub fn load_from_source(source_dir: &str, warnings: &mut Vec<String>) -> Result<
         .filter(|e| !e.file_type().is_dir())
     {
         if let Some(ext) = entry.path().extension()
-            && ext == "json" {
-                match fs::File::open(entry.path()) {
-                    Ok(file) => {
-                        let reader = io::BufReader::new(file);
-                        match serde_json::from_reader::<_, Value>(reader) {
-                            Ok(Value::Array(arr)) => {
-                                for obj in arr {
-                                    if let Some(id_val) = obj.get("id").and_then(|v| v.as_str()) {
-                                        let type_val =
-                                            obj.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                                        if !type_id_set
-                                            .insert((type_val.to_string(), id_val.to_string()))
-                                        {
-                                            warnings.push(format!(
-                                                "Duplicate ID shadowed: {} ({}) in {}",
-                                                id_val,
-                                                type_val,
-                                                entry.path().display()
-                                            ));
-                                        }
-                                    }
-                                    data.push(obj);
-                                }
-                            }
-                            Ok(Value::Object(obj)) => {
-                                if let Some(id_val) = obj.get("id").and_then(|v| v.as_str()) {
-                                    let type_val =
-                                        obj.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                                    if !type_id_set
-                                        .insert((type_val.to_string(), id_val.to_string()))
-                                    {
-                                        warnings.push(format!(
-                                            "Duplicate ID shadowed: {} ({}) in {}",
-                                            id_val,
-                                            type_val,
-                                            entry.path().display()
-                                        ));
-                                    }
-                                }
-                                data.push(Value::Object(obj));
-                            }
-                            Ok(_) => {
-                                warnings.push(format!(
-                                    "File {} contains neither array nor object",
-                                    entry.path().display()
-                                ));
-                            }
-                            Err(e) => {
-                                warnings.push(format!(
-                                    "Parse error in {}: {}",
-                                    entry.path().display(),
-                                    e
-                                ));
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        warnings.push(format!("Failed to read {}: {}", entry.path().display(), e));
+            && ext == "json"
+        {
+            let content = match fs::read_to_string(entry.path()) {
+                Ok(content) => content,
+                Err(e) => {
+                    warnings.push(SourceWarning::file(format!(
+                        "Failed to read {}: {}",
+                        entry.path().display(),
+                        e
+                    )));
+                    continue;
+                }
+            };
+
+            let objects = match parse_raw_objects(&content) {
+                Ok(Some(objects)) => objects,
+                Ok(None) => {
+                    warnings.push(SourceWarning::file(format!(
+                        "File {} contains neither array nor object",
+                        entry.path().display()
+                    )));
+                    continue;
+                }
+                Err(e) => {
+                    warnings.push(SourceWarning::file(format!(
+                        "Parse error in {}: {}",
+                        entry.path().display(),
+                        e
+                    )));
+                    continue;
+                }
+            };
+
+            let mut lines = LineCounter::new(&content);
+            for (position, obj) in objects.into_iter().enumerate() {
+                // Arrays and scalars inside a file are not game objects; listing them
+                // would only add nameless rows.
+                if !obj.get().trim_start().starts_with('{') {
+                    warnings.push(SourceWarning::file(format!(
+                        "Skipped non-object entry #{} in {}",
+                        position,
+                        entry.path().display()
+                    )));
+                    continue;
+                }
+                sources.push(SourceLocation {
+                    path: entry.path().to_path_buf(),
+                    line: lines.line_of(obj.get()),
+                });
+                data.push(obj.to_owned());
+                let Ok(key) = serde_json::from_str::<ItemKey>(obj.get()) else {
+                    continue;
+                };
+                if primary_str(key.type_.as_ref()).is_none() {
+                    let name = [&key.id, &key.abstract_]
+                        .into_iter()
+                        .find_map(|v| primary_str(v.as_ref()))
+                        .unwrap_or_default();
+                    warnings.push(SourceWarning {
+                        message: format!(
+                            "Object without type: {} (entry #{}) in {}",
+                            if name.is_empty() { "?" } else { name },
+                            position,
+                            entry.path().display()
+                        ),
+                        item: Some((String::new(), name.to_string())),
+                    });
+                }
+                if let Some(id_val) = primary_str(key.id.as_ref()) {
+                    let type_val = primary_str(key.type_.as_ref()).unwrap_or("");
+                    if !type_id_set.insert((type_val.to_string(), id_val.to_string())) {
+                        warnings.push(SourceWarning {
+                            message: format!(
+                                "Duplicate ID shadowed: {} ({}) in {}",
+                                id_val,
+                                type_val,
+                                entry.path().display()
+                            ),
+                            item: Some((type_val.to_string(), id_val.to_string())),
+                        });
                     }
                 }
             }
+        }
     }
 
     if data.is_empty() {
@@ -337,5 +911,421 @@ pub fn load_from_source(source_dir: &str, warnings: &mut Vec<String>) -> Result<
             created_at: String::new(),
         },
         data,
+        sources,
+        checksum: None,
     })
 }
+
+/// Maps slices of a file's content to line numbers, for slices visited in order.
+struct LineCounter<'a> {
+    content: &'a str,
+    offset: usize,
+    line: usize,
+}
+
+impl<'a> LineCounter<'a> {
+    fn new(content: &'a str) -> Self {
+        Self {
+            content,
+            offset: 0,
+            line: 1,
+        }
+    }
+
+    /// 1-based line `slice` starts at; `slice` must borrow from the content at
+    /// or after the previously looked up one.
+    fn line_of(&mut self, slice: &str) -> usize {
+        let start = (slice.as_ptr() as usize)
+            .saturating_sub(self.content.as_ptr() as usize)
+            .clamp(self.offset, self.content.len());
+        self.line += self.content.as_bytes()[self.offset..start]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        self.offset = start;
+        self.line
+    }
+}
+
+/// Identity fields of a game object, read without materializing the rest of it.
+#[derive(Deserialize)]
+struct ItemKey {
+    id: Option<Value>,
+    #[serde(rename = "abstract")]
+    abstract_: Option<Value>,
+    #[serde(rename = "type")]
+    type_: Option<Value>,
+}
+
+/// Splits a source file into raw per-object JSON slices.
+///
+/// Files hold either a single object or an array of objects. Returns `Ok(None)`
+/// when the top-level value is neither.
+fn parse_raw_objects(content: &str) -> Result<Option<Vec<&RawValue>>> {
+    let root: &RawValue = serde_json::from_str(content)?;
+    let text = root.get().trim_start();
+    if text.starts_with('[') {
+        Ok(Some(serde_json::from_str(text)?))
+    } else if text.starts_with('{') {
+        Ok(Some(vec![root]))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Sorts items by type, then id, which is the order the list shows them in.
+pub fn sort_indexed_items(items: &mut [IndexedItem]) {
+    items.sort_by(|a, b| a.item_type.cmp(&b.item_type).then_with(|| a.id.cmp(&b.id)));
+}
+
+/// Strings held by an `id`-like field: the string itself, or each string in an array.
+pub fn string_members(value: Option<&Value>) -> impl Iterator<Item = &str> {
+    let members: &[Value] = match value {
+        Some(Value::Array(arr)) => arr,
+        Some(value) => std::slice::from_ref(value),
+        None => &[],
+    };
+    members.iter().filter_map(Value::as_str)
+}
+
+/// The primary string of an `id`-like field: the string itself, or the first
+/// string of an array.
+pub fn primary_str(value: Option<&Value>) -> Option<&str> {
+    string_members(value).next()
+}
+
+/// Parses a raw JSON slice into an `IndexedItem`, keeping the source text.
+pub fn index_raw_item(raw: Box<RawValue>) -> Result<IndexedItem> {
+    let value: Value = serde_json::from_str(raw.get())?;
+    Ok(IndexedItem {
+        raw: Some(Box::<str>::from(raw)),
+        ..IndexedItem::from_value(value)
+    })
+}
+
+/// Rough in-memory size of a parsed JSON value, in bytes. Allocator overhead
+/// and map bookkeeping are ignored, so this undercounts a little.
+pub fn estimate_value_bytes(value: &Value) -> usize {
+    let nested = match value {
+        Value::String(s) => s.len(),
+        Value::Array(arr) => arr.iter().map(estimate_value_bytes).sum(),
+        Value::Object(obj) => obj
+            .iter()
+            .map(|(key, val)| size_of::<String>() + key.len() + estimate_value_bytes(val))
+            .sum(),
+        _ => 0,
+    };
+    size_of::<Value>() + nested
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_root_from_reader() {
+        let input = r#"{"build_number": "test", "data": [{"type": "GUN", "id": "rifle"}]}"#;
+        let root = read_root(io::Cursor::new(input)).unwrap();
+        assert_eq!(root.build.build_number, "test");
+        assert_eq!(root.data.len(), 1);
+        let item = index_raw_item(root.data.into_iter().next().unwrap()).unwrap();
+        assert_eq!(item.id, "rifle");
+
+        let err = read_root(io::Cursor::new("not json")).unwrap_err();
+        assert!(err.to_string().contains("not a valid game data JSON"));
+    }
+
+    #[test]
+    fn test_gzip_streams_to_file_with_compressed_progress() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+
+        let input = r#"{"build_number": "test", "data": [{"type": "GUN", "id": "rifle"}]}"#;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(input.as_bytes()).unwrap();
+        let archive = encoder.finish().unwrap();
+        let path = crate::test_dir("gzip_stream").join("all.json");
+
+        let mut reports = Vec::new();
+        let mut on_progress = |progress: DownloadProgress| {
+            reports.push(progress);
+            ControlFlow::Continue(())
+        };
+        stream_to_file(
+            io::Cursor::new(&archive),
+            Some("gzip"),
+            Some(archive.len() as u64),
+            &path,
+            Some(&mut on_progress),
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), input);
+        // Progress is measured in compressed bytes against the compressed length.
+        let last = reports.last().unwrap();
+        assert_eq!(last.downloaded, archive.len() as u64);
+        assert_eq!(last.total, Some(archive.len() as u64));
+
+        // A gzipped file is read the same way as a plain one.
+        fs::write(&path, &archive).unwrap();
+        let root = load_root(&path.to_string_lossy()).unwrap();
+        assert_eq!(root.data.len(), 1);
+
+        let err = stream_to_file(io::Cursor::new(b"x"), Some("br"), None, &path, None);
+        assert!(err.is_err());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_load_root_checksums_the_file_it_parses() {
+        let dir = crate::test_dir("root_checksum");
+        let checksum = |name: &str, content: &str| {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            load_root(&path.to_string_lossy()).unwrap().checksum
+        };
+        let input = r#"{"build_number": "test", "data": [{"type": "GUN", "id": "rifle"}]}"#;
+
+        let first = checksum("a.json", input);
+        assert!(first.is_some());
+        assert_eq!(checksum("b.json", input), first);
+        assert_ne!(checksum("c.json", &input.replace("rifle", "pistol")), first);
+        assert!(read_root(input.as_bytes()).unwrap().checksum.is_none());
+    }
+
+    #[test]
+    fn test_write_atomically_replaces_or_keeps_the_file() {
+        let dir = crate::test_dir("write_atomically");
+        let path = dir.join("nested/history.txt");
+        let temp_path = dir.join("nested/history.txt.tmp");
+
+        write_atomically(&path, b"t:gun").unwrap();
+        write_atomically(&path, b"t:gun\nzombie").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "t:gun\nzombie");
+        assert!(!temp_path.exists());
+
+        // A failed write leaves the existing file alone and cleans up after itself.
+        fs::create_dir_all(&temp_path).unwrap();
+        assert!(write_atomically(&path, b"lost").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "t:gun\nzombie");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_corrupt_cache_triggers_refetch() {
+        let path = crate::test_dir("corrupt_cache").join("all.json");
+        // A download cut short leaves a truncated document behind.
+        fs::write(&path, r#"{"build_number": "test", "data": [{"type": "GU"#).unwrap();
+        let err = load_root(&path.to_string_lossy()).unwrap_err();
+        assert!(validate_json(&path).is_err());
+
+        assert!(cache_needs_refetch(&err, false));
+        // A fresh download that doesn't parse is an error, not a reason to loop.
+        assert!(!cache_needs_refetch(&err, true));
+        assert!(!cache_needs_refetch(&Cancelled.into(), false));
+
+        fs::write(&path, r#"{"build_number": "test", "data": []}"#).unwrap();
+        assert!(validate_json(&path).is_ok());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_duplicate_ids_mark_their_items() {
+        let source_dir = crate::test_dir("source_warnings");
+        fs::write(
+            source_dir.join("a.json"),
+            r#"[{"type": "GUN", "id": "rifle"}, {"type": "AMMO", "id": "rifle"}]"#,
+        )
+        .unwrap();
+        fs::write(
+            source_dir.join("b.json"),
+            r#"{"type": "GUN", "id": "rifle"}"#,
+        )
+        .unwrap();
+        fs::write(source_dir.join("c.json"), "not json").unwrap();
+
+        let mut warnings = Vec::new();
+        let root = load_from_source(&source_dir.to_string_lossy(), &mut warnings).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().any(|w| w.item.is_none()));
+
+        let items: Vec<IndexedItem> = root
+            .data
+            .into_iter()
+            .map(index_raw_item)
+            .collect::<Result<_>>()
+            .unwrap();
+        let warned = items_with_warnings(&items, &warnings);
+        let mut warned: Vec<&str> = warned
+            .iter()
+            .map(|&idx| items[idx].item_type.as_str())
+            .collect();
+        warned.sort_unstable();
+        assert_eq!(warned, ["GUN", "GUN"]);
+    }
+
+    #[test]
+    fn test_non_object_entries_are_skipped() {
+        let source_dir = crate::test_dir("source_non_objects");
+        fs::write(
+            source_dir.join("mixed.json"),
+            r#"[{"type": "GUN", "id": "rifle"}, [1, 2], "text", 3, null, {"id": "untyped"}]"#,
+        )
+        .unwrap();
+
+        let mut warnings = Vec::new();
+        let root = load_from_source(&source_dir.to_string_lossy(), &mut warnings).unwrap();
+        let items: Vec<IndexedItem> = root
+            .data
+            .into_iter()
+            .map(index_raw_item)
+            .collect::<Result<_>>()
+            .unwrap();
+        let ids: Vec<&str> = items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, ["rifle", "untyped"]);
+
+        let skipped: Vec<&str> = warnings
+            .iter()
+            .filter(|w| w.item.is_none())
+            .map(|w| w.message.as_str())
+            .collect();
+        assert_eq!(skipped.len(), 4);
+        assert!(skipped[0].starts_with("Skipped non-object entry #1 in "));
+        assert!(skipped[0].ends_with("mixed.json"));
+
+        // The typeless object is kept but flagged.
+        let warned = items_with_warnings(&items, &warnings);
+        assert_eq!(warned.into_iter().collect::<Vec<_>>(), [1]);
+
+        let _ = fs::remove_dir_all(&source_dir);
+    }
+
+    #[test]
+    fn test_source_objects_record_their_line() {
+        let source_dir = crate::test_dir("source_lines");
+        let path = source_dir.join("guns.json");
+        fs::write(
+            &path,
+            "[\n  {\n    \"type\": \"GUN\",\n    \"id\": \"rifle\"\n  },\n  7,\n  { \"type\": \"GUN\", \"id\": \"pistol\" }\n]\n",
+        )
+        .unwrap();
+
+        let mut warnings = Vec::new();
+        let root = load_from_source(&source_dir.to_string_lossy(), &mut warnings).unwrap();
+        assert_eq!(root.data.len(), root.sources.len());
+        let lines: Vec<usize> = root.sources.iter().map(|s| s.line).collect();
+        assert_eq!(lines, [2, 7]);
+        assert!(root.sources.iter().all(|s| s.path == path));
+
+        let _ = fs::remove_dir_all(&source_dir);
+    }
+
+    #[test]
+    fn test_data_source_urls_use_the_configured_base() {
+        let default = DataSource::default();
+        assert_eq!(
+            default.builds_url(),
+            "https://data.cataclysmbn-guide.com/builds.json"
+        );
+        assert_eq!(
+            default.data_url("v0.9.1"),
+            "https://data.cataclysmbn-guide.com/data/v0.9.1/all.json"
+        );
+
+        let mirror = DataSource::with_base_url("http://localhost:8080/cbn/");
+        assert_eq!(mirror.name, "localhost_8080_cbn");
+        assert_eq!(mirror.builds_url(), "http://localhost:8080/cbn/builds.json");
+        assert_eq!(
+            mirror.data_url("nightly"),
+            "http://localhost:8080/cbn/data/nightly/all.json"
+        );
+
+        let custom = DataSource {
+            name: "dda".to_string(),
+            base_url: "https://example.org".to_string(),
+            builds_path: "/index/builds.json".to_string(),
+            data_path: "{version}.json".to_string(),
+            item_url: "https://example.org/{type}/{id}".to_string(),
+        };
+        assert_eq!(custom.builds_url(), "https://example.org/index/builds.json");
+        assert_eq!(custom.data_url("stable"), "https://example.org/stable.json");
+        assert_eq!(cache_dir_name("../dda"), "___dda");
+    }
+
+    #[test]
+    fn test_list_cached_versions() {
+        let cache_dir = crate::test_dir("cached_versions").join("cache");
+        assert!(list_cached_versions(&cache_dir).is_empty());
+
+        for version in ["v0.9.1", "nightly", "2024-05-01", "stable", "v0.9.2"] {
+            let dir = cache_dir.join(version);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("all.json"), "{}").unwrap();
+        }
+        // A leftover directory without data is not a usable version.
+        fs::create_dir_all(cache_dir.join("empty")).unwrap();
+        fs::write(cache_dir.join("builds.json"), "[]").unwrap();
+
+        assert_eq!(
+            list_cached_versions(&cache_dir),
+            vec!["stable", "nightly", "v0.9.2", "v0.9.1", "2024-05-01"]
+        );
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn test_dir_size_and_cache_usage() {
+        let cache_dir = crate::test_dir("dir_size").join("cache");
+        assert_eq!(dir_size(&cache_dir), 0);
+
+        fs::create_dir_all(cache_dir.join("nightly/nested")).unwrap();
+        fs::create_dir_all(cache_dir.join("stable")).unwrap();
+        fs::write(cache_dir.join("nightly/all.json"), vec![0u8; 1000]).unwrap();
+        fs::write(cache_dir.join("nightly/nested/extra"), vec![0u8; 24]).unwrap();
+        fs::write(cache_dir.join("stable/all.json"), vec![0u8; 300]).unwrap();
+        fs::write(cache_dir.join("builds.json"), vec![0u8; 7]).unwrap();
+
+        assert_eq!(dir_size(&cache_dir), 1331);
+        assert_eq!(dir_size(&cache_dir.join("nightly")), 1024);
+        assert_eq!(
+            cache_usage(&cache_dir),
+            vec![
+                ("nightly".to_string(), 1024),
+                ("stable".to_string(), 300),
+                ("builds.json".to_string(), 7),
+            ]
+        );
+
+        clear_cache(&cache_dir).unwrap();
+        assert!(cache_dir.is_dir());
+        assert_eq!(dir_size(&cache_dir), 0);
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn test_item_url_from_template() {
+        let url = item_url(DEFAULT_ITEM_URL_TEMPLATE, "nightly", "GUN", "glock_19");
+        assert_eq!(
+            url.as_deref(),
+            Some("https://cataclysmbn-guide.com/nightly/item/glock_19")
+        );
+
+        let url = item_url(
+            "https://example.org/{type}/{id}?v={version}",
+            "local",
+            "MONSTER",
+            "mon_zombie",
+        );
+        assert_eq!(
+            url.as_deref(),
+            Some("https://example.org/MONSTER/mon_zombie?v=nightly")
+        );
+
+        assert_eq!(
+            item_url(DEFAULT_ITEM_URL_TEMPLATE, "stable", "GUN", ""),
+            None
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! A loaded dataset: decoded items in list order, their search index, and an
+//! id lookup, for tools that want the items without driving the TUI.
+//!
+//! ```
+//! use cbn_tui::{data, dataset::Dataset};
+//!
+//! let json = r#"{
+//!     "build_number": "v1",
+//!     "data": [
+//!         {"type": "GUN", "id": "rifle", "ammo": ["308"]},
+//!         {"type": "AMMO", "id": "308"}
+//!     ]
+//! }"#;
+//! let dataset = Dataset::from_root(data::read_root(json.as_bytes())?)?;
+//!
+//! // Items come sorted by type, then id, as the list shows them.
+//! let ids: Vec<&str> = dataset.items().map(|item| item.id.as_str()).collect();
+//! assert_eq!(ids, ["308", "rifle"]);
+//!
+//! let rifle = dataset.get_by_id("rifle").unwrap();
+//! assert_eq!(rifle.item_type, "GUN");
+//! assert_eq!(rifle.value["ammo"][0], "308");
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::data::{self, BuildInfo, IndexedItem, Root};
+use crate::matcher;
+use crate::search_index::SearchIndex;
+use anyhow::Result;
+use foldhash::HashMap;
+
+/// Decoded items with everything needed to search and look them up.
+pub struct Dataset {
+    items: Vec<IndexedItem>,
+    search_index: SearchIndex,
+    /// Position of the first item with each non-empty id.
+    by_id: HashMap<String, usize>,
+    build: Option<BuildInfo>,
+}
+
+impl Dataset {
+    /// Decodes and indexes the objects of an `all.json` document, sorted by type, then id.
+    pub fn from_root(root: Root) -> Result<Self> {
+        let mut items = Vec::with_capacity(root.data.len());
+        let mut sources = root.sources.into_iter();
+        for raw in root.data {
+            let mut item = data::index_raw_item(raw)?;
+            item.source = sources.next();
+            items.push(item);
+        }
+        data::sort_indexed_items(&mut items);
+        let mut dataset = Self::from_items(items);
+        dataset.build = Some(root.build);
+        Ok(dataset)
+    }
+
+    /// Loads an `all.json` (plain or gzipped) or `-` for standard input.
+    pub fn load(file_path: &str) -> Result<Self> {
+        Self::from_root(data::load_root(file_path)?)
+    }
+
+    /// Indexes `items` as they are, keeping their order.
+    pub fn from_items(items: Vec<IndexedItem>) -> Self {
+        let search_index = SearchIndex::build(&items);
+        let by_id = index_by_id(&items);
+        Self {
+            items,
+            search_index,
+            by_id,
+            build: None,
+        }
+    }
+
+    /// The items in order.
+    pub fn items(&self) -> impl ExactSizeIterator<Item = &IndexedItem> {
+        self.items.iter()
+    }
+
+    /// The items as a slice, for the functions of [`matcher`] and [`crate::inheritance`].
+    pub fn as_slice(&self) -> &[IndexedItem] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The item at `idx`, as returned by [`Dataset::index_of`] or a search.
+    pub fn get(&self, idx: usize) -> Option<&IndexedItem> {
+        self.items.get(idx)
+    }
+
+    /// Position of the item with `id`. Ids are matched exactly; when objects of
+    /// different types share an id, the first in order wins. Abstract templates
+    /// have no id and aren't found.
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.by_id.get(id).copied()
+    }
+
+    /// The item with `id`, see [`Dataset::index_of`].
+    pub fn get_by_id(&self, id: &str) -> Option<&IndexedItem> {
+        self.get(self.index_of(id)?)
+    }
+
+    /// The search index over the items.
+    pub fn search_index(&self) -> &SearchIndex {
+        &self.search_index
+    }
+
+    /// Build metadata of the `all.json` the items came from; `None` for [`Dataset::from_items`].
+    pub fn build(&self) -> Option<&BuildInfo> {
+        self.build.as_ref()
+    }
+
+    /// Items matching `query`, in order, with the filter input's syntax.
+    pub fn find<'a>(&'a self, query: &str) -> impl Iterator<Item = &'a IndexedItem> + 'a {
+        matcher::find_matches(query, &self.items, &self.search_index)
+            .into_iter()
+            .map(|idx| &self.items[idx])
+    }
+}
+
+/// Position of the first item with each non-empty id; ids are kept as spelled.
+pub fn index_by_id(items: &[IndexedItem]) -> HashMap<String, usize> {
+    let mut by_id = HashMap::default();
+    for (idx, item) in items.iter().enumerate() {
+        if !item.id.is_empty() {
+            by_id.entry(item.id.clone()).or_insert(idx);
+        }
+    }
+    by_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_lookup_by_id_and_search() {
+        let (items, _) = crate::indexed(&[
+            json!({"abstract": "gun_base", "type": "GUN"}),
+            json!({"id": "rock", "type": "GENERIC"}),
+            json!({"id": "rock", "type": "terrain"}),
+            json!({"id": "rifle", "type": "GUN", "copy-from": "gun_base"}),
+        ]);
+        let dataset = Dataset::from_items(items);
+        assert_eq!(dataset.len(), 4);
+        assert_eq!(dataset.items().len(), 4);
+        assert!(dataset.build().is_none());
+
+        // The first of a shared id wins; templates have no id.
+        assert_eq!(dataset.index_of("rock"), Some(1));
+        assert_eq!(dataset.get_by_id("rifle").unwrap().item_type, "GUN");
+        assert!(dataset.get_by_id("gun_base").is_none());
+        assert!(dataset.get_by_id("Rifle").is_none());
+
+        let guns: Vec<&str> = dataset
+            .find("t:gun")
+            .map(|item| item.id_or_abstract())
+            .collect();
+        assert_eq!(guns, ["gun_base", "rifle"]);
+    }
+}
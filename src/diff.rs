@@ -0,0 +1,130 @@
+use similar::{DiffTag, TextDiff};
+
+/// How a row of a side-by-side diff differs between the two sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Same,
+    /// Only on the right side
+    Added,
+    /// Only on the left side
+    Removed,
+    /// On both sides with different content
+    Changed,
+}
+
+/// One display row of a side-by-side diff: the line index shown on each side
+/// (`None` pads the shorter side) and how the two differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffRow {
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+    pub kind: DiffKind,
+}
+
+/// Aligns the lines of `left` and `right` for side-by-side display.
+/// Replaced blocks are paired line by line; whatever is left over on the
+/// longer side becomes removed or added rows.
+pub fn diff_rows(left: &str, right: &str) -> Vec<DiffRow> {
+    let diff = TextDiff::from_lines(left, right);
+    let mut rows = Vec::new();
+
+    for op in diff.ops() {
+        let (tag, old, new) = op.as_tag_tuple();
+        let paired = match tag {
+            DiffTag::Equal | DiffTag::Replace => old.len().min(new.len()),
+            DiffTag::Delete | DiffTag::Insert => 0,
+        };
+        let paired_kind = if tag == DiffTag::Equal {
+            DiffKind::Same
+        } else {
+            DiffKind::Changed
+        };
+
+        for offset in 0..paired {
+            rows.push(DiffRow {
+                left: Some(old.start + offset),
+                right: Some(new.start + offset),
+                kind: paired_kind,
+            });
+        }
+        for line in old.start + paired..old.end {
+            rows.push(DiffRow {
+                left: Some(line),
+                right: None,
+                kind: DiffKind::Removed,
+            });
+        }
+        for line in new.start + paired..new.end {
+            rows.push(DiffRow {
+                left: None,
+                right: Some(line),
+                kind: DiffKind::Added,
+            });
+        }
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pretty(value: serde_json::Value) -> String {
+        serde_json::to_string_pretty(&value).unwrap()
+    }
+
+    #[test]
+    fn test_diff_rows_classify_lines() {
+        let base = pretty(json!({"id": "rifle", "range": 10, "weight": 2}));
+        let variant = pretty(json!({"id": "rifle_mod", "range": 10, "color": "red"}));
+        let rows = diff_rows(&base, &variant);
+
+        let kinds: Vec<DiffKind> = rows.iter().map(|row| row.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DiffKind::Same,    // {
+                DiffKind::Changed, // "id": "rifle" -> "id": "rifle_mod"
+                DiffKind::Same,    // "range": 10,
+                DiffKind::Changed, // "weight": 2 -> "color": "red"
+                DiffKind::Same,    // }
+            ]
+        );
+        // Every line of both sides appears exactly once, in order.
+        let left: Vec<usize> = rows.iter().filter_map(|row| row.left).collect();
+        let right: Vec<usize> = rows.iter().filter_map(|row| row.right).collect();
+        assert_eq!(left, (0..base.lines().count()).collect::<Vec<_>>());
+        assert_eq!(right, (0..variant.lines().count()).collect::<Vec<_>>());
+
+        let rows = diff_rows(&base, &pretty(json!({"id": "rifle", "range": 10})));
+        let kinds: Vec<DiffKind> = rows.iter().map(|row| row.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DiffKind::Same,    // {
+                DiffKind::Same,    // "id"
+                DiffKind::Changed, // "range": 10, -> "range": 10
+                DiffKind::Removed, // "weight": 2
+                DiffKind::Same,    // }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_rows_pad_shorter_side() {
+        let rows = diff_rows("{}", &pretty(json!({"a": 1, "b": 2})));
+        assert_eq!(
+            rows.iter().filter(|row| row.left.is_none()).count(),
+            rows.len() - 1
+        );
+        assert!(rows.iter().all(|row| row.kind != DiffKind::Same));
+
+        assert!(
+            diff_rows("x\ny", "x\ny")
+                .iter()
+                .all(|row| row.kind == DiffKind::Same)
+        );
+    }
+}
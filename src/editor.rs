@@ -0,0 +1,106 @@
+use std::env;
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+
+/// The user's editor command: `$VISUAL`, then `$EDITOR`.
+pub fn configured_editor() -> Option<String> {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+}
+
+/// Builds the command opening `path` in `editor`, an editor command line such
+/// as `nvim` or `code -w`. Editors with a known go-to-line syntax are sent to
+/// `line`; any other editor just gets the file.
+pub fn editor_command(editor: &str, path: &Path, line: usize) -> Option<Command> {
+    let mut words = editor.split_whitespace();
+    let program = words.next()?;
+    let mut command = Command::new(program);
+    command.args(words);
+
+    let name = Path::new(program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(program);
+    match name {
+        "vi" | "vim" | "nvim" | "nano" | "emacs" | "emacsclient" | "micro" | "kak" | "joe" => {
+            command.arg(format!("+{}", line)).arg(path);
+        }
+        "code" | "code-insiders" | "codium" => {
+            command.arg("--goto").arg(path_with_line(path, line));
+        }
+        "subl" | "hx" | "helix" | "zed" => {
+            command.arg(path_with_line(path, line));
+        }
+        _ => {
+            command.arg(path);
+        }
+    }
+    Some(command)
+}
+
+/// `path:line`, the location syntax of most GUI editors.
+fn path_with_line(path: &Path, line: usize) -> OsString {
+    let mut target = path.as_os_str().to_owned();
+    target.push(format!(":{}", line));
+    target
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(command: &Command) -> (String, Vec<String>) {
+        (
+            command.get_program().to_string_lossy().into_owned(),
+            command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_editor_command_passes_line_when_supported() {
+        let path = Path::new("data/json/items/gun.json");
+
+        let vim = editor_command("/usr/bin/nvim", path, 42).unwrap();
+        assert_eq!(
+            parts(&vim),
+            (
+                "/usr/bin/nvim".into(),
+                vec!["+42".into(), path.display().to_string()]
+            )
+        );
+
+        let code = editor_command("code -w", path, 7).unwrap();
+        assert_eq!(
+            parts(&code),
+            (
+                "code".into(),
+                vec![
+                    "-w".into(),
+                    "--goto".into(),
+                    format!("{}:7", path.display())
+                ]
+            )
+        );
+
+        let hx = editor_command("hx", path, 3).unwrap();
+        assert_eq!(parts(&hx).1, [format!("{}:3", path.display())]);
+
+        // Unknown editors only get the file.
+        let other = editor_command("gedit --new-window", path, 42).unwrap();
+        assert_eq!(
+            parts(&other),
+            (
+                "gedit".into(),
+                vec!["--new-window".into(), path.display().to_string()]
+            )
+        );
+
+        assert!(editor_command("  ", path, 1).is_none());
+    }
+}
@@ -0,0 +1,294 @@
+//! Resolution of `copy-from` inheritance into the effective definition of an object.
+
+use crate::data::IndexedItem;
+use crate::search_index::SearchIndex;
+use serde_json::{Map, Value};
+
+/// Longest `copy-from` chain followed before giving up.
+const MAX_DEPTH: usize = 32;
+
+/// Fields that describe the definition itself rather than values a child inherits.
+const NOT_INHERITED: &[&str] = &["abstract", "copy-from"];
+
+/// Modifier blocks applied to inherited values, then dropped from the result.
+const MODIFIERS: &[&str] = &["extend", "delete", "relative", "proportional"];
+
+/// Returns the effective definition of `items[idx]`: its `copy-from` parents are
+/// resolved recursively and the object's own fields are laid over them.
+///
+/// `extend` appends to and `delete` removes from inherited arrays, `relative`
+/// adds to and `proportional` multiplies inherited numbers. Missing parents and
+/// cycles stop the walk; whatever was resolved so far is kept.
+pub fn resolve_item(items: &[IndexedItem], index: &SearchIndex, idx: usize) -> Value {
+    let mut chain = Vec::new();
+    resolve_recursive(items, index, idx, &mut chain)
+}
+
+fn resolve_recursive(
+    items: &[IndexedItem],
+    index: &SearchIndex,
+    idx: usize,
+    chain: &mut Vec<usize>,
+) -> Value {
+    let Some(item) = items.get(idx) else {
+        return Value::Null;
+    };
+    let Value::Object(own) = &item.value else {
+        return item.value.clone();
+    };
+
+    chain.push(idx);
+    let parent = crate::data::primary_str(own.get("copy-from"))
+        .and_then(|parent_id| find_parent(items, index, parent_id, &item.item_type, chain))
+        .map(|parent_idx| resolve_recursive(items, index, parent_idx, chain));
+    chain.pop();
+
+    let Some(Value::Object(inherited)) = parent else {
+        return item.value.clone();
+    };
+    // The object's own fields first, in source order, then whatever it inherits.
+    let mut merged: Map<String, Value> = own
+        .iter()
+        .filter(|(key, _)| !MODIFIERS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    for (key, value) in inherited {
+        if !NOT_INHERITED.contains(&key.as_str()) {
+            merged.entry(key).or_insert(value);
+        }
+    }
+    apply_modifiers(&mut merged, own);
+    Value::Object(merged)
+}
+
+/// Looks up the object named by `copy-from`, preferring one of the same type.
+/// Objects already on the chain are skipped so cycles terminate.
+fn find_parent(
+    items: &[IndexedItem],
+    index: &SearchIndex,
+    parent_id: &str,
+    item_type: &str,
+    chain: &[usize],
+) -> Option<usize> {
+    if chain.len() >= MAX_DEPTH {
+        return None;
+    }
+    let mut candidates: Vec<usize> = index
+        .by_id
+        .get(&parent_id.to_lowercase())?
+        .iter()
+        .copied()
+        .filter(|idx| !chain.contains(idx))
+        .collect();
+    candidates.sort_unstable();
+    candidates
+        .iter()
+        .copied()
+        .find(|&idx| items[idx].item_type == item_type)
+        .or_else(|| candidates.first().copied())
+}
+
+/// An object whose `copy-from` names nothing in the dataset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingCopyFrom {
+    /// Index of the offending object in `items`.
+    pub idx: usize,
+    /// The missing parent it names.
+    pub parent: String,
+}
+
+/// Lists the objects whose `copy-from` is neither an id in `ids` nor an
+/// abstract template, in dataset order.
+pub fn dangling_copy_from(
+    items: &[IndexedItem],
+    ids: &foldhash::HashSet<String>,
+) -> Vec<DanglingCopyFrom> {
+    let abstracts: foldhash::HashSet<&str> = items
+        .iter()
+        .filter_map(|item| item.value.get("abstract").and_then(Value::as_str))
+        .collect();
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| {
+            let parent = crate::data::primary_str(item.value.get("copy-from"))?;
+            if ids.contains(parent) || abstracts.contains(parent) {
+                return None;
+            }
+            Some(DanglingCopyFrom {
+                idx,
+                parent: parent.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn apply_modifiers(merged: &mut Map<String, Value>, own: &Map<String, Value>) {
+    if let Some(Value::Object(extend)) = own.get("extend") {
+        for (key, added) in extend {
+            let target = merged
+                .entry(key.clone())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            match (target, added) {
+                (Value::Array(target), Value::Array(added)) => target.extend(added.iter().cloned()),
+                (Value::Array(target), added) => target.push(added.clone()),
+                (target, added) => *target = added.clone(),
+            }
+        }
+    }
+    if let Some(Value::Object(delete)) = own.get("delete") {
+        for (key, removed) in delete {
+            if let Some(Value::Array(target)) = merged.get_mut(key) {
+                let removed: Vec<&Value> = match removed {
+                    Value::Array(values) => values.iter().collect(),
+                    value => vec![value],
+                };
+                target.retain(|value| !removed.contains(&value));
+            }
+        }
+    }
+    if let Some(Value::Object(relative)) = own.get("relative") {
+        for (key, delta) in relative {
+            if let Some(target) = merged.get_mut(key) {
+                combine_numbers(target, delta, |base, delta| base + delta);
+            }
+        }
+    }
+    if let Some(Value::Object(proportional)) = own.get("proportional") {
+        for (key, factor) in proportional {
+            if let Some(target) = merged.get_mut(key) {
+                combine_numbers(target, factor, |base, factor| base * factor);
+            }
+        }
+    }
+}
+
+/// Combines numbers in `target` with the matching ones in `modifier`, recursing
+/// into objects. Values without a numeric counterpart are left alone.
+fn combine_numbers(target: &mut Value, modifier: &Value, op: fn(f64, f64) -> f64) {
+    match (target, modifier) {
+        (Value::Object(target), Value::Object(modifier)) => {
+            for (key, modifier) in modifier {
+                if let Some(target) = target.get_mut(key) {
+                    combine_numbers(target, modifier, op);
+                }
+            }
+        }
+        (target @ Value::Number(_), Value::Number(modifier)) => {
+            let (Some(base), Some(modifier)) = (target.as_f64(), modifier.as_f64()) else {
+                return;
+            };
+            let result = op(base, modifier);
+            // Keep integers integral so the resolved JSON reads like the source.
+            *target = if target.is_f64() || result.fract() != 0.0 {
+                serde_json::Number::from_f64(result).map_or(Value::Null, Value::Number)
+            } else {
+                Value::from(result as i64)
+            };
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_two_level_inheritance() {
+        let (items, index) = crate::indexed(&[
+            json!({
+                "type": "GUN",
+                "abstract": "rifle_base",
+                "name": "rifle",
+                "weight": 3000,
+                "range": 20,
+                "flags": ["A", "B"],
+                "ranged_damage": {"amount": 10, "armor_penetration": 2}
+            }),
+            json!({
+                "type": "GUN",
+                "id": "rifle",
+                "copy-from": "rifle_base",
+                "range": 25,
+                "extend": {"flags": ["C"]},
+                "delete": {"flags": ["A"]}
+            }),
+            json!({
+                "type": "GUN",
+                "id": "rifle_mod",
+                "copy-from": "rifle",
+                "name": "modded rifle",
+                "relative": {"ranged_damage": {"amount": 5}},
+                "proportional": {"weight": 1.5}
+            }),
+        ]);
+
+        assert_eq!(
+            resolve_item(&items, &index, 2),
+            json!({
+                "type": "GUN",
+                "name": "modded rifle",
+                "weight": 4500,
+                "range": 25,
+                "flags": ["B", "C"],
+                "ranged_damage": {"amount": 15, "armor_penetration": 2},
+                "id": "rifle_mod",
+                "copy-from": "rifle",
+            })
+        );
+        // Objects without `copy-from` come back unchanged.
+        assert_eq!(resolve_item(&items, &index, 0), items[0].value);
+    }
+
+    #[test]
+    fn test_cycles_and_missing_parents_terminate() {
+        let (items, index) = crate::indexed(&[
+            json!({"type": "GUN", "id": "a", "copy-from": "b", "x": 1}),
+            json!({"type": "GUN", "id": "b", "copy-from": "a", "y": 2}),
+            json!({"type": "GUN", "id": "c", "copy-from": "missing", "z": 3}),
+        ]);
+
+        let resolved = resolve_item(&items, &index, 0);
+        assert_eq!(resolved["x"], 1);
+        assert_eq!(resolved["y"], 2);
+        assert_eq!(resolve_item(&items, &index, 2), items[2].value);
+    }
+
+    #[test]
+    fn test_parent_of_same_type_is_preferred() {
+        let (items, index) = crate::indexed(&[
+            json!({"type": "MONSTER", "id": "thing", "hp": 50}),
+            json!({"type": "GUN", "id": "thing", "range": 5}),
+            json!({"type": "GUN", "id": "child", "copy-from": "thing"}),
+        ]);
+
+        let resolved = resolve_item(&items, &index, 2);
+        assert_eq!(resolved["range"], 5);
+        assert!(resolved.get("hp").is_none());
+    }
+
+    #[test]
+    fn test_dangling_copy_from_is_reported() {
+        let (items, _) = crate::indexed(&[
+            json!({"type": "GUN", "abstract": "rifle_base"}),
+            json!({"type": "GUN", "id": "rifle", "copy-from": "rifle_base"}),
+            json!({"type": "GUN", "id": "rifle_mod", "copy-from": "rifle"}),
+            json!({"type": "GUN", "id": "orphan", "copy-from": "gone"}),
+        ]);
+        let ids = items
+            .iter()
+            .filter(|item| !item.id.is_empty())
+            .map(|item| item.id.clone())
+            .collect();
+
+        assert_eq!(
+            dangling_copy_from(&items, &ids),
+            [DanglingCopyFrom {
+                idx: 3,
+                parent: "gone".into()
+            }]
+        );
+    }
+}
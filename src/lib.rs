@@ -0,0 +1,50 @@
+//! # cbn-tui
+//!
+//! Data loading, indexing and query matching behind the cbn-tui browser, usable on
+//! their own to build other frontends with the same search semantics.
+//!
+//! ```no_run
+//! use cbn_tui::{data, matcher, search_index::SearchIndex};
+//!
+//! let root = data::load_root("all.json")?;
+//! let items = root
+//!     .data
+//!     .into_iter()
+//!     .map(data::index_raw_item)
+//!     .collect::<anyhow::Result<Vec<_>>>()?;
+//! let index = SearchIndex::build(&items);
+//! for idx in matcher::find_matches("t:GUN ammo:9mm", &items, &index) {
+//!     println!("{}", items[idx].id);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! [`dataset::Dataset`] does the same steps in one call and adds lookups by id.
+
+pub mod compare;
+pub mod data;
+pub mod dataset;
+pub mod inheritance;
+pub mod matcher;
+pub mod search_index;
+
+/// Returns an empty scratch directory unique to this test process.
+#[cfg(test)]
+fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("cbn_test_{}_{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create test dir");
+    dir
+}
+
+/// Wraps test objects as indexed items and builds their search index.
+#[cfg(test)]
+fn indexed(values: &[serde_json::Value]) -> (Vec<data::IndexedItem>, search_index::SearchIndex) {
+    let items: Vec<_> = values
+        .iter()
+        .cloned()
+        .map(data::IndexedItem::from_value)
+        .collect();
+    let index = search_index::SearchIndex::build(&items);
+    (items, index)
+}
@@ -0,0 +1,440 @@
+//! Loads a dataset on a worker thread. Progress comes back over a channel, so
+//! the UI thread stays free to draw, read input and cancel.
+
+use crate::{TransferRate, format_transfer, progress_ratio};
+use anyhow::Result;
+use cbn_tui::{data, search_index};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Shortest gap between two progress messages; the last step of a stage is always sent.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(120);
+
+/// File next to a downloaded `all.json` holding its saved search index.
+const INDEX_CACHE_FILE: &str = "search_index.json.gz";
+
+/// Where a dataset is loaded from.
+pub enum LoadSource {
+    /// Data parsed before the TUI started (read from standard input).
+    Preloaded(data::Root),
+    /// A directory of JSON files (`--source`).
+    SourceDir(String),
+    /// An `all.json` given with `--file`.
+    File(String),
+    /// A previously downloaded `all.json`, used in --offline mode.
+    Cached(PathBuf),
+    /// A game version's `all.json`, downloaded unless the cached copy is fresh.
+    Download {
+        source: data::DataSource,
+        version: String,
+        force: bool,
+    },
+}
+
+impl LoadSource {
+    /// Title of the progress display.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::SourceDir(_) => "Loading local data",
+            Self::Cached(_) => "Loading cached data",
+            _ => "Loading data",
+        }
+    }
+
+    /// The stages the worker reports on, in order.
+    pub fn stages(&self) -> &'static [&'static str] {
+        match self {
+            Self::SourceDir(_) => &["Loading files", "Parsing", "Indexing"],
+            Self::Download { .. } => &["Downloading", "Parsing", "Indexing"],
+            _ => &["Parsing", "Indexing"],
+        }
+    }
+}
+
+/// What the worker sends back.
+pub enum LoadMessage {
+    /// `stage` reached `ratio` (0.0 to 1.0), or is running with no known size
+    /// when `ratio` is `None`. A `detail` replaces the stage's detail line.
+    Progress {
+        stage: &'static str,
+        ratio: Option<f64>,
+        detail: Option<String>,
+    },
+    /// The last message: the loaded dataset, or why there is none.
+    /// A cancelled load ends with [`data::Cancelled`].
+    Done(Result<Box<LoadedData>>),
+}
+
+/// A parsed and indexed dataset, ready for `AppState::apply_new_dataset`.
+pub struct LoadedData {
+    pub indexed_items: Vec<data::IndexedItem>,
+    pub search_index: search_index::SearchIndex,
+    pub total_items: usize,
+    pub index_time_ms: f64,
+    pub build: data::BuildInfo,
+    /// Warnings from a source-directory load; `None` for other sources.
+    pub source_warnings: Option<Vec<data::SourceWarning>>,
+    /// Something the user should know about the load, e.g. a discarded corrupt cache.
+    pub warning: Option<String>,
+}
+
+/// A load running on its own thread. Dropping the task cancels it.
+pub struct LoadTask {
+    receiver: Receiver<LoadMessage>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl LoadTask {
+    pub fn spawn(source: LoadSource) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut reporter = Reporter {
+            sender,
+            cancel: Arc::clone(&cancel),
+            last_sent: None,
+        };
+        thread::spawn(move || {
+            let result = load(source, &mut reporter).map(Box::new);
+            // Nobody is listening any more if the task was dropped.
+            let _ = reporter.sender.send(LoadMessage::Done(result));
+        });
+        Self { receiver, cancel }
+    }
+
+    /// Asks the worker to stop; it ends with `Done(Err(Cancelled))` at its next check.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// The next message, if one arrives within `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<LoadMessage> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(message) => Some(message),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(worker_lost()),
+        }
+    }
+
+    /// The next message, if one is waiting.
+    pub fn try_recv(&self) -> Option<LoadMessage> {
+        match self.receiver.try_recv() {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(worker_lost()),
+        }
+    }
+}
+
+impl Drop for LoadTask {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Stands in for the `Done` of a worker that died without sending one.
+fn worker_lost() -> LoadMessage {
+    LoadMessage::Done(Err(anyhow::anyhow!(
+        "The loader thread stopped unexpectedly"
+    )))
+}
+
+/// The worker's end of the channel.
+struct Reporter {
+    sender: Sender<LoadMessage>,
+    cancel: Arc<AtomicBool>,
+    last_sent: Option<Instant>,
+}
+
+impl Reporter {
+    /// Sends progress for `stage`, throttled, and tells the caller whether to go on.
+    /// `ratio` is `None` while the stage's size is unknown. `detail` is only built
+    /// for messages that are actually sent.
+    fn report(
+        &mut self,
+        stage: &'static str,
+        ratio: Option<f64>,
+        detail: impl FnOnce() -> Option<String>,
+    ) -> ControlFlow<()> {
+        let due = self
+            .last_sent
+            .is_none_or(|sent| sent.elapsed() >= PROGRESS_INTERVAL);
+        if ratio.is_some_and(|ratio| ratio >= 1.0) || due {
+            self.last_sent = Some(Instant::now());
+            let _ = self.sender.send(LoadMessage::Progress {
+                stage,
+                ratio: ratio.map(|ratio| ratio.clamp(0.0, 1.0)),
+                detail: detail(),
+            });
+        }
+        if self.cancel.load(Ordering::Relaxed) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    /// Marks `stage` as done, failing with [`data::Cancelled`] if a cancel is pending.
+    fn finish(&mut self, stage: &'static str) -> Result<()> {
+        if self.report(stage, Some(1.0), || None).is_break() {
+            return Err(data::Cancelled.into());
+        }
+        Ok(())
+    }
+}
+
+fn load(source: LoadSource, reporter: &mut Reporter) -> Result<LoadedData> {
+    let mut source_warnings = None;
+    let mut warning = None;
+    // Only downloaded data is big and unchanging enough to be worth saving the index of.
+    let mut downloaded_path = match &source {
+        LoadSource::Cached(path) => Some(path.clone()),
+        _ => None,
+    };
+    let root = match source {
+        LoadSource::Preloaded(root) => root,
+        LoadSource::SourceDir(dir) => {
+            let mut warnings = Vec::new();
+            let root = data::load_from_source(&dir, &mut warnings)?;
+            source_warnings = Some(warnings);
+            reporter.finish("Loading files")?;
+            root
+        }
+        LoadSource::File(file) => data::load_root(&file)?,
+        LoadSource::Cached(path) => data::load_root(&path.to_string_lossy())?,
+        LoadSource::Download {
+            source,
+            version,
+            force,
+        } => {
+            let mut rate = TransferRate::default();
+            let (root, cache_warning) =
+                data::load_game_data_with_progress(&source, &version, force, |progress| {
+                    let bytes_per_sec = rate.update(Instant::now(), progress.downloaded);
+                    reporter.report("Downloading", progress_ratio(progress), || {
+                        Some(format_transfer(
+                            progress.downloaded,
+                            progress.total,
+                            bytes_per_sec,
+                        ))
+                    })
+                })?;
+            reporter.finish("Downloading")?;
+            warning = cache_warning;
+            downloaded_path = data::cached_game_data_path(&source, &version).ok();
+            root
+        }
+    };
+    reporter.finish("Parsing")?;
+
+    let total_items = root.data.len();
+    let start = Instant::now();
+    let mut indexed_items = Vec::with_capacity(total_items);
+    let mut sources = root.sources.into_iter();
+    for (idx, raw) in root.data.into_iter().enumerate() {
+        let mut item = data::index_raw_item(raw)?;
+        item.source = sources.next();
+        indexed_items.push(item);
+
+        // Parsing the objects is the first 40% of indexing.
+        let ratio = (idx + 1) as f64 / total_items as f64 * 0.4;
+        if reporter.report("Indexing", Some(ratio), || None).is_break() {
+            return Err(data::Cancelled.into());
+        }
+    }
+    data::sort_indexed_items(&mut indexed_items);
+
+    let saved_index = downloaded_path
+        .zip(root.checksum)
+        .map(|(path, checksum)| SavedIndexPath::for_data(&path, checksum));
+    let search_index = match saved_index.as_ref().and_then(SavedIndexPath::load) {
+        Some(search_index) => search_index,
+        None => {
+            let search_index = search_index::SearchIndex::build_with_progress(
+                &indexed_items,
+                |processed, total| {
+                    let ratio = if total > 0 {
+                        0.4 + 0.6 * (processed as f64 / total as f64)
+                    } else {
+                        1.0
+                    };
+                    reporter.report("Indexing", Some(ratio), || None)
+                },
+            )?;
+            // A failed save only means rebuilding next time.
+            if let Some(saved_index) = &saved_index {
+                let _ = search_index.save(&saved_index.path, saved_index.dataset);
+            }
+            search_index
+        }
+    };
+    let index_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+    reporter.finish("Indexing")?;
+
+    Ok(LoadedData {
+        indexed_items,
+        search_index,
+        total_items,
+        index_time_ms,
+        build: root.build,
+        source_warnings,
+        warning,
+    })
+}
+
+/// Where the index of a downloaded `all.json` is saved, and the checksum it is keyed by.
+struct SavedIndexPath {
+    path: PathBuf,
+    dataset: u64,
+}
+
+impl SavedIndexPath {
+    /// `checksum` is the one `load_root` took while parsing the file, so it
+    /// isn't read a second time.
+    fn for_data(data_path: &Path, checksum: u64) -> Self {
+        Self {
+            path: data_path.with_file_name(INDEX_CACHE_FILE),
+            dataset: checksum,
+        }
+    }
+
+    fn load(&self) -> Option<search_index::SearchIndex> {
+        search_index::SearchIndex::load(&self.path, self.dataset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_JSON: &str = r#"{
+        "build_number": "v1",
+        "data": [
+            {"type": "GUN", "id": "rifle"},
+            {"type": "GUN", "id": "pistol"}
+        ]
+    }"#;
+
+    fn write_all_json(name: &str) -> PathBuf {
+        let path = crate::test_dir(name).join("all.json");
+        std::fs::write(&path, ALL_JSON).unwrap();
+        path
+    }
+
+    type Progress = Vec<(&'static str, Option<f64>)>;
+
+    /// Every message the task sends, up to and including `Done`.
+    fn collect(task: &LoadTask) -> (Progress, Result<Box<LoadedData>>) {
+        let mut progress = Vec::new();
+        loop {
+            match task.recv_timeout(Duration::from_secs(10)) {
+                Some(LoadMessage::Progress { stage, ratio, .. }) => progress.push((stage, ratio)),
+                Some(LoadMessage::Done(result)) => return (progress, result),
+                None => panic!("loader sent nothing for 10s"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_reports_each_stage_then_done() {
+        let path = write_all_json("loader_all");
+        let source = LoadSource::File(path.to_string_lossy().into_owned());
+        let stages = source.stages();
+        let task = LoadTask::spawn(source);
+
+        let (progress, result) = collect(&task);
+        let loaded = result.unwrap();
+        assert_eq!(loaded.total_items, 2);
+        assert_eq!(loaded.indexed_items.len(), 2);
+        assert_eq!(loaded.build.tag_name, "v1");
+        assert!(loaded.source_warnings.is_none());
+
+        // Stages arrive in the advertised order, each ending complete.
+        let mut finished: Vec<&str> = progress
+            .iter()
+            .filter(|(_, ratio)| *ratio == Some(1.0))
+            .map(|(stage, _)| *stage)
+            .collect();
+        finished.dedup();
+        assert_eq!(finished, stages);
+        assert!(
+            progress
+                .iter()
+                .all(|(_, ratio)| ratio.is_some_and(|ratio| (0.0..=1.0).contains(&ratio)))
+        );
+
+        // Nothing follows `Done`; the closed channel reads as a lost worker.
+        assert!(matches!(
+            task.recv_timeout(Duration::from_secs(10)),
+            Some(LoadMessage::Done(Err(_)))
+        ));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_cached_data_saves_and_reuses_its_index() {
+        let dir = crate::test_dir("loader_index");
+        let path = dir.join("all.json");
+        std::fs::write(&path, ALL_JSON).unwrap();
+        let index_path = dir.join(INDEX_CACHE_FILE);
+
+        let first = collect(&LoadTask::spawn(LoadSource::Cached(path.clone())))
+            .1
+            .unwrap();
+        assert!(index_path.is_file());
+        let second = collect(&LoadTask::spawn(LoadSource::Cached(path.clone())))
+            .1
+            .unwrap();
+        assert_eq!(second.search_index.stats(), first.search_index.stats());
+
+        // New data under the same name gets a fresh index.
+        std::fs::write(&path, ALL_JSON.replace("pistol", "carbine")).unwrap();
+        let third = collect(&LoadTask::spawn(LoadSource::Cached(path)))
+            .1
+            .unwrap();
+        assert!(third.search_index.by_id.contains_key("carbine"));
+    }
+
+    #[test]
+    fn test_cancelled_load_ends_with_cancelled() {
+        let path = write_all_json("loader_cancel");
+        let (sender, receiver) = mpsc::channel();
+        let mut reporter = Reporter {
+            sender,
+            cancel: Arc::new(AtomicBool::new(true)),
+            last_sent: None,
+        };
+
+        let result = load(
+            LoadSource::File(path.to_string_lossy().into_owned()),
+            &mut reporter,
+        );
+        assert!(result.is_err_and(|err| err.is::<data::Cancelled>()));
+        // The worker stops at the first stage boundary.
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(LoadMessage::Progress {
+                stage: "Parsing",
+                ..
+            })
+        ));
+        assert!(receiver.try_recv().is_err());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_failed_load_reports_the_error() {
+        let task = LoadTask::spawn(LoadSource::File(
+            "/nonexistent/cbn_test_loader.json".to_string(),
+        ));
+
+        let (progress, result) = collect(&task);
+        assert!(progress.is_empty());
+        let err = result.err().unwrap();
+        assert!(!err.is::<data::Cancelled>());
+    }
+}
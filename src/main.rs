@@ -3,8 +3,10 @@
 //! A terminal user interface (TUI) for browsing Cataclysm: Bright Nights game data.
 
 use anyhow::Result;
+use cbn_tui::{compare, data, dataset, inheritance, matcher, search_index};
 use clap::Parser;
 use crossterm::{
+    clipboard::CopyToClipboard,
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
     },
@@ -12,16 +14,21 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend, widgets::ListState};
-use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::fs;
 use std::io;
+use std::ops::ControlFlow;
 use std::str::FromStr;
 use std::time::{Duration, Instant};
 use tui_scrollview::ScrollViewState;
 
-mod data;
-mod matcher;
-mod search_index;
+mod bookmarks;
+mod config;
+mod diff;
+mod editor;
+mod loader;
+mod recent;
+mod session;
 mod theme;
 mod ui;
 
@@ -34,26 +41,39 @@ mod ui;
                   Supports searching through items, monsters, and other game entities with a built-in search index."
 )]
 struct Args {
-    /// Path to the all.json file
+    /// Path to the all.json file, or - to read it from stdin
     #[arg(short, long)]
     file: Option<String>,
 
-    /// Game version to download (e.g., v0.9.1, stable, nightly)
-    #[arg(short, long, default_value = "nightly")]
-    game: String,
+    /// Same as --file (e.g. `cat all.json | cbn-tui -`)
+    #[arg(value_name = "FILE", conflicts_with = "file")]
+    input: Option<String>,
+
+    /// Game version to download (e.g., v0.9.1, stable, nightly); defaults to nightly, or after
+    /// switching download source to the version last loaded from it
+    #[arg(short, long)]
+    game: Option<String>,
 
     /// Force download of game data even if cached
     #[arg(long)]
     force: bool,
 
+    /// Never touch the network; only use datasets already in the cache
+    #[arg(long, conflicts_with = "force")]
+    offline: bool,
+
     /// List all available game versions
     #[arg(long)]
     game_versions: bool,
 
-    /// UI theme (dracula, solarized, gruvbox, everforest_light)
+    /// UI theme (dracula, solarized, gruvbox, everforest_light, monochrome)
     #[arg(short, long)]
     theme: Option<String>,
 
+    /// Disable colors (same as setting NO_COLOR)
+    #[arg(long, conflicts_with = "theme")]
+    no_color: bool,
+
     /// Show all paths used by the application (data, cache, history)
     #[arg(long)]
     config: bool,
@@ -62,9 +82,60 @@ struct Args {
     #[arg(long)]
     clear_history: bool,
 
+    /// Show how much disk the downloaded datasets use
+    #[arg(long)]
+    cache_info: bool,
+
+    /// Delete all downloaded datasets
+    #[arg(long)]
+    clear_cache: bool,
+
     /// Local directory of JSON files to source data from
     #[arg(short, long)]
     source: Option<String>,
+
+    /// Initial filter query (used by --json-only, or pre-filled in the TUI)
+    #[arg(short, long)]
+    query: Option<String>,
+
+    /// Print items matching --query as a JSON array and exit, without the TUI
+    #[arg(long)]
+    json_only: bool,
+
+    /// Load the dataset, print its object count and memory use, and exit
+    #[arg(long)]
+    stats: bool,
+
+    /// Print objects whose `copy-from` names a missing parent, and exit (non-zero if any)
+    #[arg(long)]
+    lint: bool,
+
+    /// Also load this game version for comparison (`other:` filter, Ctrl+D diff); doubles memory use
+    #[arg(long, value_name = "VERSION")]
+    compare: Option<String>,
+
+    /// Start fresh, neither restoring nor saving the last filter and theme
+    #[arg(long)]
+    no_restore: bool,
+
+    /// URL template for opening items online ({version}, {type}, {id}); overrides `item_url` of the data source
+    #[arg(long, value_name = "TEMPLATE")]
+    item_url: Option<String>,
+
+    /// Base URL to download game data from instead of the C:BN guide, e.g. a mirror
+    #[arg(long, value_name = "URL")]
+    source_url: Option<String>,
+
+    /// Where downloads come from: --source-url, else `[data_source]` in the config
+    #[arg(skip)]
+    data_source: data::DataSource,
+}
+
+impl Args {
+    /// The game version to download: `--game`, or nightly when none was given or remembered.
+    fn game(&self) -> &str {
+        self.game.as_deref().unwrap_or("nightly")
+    }
 }
 
 /// Current input mode for the application.
@@ -83,11 +154,50 @@ pub enum FocusPane {
     Filter,
 }
 
+/// How the list and details panes are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutMode {
+    /// Side by side, stacked when the terminal is narrow
+    Auto,
+    /// Always side by side
+    SideBySide,
+    /// Always list on top, details below
+    Stacked,
+}
+
+impl LayoutMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Auto => Self::SideBySide,
+            Self::SideBySide => Self::Stacked,
+            Self::Stacked => Self::Auto,
+        }
+    }
+}
+
+/// Release channel of a build listed in the version picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildKind {
+    Stable,
+    Nightly,
+}
+
+impl BuildKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Nightly => "nightly",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct VersionEntry {
     pub label: String,
     pub version: String,
     pub detail: Option<String>,
+    /// Channel of a concrete build; `None` for the channel aliases and cached datasets.
+    pub kind: Option<BuildKind>,
 }
 
 #[derive(Debug, Clone)]
@@ -95,13 +205,145 @@ pub struct ProgressStage {
     pub label: String,
     pub ratio: f64,
     pub done: bool,
+    /// The stage's size is unknown (a download without a length), so there is
+    /// no meaningful `ratio` and an animated bar is shown instead
+    pub indeterminate: bool,
+    /// Extra status shown under the gauge (e.g. transfer speed and ETA)
+    pub detail: Option<String>,
+}
+
+/// A dataset download that failed, shown in the error modal until retried or dismissed.
+#[derive(Debug, Clone)]
+pub struct LoadFailure {
+    /// Version whose download failed
+    pub version: String,
+    /// Error text, including its causes
+    pub message: String,
+    /// Whether the version was being loaded for comparison rather than browsing
+    pub compare: bool,
+}
+
+/// A version loading on a worker thread while the current dataset stays in use.
+pub struct BackgroundLoad {
+    task: loader::LoadTask,
+    /// Version being loaded
+    pub version: String,
+    /// Description of where the data comes from, for the about overlay
+    data_source: String,
+    /// Whether the version becomes the dataset compared against instead of the current one
+    compare: bool,
+}
+
+/// Completions offered for the filter term being typed.
+pub struct Completion {
+    /// The partial term in `filter_text` that an accepted candidate replaces
+    token: matcher::CompletionToken,
+    /// Indexed values extending the term, most common first
+    pub candidates: Vec<String>,
+}
+
+/// Most completions the popup lists.
+const COMPLETION_LIMIT: usize = 8;
+
+/// How long the UI waits for loader messages before redrawing and checking input.
+const LOAD_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Most filter history entries kept; older ones are dropped.
+const MAX_HISTORY: usize = 500;
+/// Name of the file in the working directory that Alt+E writes the marked items
+/// to; later exports add a number instead of overwriting it.
+const EXPORT_FILE: &str = "cbn-tui-export.json";
+/// How long to wait before retrying a history save that failed.
+const HISTORY_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Pause after which list type-ahead ends and the next letter goes to the filter again.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Incremental jump through the list by name prefix, started with `'`.
+#[derive(Debug, Clone)]
+pub struct TypeAhead {
+    /// Prefix typed so far
+    pub query: String,
+    /// Whether some row starts with `query`
+    pub matched: bool,
+    last_key: Instant,
+}
+
+/// Number of attempts made when the user asks to retry a failed download.
+const RETRY_ATTEMPTS: u32 = 3;
+/// Delay before the second attempt; it doubles for each one after.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// How long to wait before download attempt `attempt` (0-based) of a retry.
+fn retry_backoff(attempt: u32) -> Duration {
+    match attempt {
+        0 => Duration::ZERO,
+        n => RETRY_BASE_DELAY * 2u32.pow(n - 1),
+    }
+}
+
+/// Measures transfer speed over a short sliding window.
+#[derive(Debug, Default)]
+struct TransferRate {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl TransferRate {
+    const WINDOW: Duration = Duration::from_secs(2);
+
+    /// Records the running byte count and returns the current speed in bytes/sec.
+    fn update(&mut self, now: Instant, downloaded: u64) -> f64 {
+        self.samples.push_back((now, downloaded));
+        // Drop samples outside the window, keeping at least one to measure against.
+        while self.samples.len() > 2 && now.duration_since(self.samples[0].0) > Self::WINDOW {
+            self.samples.pop_front();
+        }
+        let (first_at, first_bytes) = self.samples[0];
+        let elapsed = now.duration_since(first_at).as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        downloaded.saturating_sub(first_bytes) as f64 / elapsed
+    }
+}
+
+/// Number of listed items with `value` at `path`, for the status bar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueCount {
+    pub path: String,
+    pub value: String,
+    pub count: usize,
+}
+
+/// How many top-level fields an id preview lists besides the type.
+const ID_PREVIEW_FIELDS: usize = 4;
+
+/// The object a hovered id refers to, shown in a floating box by the mouse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdPreview {
+    pub id: String,
+    /// Screen cell the mouse was on when the preview opened
+    pub anchor: (u16, u16),
+    pub title: String,
+    /// `(field, value)` rows: the type, then the first few scalar fields
+    pub fields: Vec<(String, String)>,
 }
 
 #[derive(Debug, Clone)]
 enum AppAction {
     OpenVersionPicker,
     SwitchVersion(String),
-    ReloadSource,
+    /// Loads the given version again: `local` for the source directory
+    Reload(String),
+    OpenInBrowser(String),
+    OpenInEditor(data::SourceLocation),
+    CopyToClipboard(String),
+    /// Writes the marked items to a new file in the given directory
+    ExportMarked(std::path::PathBuf),
+    /// Re-attempts the download described by `AppState::load_failure`
+    RetryLoad,
+    /// Loads a version as the dataset compared against
+    LoadCompare(String),
 }
 
 /// Application state for the Ratatui app.
@@ -112,14 +354,34 @@ pub struct AppState {
     pub search_index: search_index::SearchIndex,
     /// Set of purely IDs for O(1) existence checks (used for click navigation)
     pub id_set: foldhash::HashSet<String>,
+    /// Index into indexed_items of the first item with each ID, for selecting by ID
+    pub id_to_index: foldhash::HashMap<String, usize>,
     /// Indices into indexed_items that match the current filter
     pub filtered_indices: Vec<usize>,
+    /// Whether the filter walked every item rather than using the index
+    pub filter_slow_path: bool,
+    /// Classifier of a slow-path filter term that matched nothing (for the empty-list hint)
+    pub filter_slow_path_miss: Option<String>,
+    /// Filter classifiers that match no indexed field or item path
+    pub filter_unknown_classifiers: Vec<String>,
+    /// Number of matches for a `count:` query, shown instead of the list
+    pub match_count: Option<usize>,
+    /// Matches before a `limit:` term cut the list down, for "showing N of M"
+    pub limited_total: Option<usize>,
+    /// Error for a query that could not be evaluated
+    pub filter_error: Option<String>,
     /// List selection state managed by ratatui
     pub list_state: ListState,
     /// Filter input text
     pub filter_text: String,
     /// Cursor position in filter
     pub filter_cursor: usize,
+    /// When the filter was last edited without re-running the matcher
+    pub filter_pending_since: Option<Instant>,
+    /// Completions for the term at the filter cursor, shown in a popup while typing
+    pub completion: Option<Completion>,
+    /// Selection state for the completion popup
+    pub completion_list_state: ListState,
     /// Current input mode
     pub input_mode: InputMode,
     /// Which pane currently has keyboard focus
@@ -134,10 +396,20 @@ pub struct AppState {
     pub app_version: String,
     /// Whether to force downloads when switching
     pub force_download: bool,
+    /// Whether to use only cached datasets (--offline)
+    pub offline: bool,
+    /// Whether the dataset came from the download source or its cache, so it can be fetched again
+    pub from_download: bool,
     /// Number of items in the full dataset
     pub total_items: usize,
     /// Time taken to build the index
     pub index_time_ms: f64,
+    /// Where the current dataset was loaded from, e.g. a source dir or the download cache
+    pub data_source: String,
+    /// Where game versions are downloaded from
+    pub download_source: data::DataSource,
+    /// Game version last loaded from each download source, keyed by source name
+    pub versions_by_source: BTreeMap<String, String>,
     /// Scroll state for details pane
     /// State for scrolling the details pane
     pub details_scroll_state: ScrollViewState,
@@ -147,14 +419,96 @@ pub struct AppState {
     pub details_wrapped_annotated: Vec<Vec<ui::AnnotatedSpan>>,
     /// Width used for current details_wrapped_annotated
     pub details_wrapped_width: u16,
+    /// For each wrapped details line, whether it continues on the next one
+    pub details_wrap_continued: Vec<bool>,
+    /// Text selected in the details pane (Shift+arrows or mouse drag)
+    pub details_selection: Option<ui::DetailsSelection>,
+    /// Whether details lines wrap at the pane width (otherwise they scroll horizontally)
+    pub details_wrap: bool,
+    /// Whether numbers in the details pane are shown with thousands separators (display only)
+    pub group_digits: bool,
+    /// Whether long strings in the details pane are shown unquoted and wrapped at words (display only)
+    pub prose_strings: bool,
+    /// Spaces per nesting level of the JSON in the details pane
+    pub indent_width: usize,
+    /// Whether every other list row gets the theme's stripe background
+    pub zebra_stripes: bool,
+    /// Top-level fields whose values aren't links in the details pane
+    pub excluded_fields: Vec<String>,
+    /// Whether typing a letter outside the filter starts filtering (otherwise only `/` does)
+    pub auto_filter: bool,
+    /// Whether Up/Down wrap around at the ends of the list
+    pub wrap_navigation: bool,
+    /// Lines moved per mouse wheel step, and per arrow key in the details pane
+    pub scroll_lines: u16,
+    /// Lines of the previous page kept in view by PageUp/PageDown
+    pub page_overlap: u16,
+    /// Second dataset loaded for comparison; diffed against when nothing is pinned
+    pub compare: Option<compare::CompareDataset>,
+    /// Dataset(s) the filter is evaluated against while `compare` is loaded
+    pub search_target: compare::SearchTarget,
+    /// Item pinned as the left side of the diff view (index into indexed_items)
+    pub pinned_item: Option<usize>,
+    /// Items marked with Space for export (indices into indexed_items, in list order)
+    pub marked: BTreeSet<usize>,
+    /// Bookmarked object ids, persisted in the data dir
+    pub bookmarks: bookmarks::Bookmarks,
+    /// Classifier aliases, warned items and compare statuses used when matching
+    pub match_options: matcher::MatchOptions,
+    /// Whether the bookmarks overlay is visible
+    pub show_bookmarks: bool,
+    /// Selection state for the bookmarks overlay
+    pub bookmark_list_state: ListState,
+    /// Recently viewed object ids, saved with the session
+    pub recent: recent::RecentItems,
+    /// Whether the recently viewed overlay is visible
+    pub show_recent: bool,
+    /// Selection state for the recently viewed overlay
+    pub recent_list_state: ListState,
+    /// Whether the lint report overlay is visible
+    pub show_lint: bool,
+    /// Dangling `copy-from` references found by the last lint run
+    pub lint_entries: Vec<inheritance::DanglingCopyFrom>,
+    /// Selection state for the lint report overlay
+    pub lint_list_state: ListState,
+    /// Whether the source warnings overlay is visible
+    pub show_warnings: bool,
+    /// Selection state for the source warnings overlay
+    pub warnings_list_state: ListState,
+    /// Whether the details pane compares the pinned item with the selected one
+    pub show_diff: bool,
+    /// Side-by-side diff rendered instead of the details JSON while `show_diff` is on
+    pub details_diff: Option<ui::DetailsDiff>,
     /// Currently hovered span ID for tracking click/hover
     pub hovered_span_id: Option<usize>,
+    /// JSON dot-path of the hovered span, shown in the status bar
+    pub hovered_path: Option<String>,
+    /// How many listed items share the hovered link's value
+    pub hovered_value_count: Option<ValueCount>,
+    /// Counts of listed items by (path, value) hovered since the filter last ran
+    value_counts: foldhash::HashMap<(String, String), usize>,
+    /// JSON dot-path of whatever details span is under the mouse, shown as the breadcrumb
+    pub hovered_key_path: Option<String>,
+    /// Preview of the object whose id is under the mouse
+    pub hovered_preview: Option<IdPreview>,
+    /// One-off message shown in the status bar until the next key press
+    pub status_message: Option<String>,
+    /// Span ID of the link selected via keyboard in the details pane
+    pub focused_link_id: Option<usize>,
+    /// Whether abstract templates are left out of the filtered list
+    pub hide_abstracts: bool,
+    /// Arrangement of the list and details panes
+    pub layout_mode: LayoutMode,
     /// Screen region of the JSON content area (set during render)
     pub details_content_area: Option<ratatui::layout::Rect>,
+    /// Screen column of the details minimap, when the body is too long to fit (set during render)
+    pub details_minimap_area: Option<ratatui::layout::Rect>,
     /// Screen region of the item list pane (including borders)
     pub list_area: Option<ratatui::layout::Rect>,
     /// Screen region of list content (inside borders)
     pub list_content_area: Option<ratatui::layout::Rect>,
+    /// Clickable type/category values of the details header (set during render)
+    pub header_links: Vec<ui::HeaderLink>,
     /// Screen region of the details pane (including borders)
     pub details_area: Option<ratatui::layout::Rect>,
     /// Screen region of the filter pane (including borders)
@@ -165,18 +519,42 @@ pub struct AppState {
     pub should_quit: bool,
     /// Whether help overlay is visible
     pub show_help: bool,
+    /// Whether the about overlay (versions, dataset, data source) is visible
+    pub show_about: bool,
     /// Whether a version picker is visible
     pub show_version_picker: bool,
     /// List of available versions for the picker
     pub version_entries: Vec<VersionEntry>,
-    /// Selection state for version picker
+    /// Text typed into the version picker to narrow its list
+    pub version_picker_query: String,
+    /// Selection state for version picker (indexes the narrowed list)
     pub version_list_state: ListState,
+    /// Whether the type picker is visible
+    pub show_type_picker: bool,
+    /// Distinct item types in the dataset with their item counts, sorted by name
+    pub type_entries: Vec<(String, usize)>,
+    /// Text typed into the type picker to narrow its list
+    pub type_picker_query: String,
+    /// Selection state for the type picker (indexes the narrowed list)
+    pub type_list_state: ListState,
+    /// Whether the filter-history search overlay is visible
+    pub show_history_search: bool,
+    /// Text typed into the history search to fuzzy-match entries
+    pub history_search_query: String,
+    /// Active list type-ahead, if any
+    pub type_ahead: Option<TypeAhead>,
+    /// Selection state for the history search (indexes the ranked list)
+    pub history_list_state: ListState,
     /// Whether progress modal is visible
     pub show_progress: bool,
     /// Progress modal title
     pub progress_title: String,
     /// Progress stages for modal display
     pub progress_stages: Vec<ProgressStage>,
+    /// Animation step of indeterminate progress bars, advanced on every draw
+    pub progress_frame: usize,
+    /// Set when Esc is pressed while a load is in progress
+    pub progress_cancel_requested: bool,
     /// Previous search expressions
     pub filter_history: Vec<String>,
     /// Current index in history during navigation
@@ -185,22 +563,52 @@ pub struct AppState {
     pub stashed_input: String,
     /// Path to history file
     pub history_path: std::path::PathBuf,
+    /// When the last history save failed; its entries are written again after
+    /// `HISTORY_RETRY_INTERVAL` and at quit
+    pub history_unsaved_since: Option<Instant>,
     /// Pending action to execute after input handling
     pending_action: Option<AppAction>,
+    /// Failed download awaiting retry or dismissal
+    pub load_failure: Option<LoadFailure>,
+    /// Version load in progress; its stages are in `progress_stages`
+    pub background_load: Option<BackgroundLoad>,
+    /// Size of the download cache, measured when the help overlay first opens
+    /// after a load
+    pub cache_size: Option<u64>,
+    /// Set by the first `x` in the help overlay; a second one clears the cache
+    pub confirm_clear_cache: bool,
+    /// Outcome of the last cache clearing, shown in the help overlay
+    pub cache_clear_result: Option<String>,
     /// Source directory, if in --source mode
     pub source_dir: Option<String>,
     /// Warnings accumulated during source loading
-    pub source_warnings: Vec<String>,
+    pub source_warnings: Vec<data::SourceWarning>,
     /// Index into indexed_items that is currently rendered in the details pane.
     /// Used to skip expensive JSON re-rendering when the same item is re-selected.
     cached_details_item_idx: Option<usize>,
-    /// Pre-computed (display_name, type_prefix) strings for the current filtered list.
+    /// Pre-computed display entries for the current filtered list.
     /// Rebuilt only when filtered_indices changes, used by render_item_list via &str borrows
     /// to avoid JSON traversal and String allocations on every frame.
-    pub cached_display: Vec<(String, String)>,
+    pub cached_display: Vec<ui::ListRow>,
+    /// Whether the list shows aligned columns (name, type, `list_columns`) instead of names
+    pub show_columns: bool,
+    /// Field paths shown as extra columns in the columns view, from the config file
+    pub list_columns: Vec<String>,
+    /// Field paths added to the details header, by object type, from the config file
+    pub header_fields: BTreeMap<String, Vec<String>>,
+    /// Width of each column in the columns view, header included
+    pub cached_column_widths: Vec<usize>,
     /// Cached horizontal separator for the details pane to avoid an allocation per frame.
     /// Stores the width and the generated string.
     cached_separator: (u16, String),
+    /// Whether the details pane shows the item's original source text instead of pretty JSON
+    pub show_raw_json: bool,
+    /// Whether the details pane shows the item with its `copy-from` chain applied
+    pub show_resolved: bool,
+    /// Whether the details pane lists `path = value` lines instead of nested JSON
+    pub show_table: bool,
+    /// Filter text the details pane highlights were computed for
+    cached_details_filter: String,
 }
 
 impl AppState {
@@ -224,6 +632,7 @@ impl AppState {
             .filter(|item| !item.id.is_empty())
             .map(|item| item.id.clone())
             .collect();
+        let id_to_index = dataset::index_by_id(&indexed_items);
         let mut list_state = ListState::default();
         if filtered_indices.is_empty() {
             list_state.select(None);
@@ -235,10 +644,20 @@ impl AppState {
             indexed_items,
             search_index,
             id_set,
+            id_to_index,
             filtered_indices,
+            filter_slow_path: false,
+            filter_slow_path_miss: None,
+            filter_unknown_classifiers: Vec::new(),
+            match_count: None,
+            limited_total: None,
+            filter_error: None,
             list_state,
             filter_text: String::new(),
             filter_cursor: 0,
+            filter_pending_since: None,
+            completion: None,
+            completion_list_state: ListState::default(),
             input_mode: InputMode::Normal,
             focused_pane: FocusPane::List,
             theme,
@@ -246,59 +665,154 @@ impl AppState {
             game_version_key,
             app_version,
             force_download,
+            from_download: false,
+            offline: false,
             total_items,
             index_time_ms,
+            data_source: String::new(),
+            download_source: data::DataSource::default(),
+            versions_by_source: BTreeMap::new(),
             details_scroll_state: ScrollViewState::default(),
             details_annotated: Vec::new(),
             details_wrapped_annotated: Vec::new(),
             details_wrapped_width: 0,
+            details_wrap_continued: Vec::new(),
+            details_selection: None,
+            details_wrap: true,
+            group_digits: false,
+            prose_strings: false,
+            indent_width: 2,
+            zebra_stripes: false,
+            excluded_fields: config::DEFAULT_EXCLUDED_FIELDS
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+            auto_filter: true,
+            wrap_navigation: false,
+            scroll_lines: 1,
+            page_overlap: 0,
+            compare: None,
+            search_target: compare::SearchTarget::default(),
+            pinned_item: None,
+            marked: BTreeSet::new(),
+            show_diff: false,
+            bookmarks: bookmarks::Bookmarks::default(),
+            match_options: matcher::MatchOptions::default(),
+            show_bookmarks: false,
+            bookmark_list_state: ListState::default(),
+            recent: recent::RecentItems::default(),
+            show_recent: false,
+            recent_list_state: ListState::default(),
+            show_lint: false,
+            lint_entries: Vec::new(),
+            lint_list_state: ListState::default(),
+            show_warnings: false,
+            warnings_list_state: ListState::default(),
+            details_diff: None,
             hovered_span_id: None,
+            hovered_path: None,
+            hovered_value_count: None,
+            value_counts: foldhash::HashMap::default(),
+            hovered_key_path: None,
+            hovered_preview: None,
+            status_message: None,
+            focused_link_id: None,
+            hide_abstracts: false,
+            layout_mode: LayoutMode::Auto,
             details_content_area: None,
+            details_minimap_area: None,
             list_area: None,
             list_content_area: None,
+            header_links: Vec::new(),
             details_area: None,
             filter_area: None,
             filter_input_area: None,
             should_quit: false,
             show_help: false,
+            show_about: false,
             show_version_picker: false,
             version_entries: Vec::new(),
+            version_picker_query: String::new(),
             version_list_state: ListState::default(),
+            show_type_picker: false,
+            type_entries: Vec::new(),
+            type_picker_query: String::new(),
+            type_list_state: ListState::default(),
+            show_history_search: false,
+            history_search_query: String::new(),
+            history_list_state: ListState::default(),
+            type_ahead: None,
             show_progress: false,
             progress_title: String::new(),
             progress_stages: Vec::new(),
+            progress_frame: 0,
+            progress_cancel_requested: false,
             filter_history: Vec::new(),
             history_index: None,
             stashed_input: String::new(),
             history_path,
+            history_unsaved_since: None,
             pending_action: None,
+            load_failure: None,
+            background_load: None,
+            cache_size: None,
+            confirm_clear_cache: false,
+            cache_clear_result: None,
             source_dir,
             source_warnings: Vec::new(),
             cached_details_item_idx: None,
             cached_display: Vec::new(),
+            show_columns: false,
+            list_columns: Vec::new(),
+            header_fields: BTreeMap::new(),
+            cached_column_widths: Vec::new(),
             cached_separator: (0, String::new()),
+            show_raw_json: false,
+            show_resolved: false,
+            show_table: false,
+            cached_details_filter: String::new(),
         };
         app.load_history();
         app.refresh_details();
         app
     }
 
+    /// Reads the saved history the way `push_history` keeps it: one entry per
+    /// filter at its last position, and no more than `MAX_HISTORY`.
     fn load_history(&mut self) {
-        if let Ok(content) = fs::read_to_string(&self.history_path) {
-            self.filter_history = content
-                .lines()
-                .filter(|line| !line.trim().is_empty())
-                .map(|s| s.to_string())
-                .collect();
+        let Ok(content) = fs::read_to_string(&self.history_path) else {
+            return;
+        };
+        self.filter_history.clear();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            self.push_history(line.to_string());
         }
     }
 
-    fn save_history(&self) {
-        if let Some(parent) = self.history_path.parent() {
-            let _ = fs::create_dir_all(parent);
-        }
+    /// Records a submitted filter as the newest history entry, moving an identical
+    /// older entry instead of duplicating it and dropping the oldest past the cap.
+    fn push_history(&mut self, entry: String) {
+        self.filter_history.retain(|existing| *existing != entry);
+        self.filter_history.push(entry);
+        let excess = self.filter_history.len().saturating_sub(MAX_HISTORY);
+        self.filter_history.drain(..excess);
+    }
+
+    fn save_history(&mut self) {
         let content = self.filter_history.join("\n");
-        let _ = fs::write(&self.history_path, content);
+        self.history_unsaved_since = data::write_atomically(&self.history_path, content.as_bytes())
+            .is_err()
+            .then(Instant::now);
+    }
+
+    /// Retries a failed history save once `HISTORY_RETRY_INTERVAL` has passed.
+    fn retry_history_save(&mut self) {
+        if self
+            .history_unsaved_since
+            .is_some_and(|since| since.elapsed() >= HISTORY_RETRY_INTERVAL)
+        {
+            self.save_history();
+        }
     }
 
     /// Gets or creates the horizontal separator for a given width.
@@ -323,16 +837,52 @@ impl AppState {
         // Skip the expensive serde_json::to_string_pretty + highlight pass when
         // the same item is already rendered. The wrapped cache is kept intact, so
         // the width-change guard in render_details still triggers a re-wrap on resize.
-        if self.cached_details_item_idx == selected_item_idx && selected_item_idx.is_some() {
+        if self.cached_details_item_idx == selected_item_idx
+            && selected_item_idx.is_some()
+            && self.cached_details_filter == self.filter_text
+        {
             return;
         }
         self.cached_details_item_idx = selected_item_idx;
+        self.cached_details_filter.clone_from(&self.filter_text);
+        self.focused_link_id = None;
+        self.hovered_preview = None;
+
+        if let Some(id) = self.get_selected_item().map(|item| item.id.clone()) {
+            self.recent.push(&id);
+        }
 
         if let Some(item) = self.get_selected_item() {
-            match serde_json::to_string_pretty(&item.value) {
-                Ok(json_str) => {
-                    self.details_annotated =
-                        ui::highlight_json_annotated(&json_str, &self.theme.json_style);
+            let annotated = if self.show_table {
+                let value = match selected_item_idx {
+                    Some(idx) if self.show_resolved => std::borrow::Cow::Owned(
+                        inheritance::resolve_item(&self.indexed_items, &self.search_index, idx),
+                    ),
+                    _ => std::borrow::Cow::Borrowed(&item.value),
+                };
+                Ok(ui::table_annotated(&value, &self.theme.json_style))
+            } else {
+                let json_text = match (&item.raw, selected_item_idx) {
+                    (Some(raw), _) if self.show_raw_json => Ok(raw.to_string()),
+                    (_, Some(idx)) if self.show_resolved => pretty_json(
+                        &inheritance::resolve_item(&self.indexed_items, &self.search_index, idx),
+                        self.indent_width,
+                    ),
+                    _ => pretty_json(&item.value, self.indent_width),
+                };
+                json_text
+                    .map(|json_str| ui::highlight_json_annotated(&json_str, &self.theme.json_style))
+            };
+            match annotated {
+                Ok(annotated) => {
+                    self.details_annotated = annotated;
+                    let mut terms = matcher::parse_query(&self.filter_text);
+                    self.match_options.aliases.apply(&mut terms);
+                    ui::highlight_search_matches(
+                        &mut self.details_annotated,
+                        &terms,
+                        self.theme.search_match,
+                    );
                 }
                 Err(_) => {
                     self.details_annotated = vec![vec![ui::AnnotatedSpan {
@@ -351,11 +901,308 @@ impl AppState {
                 span_id: None,
             }]];
         }
+        self.details_diff = self.build_details_diff();
         // Invalidate wrapped cache so render_details re-wraps for the new content.
         self.details_wrapped_width = 0;
         self.details_wrapped_annotated.clear();
     }
 
+    /// Lines up the pinned item (left) against the selected one (right).
+    fn build_details_diff(&self) -> Option<ui::DetailsDiff> {
+        if !self.show_diff {
+            return None;
+        }
+        let base = self.diff_base()?;
+        let selected = self.get_selected_item()?;
+        let left = pretty_json(&base.value, self.indent_width).ok()?;
+        let right = pretty_json(&selected.value, self.indent_width).ok()?;
+        let mut left_lines = ui::highlight_json_annotated(&left, &self.theme.json_style);
+        let mut right_lines = ui::highlight_json_annotated(&right, &self.theme.json_style);
+        if self.group_digits {
+            left_lines = ui::group_number_spans(&left_lines);
+            right_lines = ui::group_number_spans(&right_lines);
+        }
+        Some(ui::DetailsDiff {
+            rows: diff::diff_rows(&left, &right),
+            left: left_lines,
+            right: right_lines,
+        })
+    }
+
+    /// Left side of the diff: the pinned item, or else the selected item's
+    /// counterpart in the compare dataset.
+    fn diff_base(&self) -> Option<&data::IndexedItem> {
+        if let Some(pinned) = self.pinned_item {
+            return self.indexed_items.get(pinned);
+        }
+        self.compare
+            .as_ref()?
+            .counterpart(self.get_selected_item()?)
+    }
+
+    /// Names the left side of the diff view.
+    pub fn diff_label(&self) -> Option<&str> {
+        if self.pinned_item.is_some() {
+            return self.pinned_label();
+        }
+        self.compare_version()
+    }
+
+    /// Version of the dataset compared against, if one is loaded.
+    pub fn compare_version(&self) -> Option<&str> {
+        self.compare.as_ref().map(|other| other.version.as_str())
+    }
+
+    /// Replaces the dataset compared against, refreshing statuses and results.
+    fn set_compare(&mut self, compare: Option<compare::CompareDataset>) {
+        self.match_options.compare = compare
+            .as_ref()
+            .map(|other| compare::compare_statuses(&self.indexed_items, other))
+            .unwrap_or_default();
+        self.compare = compare;
+        if self.compare.is_none() && self.pinned_item.is_none() {
+            self.show_diff = false;
+        }
+        self.cached_details_item_idx = None;
+        self.update_filter();
+    }
+
+    /// Switches which dataset(s) the filter runs against; only useful while
+    /// a compare dataset is loaded.
+    fn cycle_search_target(&mut self) {
+        if self.compare.is_none() {
+            return;
+        }
+        self.search_target = self.search_target.next();
+        self.update_filter();
+    }
+
+    /// How the selected item compares to the compare dataset, if one is loaded.
+    pub fn selected_compare_status(&self) -> Option<compare::CompareStatus> {
+        let sel = self.list_state.selected()?;
+        let idx = *self.filtered_indices.get(sel)?;
+        self.match_options.compare.get(idx).copied().flatten()
+    }
+
+    /// Label for the pinned item: its id, or its abstract name for templates.
+    pub fn pinned_label(&self) -> Option<&str> {
+        let item = self.indexed_items.get(self.pinned_item?)?;
+        Some(item.id_or_abstract()).filter(|label| !label.is_empty())
+    }
+
+    /// Marks or unmarks the selected item for export.
+    fn toggle_mark(&mut self) {
+        let Some(idx) = self
+            .list_state
+            .selected()
+            .and_then(|sel| self.filtered_indices.get(sel).copied())
+        else {
+            return;
+        };
+        if !self.marked.remove(&idx) {
+            self.marked.insert(idx);
+        }
+    }
+
+    /// Queues writing the marked items to the working directory, or says how to
+    /// mark some.
+    fn export_marked(&mut self) {
+        if self.marked.is_empty() {
+            self.status_message = Some("Nothing marked: press Space on items to mark them".into());
+            return;
+        }
+        let dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+        self.pending_action = Some(AppAction::ExportMarked(dir));
+    }
+
+    /// Writes the marked items' JSON as an array, in list order, to a file in
+    /// `dir` that doesn't exist yet, and reports its full path.
+    fn export_marked_to(&mut self, dir: &std::path::Path) {
+        let path = unused_export_path(dir);
+        let values: Vec<&serde_json::Value> = self
+            .marked
+            .iter()
+            .map(|&idx| &self.indexed_items[idx].value)
+            .collect();
+        let result = serde_json::to_vec_pretty(&values)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(data::write_atomically(&path, &json)?));
+        self.status_message = Some(match result {
+            Ok(()) => format!("Exported {} items to {}", values.len(), path.display()),
+            Err(err) => format!("Export failed: {:#}", err),
+        });
+    }
+
+    /// Pins the selected item for diffing, or unpins it if it is already pinned.
+    fn toggle_pin(&mut self) {
+        let selected = self
+            .list_state
+            .selected()
+            .and_then(|sel| self.filtered_indices.get(sel).copied());
+        self.pinned_item = if self.pinned_item == selected {
+            None
+        } else {
+            selected
+        };
+        if self.pinned_item.is_none() {
+            self.show_diff = false;
+        }
+        self.cached_details_item_idx = None;
+        self.refresh_details();
+    }
+
+    /// Switches the details pane between the selected item and its diff against
+    /// the pinned one, or its counterpart in the compare dataset.
+    fn toggle_diff(&mut self) {
+        if self.pinned_item.is_none() && self.compare.is_none() {
+            return;
+        }
+        self.show_diff = !self.show_diff;
+        self.cached_details_item_idx = None;
+        self.refresh_details();
+    }
+
+    /// Switches the details pane between pretty-printed and original source JSON.
+    fn toggle_raw_json(&mut self) {
+        self.show_raw_json = !self.show_raw_json;
+        self.show_resolved = false;
+        self.show_table = false;
+        // Same item, different text: drop the cache so refresh_details re-renders.
+        self.cached_details_item_idx = None;
+        self.refresh_details();
+    }
+
+    /// Switches the details pane between nested JSON and flat `path = value` lines.
+    fn toggle_table(&mut self) {
+        self.show_table = !self.show_table;
+        // Source text can't be flattened; the table shows the parsed value.
+        self.show_raw_json = false;
+        self.cached_details_item_idx = None;
+        self.refresh_details();
+    }
+
+    /// Switches the details pane between the item as written and with inheritance resolved.
+    fn toggle_resolved(&mut self) {
+        self.show_resolved = !self.show_resolved;
+        self.show_raw_json = false;
+        self.cached_details_item_idx = None;
+        self.refresh_details();
+    }
+
+    /// Switches the details pane between wrapped lines and horizontal scrolling.
+    fn toggle_details_wrap(&mut self) {
+        self.details_wrap = !self.details_wrap;
+        self.details_wrapped_width = 0;
+        let offset = self.details_scroll_state.offset();
+        self.details_scroll_state
+            .set_offset(ratatui::layout::Position::new(0, offset.y));
+    }
+
+    /// Returns span IDs of followable links in the details pane, in reading order.
+    fn details_link_ids(&self) -> Vec<usize> {
+        let mut ids: Vec<usize> = Vec::new();
+        // The diff view doesn't show the annotated lines links point into.
+        if self.details_diff.is_some() {
+            return ids;
+        }
+        for span in self.details_annotated.iter().flatten() {
+            if link_path(span, &self.excluded_fields).is_some()
+                && let Some(id) = span.span_id
+                && ids.last() != Some(&id)
+            {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    /// Moves the keyboard-focused link forward or backward.
+    /// Returns false once cycling runs past either end, so focus can leave the pane.
+    fn cycle_focused_link(&mut self, forward: bool) -> bool {
+        let ids = self.details_link_ids();
+        let current = self
+            .focused_link_id
+            .and_then(|id| ids.iter().position(|&other| other == id));
+        let next = match (current, forward) {
+            (None, true) => Some(0),
+            (None, false) => ids.len().checked_sub(1),
+            (Some(pos), true) => Some(pos + 1).filter(|&p| p < ids.len()),
+            (Some(pos), false) => pos.checked_sub(1),
+        };
+        self.focused_link_id = next.and_then(|pos| ids.get(pos).copied());
+        if let Some(id) = self.focused_link_id {
+            self.scroll_details_to_span(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Adjusts the details scroll offset so the given span is on screen.
+    fn scroll_details_to_span(&mut self, span_id: usize) {
+        let Some(line_idx) = self
+            .details_wrapped_annotated
+            .iter()
+            .position(|line| line.iter().any(|s| s.span_id == Some(span_id)))
+        else {
+            return;
+        };
+        let line_idx = line_idx as u16;
+        let height = self.details_content_area.map(|a| a.height).unwrap_or(1);
+        let mut offset = self.details_scroll_state.offset();
+        if line_idx < offset.y {
+            offset.y = line_idx;
+        } else if line_idx >= offset.y + height {
+            offset.y = line_idx + 1 - height;
+        }
+        self.details_scroll_state.set_offset(offset);
+    }
+
+    /// Scrolls the details body so the lines under minimap row `row` sit mid-view.
+    fn scroll_details_to_minimap(&mut self, row: u16) {
+        let Some(area) = self.details_minimap_area else {
+            return;
+        };
+        let total = self.details_wrapped_annotated.len();
+        let visible = self.details_content_area.map_or(area.height, |a| a.height) as usize;
+        let line = ui::minimap_lines(row.saturating_sub(area.y), area.height, total).start;
+        let top = line
+            .saturating_sub(visible / 2)
+            .min(total.saturating_sub(visible));
+        let offset = self.details_scroll_state.offset();
+        self.details_scroll_state
+            .set_offset(ratatui::layout::Position::new(offset.x, top as u16));
+    }
+
+    /// Follows the keyboard-focused link, mirroring a click (or Ctrl-click when `jump_to_id`).
+    fn activate_focused_link(&mut self, jump_to_id: bool) {
+        if let Some(id) = self.focused_link_id {
+            self.follow_span_link(id, jump_to_id);
+        }
+    }
+
+    /// Adds the hovered value to the filter, as clicking it would.
+    fn append_hovered_link(&mut self) {
+        if let Some(id) = self.hovered_span_id {
+            self.follow_span_link(id, false);
+        }
+    }
+
+    /// Follows span `id` of the details pane if it is a link.
+    fn follow_span_link(&mut self, id: usize, jump_to_id: bool) {
+        let Some(path) = self
+            .details_annotated
+            .iter()
+            .flatten()
+            .find(|s| s.span_id == Some(id))
+            .and_then(|span| link_path(span, &self.excluded_fields))
+            .map(str::to_string)
+        else {
+            return;
+        };
+        follow_link(self, &path, id, jump_to_id);
+    }
+
     /// Clamps the current list selection to valid bounds.
     fn clamp_selection(&mut self) {
         let len = self.filtered_indices.len();
@@ -372,7 +1219,20 @@ impl AppState {
     }
 
     /// Moves selection by `direction` (+1 or -1) and refreshes details.
+    /// With `wrap_navigation`, moving past either end continues from the other.
     fn move_selection(&mut self, direction: i32) {
+        let len = self.filtered_indices.len();
+        if self.wrap_navigation && len > 0 {
+            let current = self.list_state.selected().unwrap_or(0).min(len - 1);
+            let next = if direction < 0 {
+                current.checked_sub(1).unwrap_or(len - 1)
+            } else {
+                (current + 1) % len
+            };
+            self.list_state.select(Some(next));
+            self.refresh_details();
+            return;
+        }
         if direction < 0 {
             self.list_state.select_previous();
         } else {
@@ -382,6 +1242,37 @@ impl AppState {
         self.refresh_details();
     }
 
+    /// Selects the first row of the next type group, or of the previous one when
+    /// `forward` is false. The list is sorted by type, so each type is one run of rows.
+    fn jump_type_group(&mut self, forward: bool) {
+        let Some(current) = self.list_state.selected() else {
+            return;
+        };
+        let item_type = |row: usize| {
+            self.filtered_indices
+                .get(row)
+                .map(|&idx| self.indexed_items[idx].item_type.as_str())
+        };
+        // First row of the run of same-typed rows that `row` is in.
+        let group_start = |row: usize| {
+            let row_type = item_type(row);
+            (0..row)
+                .rev()
+                .find(|&prev| item_type(prev) != row_type)
+                .map_or(0, |prev| prev + 1)
+        };
+        let target = if forward {
+            let current_type = item_type(current);
+            (current + 1..self.filtered_indices.len()).find(|&row| item_type(row) != current_type)
+        } else {
+            group_start(current).checked_sub(1).map(group_start)
+        };
+        if let Some(row) = target {
+            self.list_state.select(Some(row));
+            self.refresh_details();
+        }
+    }
+
     pub fn get_selected_item(&self) -> Option<&data::IndexedItem> {
         self.list_state
             .selected()
@@ -397,6 +1288,11 @@ impl AppState {
         self.details_scroll_state.scroll_down();
     }
 
+    /// How far PageUp/PageDown move in a view `visible` lines tall.
+    fn page_step(&self, visible: u16) -> u16 {
+        visible.saturating_sub(self.page_overlap).max(1)
+    }
+
     fn scroll_details_by_lines(&mut self, lines: u16, down: bool) {
         for _ in 0..lines {
             if down {
@@ -500,7 +1396,70 @@ impl AppState {
         self.filter_cursor = new_cursor;
     }
 
+    /// Offers completions for the term ending at the filter cursor, or hides the popup.
+    fn update_completions(&mut self) {
+        self.completion = self.find_completion();
+        self.completion_list_state
+            .select(self.completion.as_ref().map(|_| 0));
+    }
+
+    fn find_completion(&self) -> Option<Completion> {
+        let cursor = self
+            .filter_text
+            .char_indices()
+            .nth(self.filter_cursor)
+            .map_or(self.filter_text.len(), |(idx, _)| idx);
+        let token = matcher::completion_token(&self.filter_text, cursor)?;
+        // Complete what an aliased classifier really searches.
+        let mut terms = [matcher::SearchTerm {
+            classifier: token.classifier.clone(),
+            pattern: token.prefix.clone(),
+            exact: false,
+        }];
+        self.match_options.aliases.apply(&mut terms);
+        let [term] = terms;
+        let lookup = matcher::CompletionToken {
+            classifier: term.classifier,
+            ..token.clone()
+        };
+        let candidates: Vec<String> =
+            matcher::completion_candidates(&self.search_index, &lookup, COMPLETION_LIMIT)
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+        (!candidates.is_empty()).then_some(Completion { token, candidates })
+    }
+
+    /// Replaces the partial term with the selected completion and closes the popup.
+    fn accept_completion(&mut self) {
+        let Some(completion) = self.completion.take() else {
+            return;
+        };
+        let Some(candidate) = self
+            .completion_list_state
+            .selected()
+            .and_then(|idx| completion.candidates.get(idx))
+        else {
+            return;
+        };
+        let range = completion.token.range;
+        // The filter may have changed under a stale popup.
+        if self.filter_text.get(range.clone()) != Some(completion.token.prefix.as_str()) {
+            return;
+        }
+        let value = if candidate.contains(char::is_whitespace) {
+            quote_filter_value(candidate)
+        } else {
+            candidate.clone()
+        };
+        self.filter_text.replace_range(range.clone(), &value);
+        self.filter_cursor = self.filter_text[..range.start + value.len()]
+            .chars()
+            .count();
+    }
+
     fn focus_pane(&mut self, pane: FocusPane) {
+        self.completion = None;
         self.focused_pane = pane;
         self.input_mode = if pane == FocusPane::Filter {
             InputMode::Filtering
@@ -527,39 +1486,616 @@ impl AppState {
         self.focus_pane(prev);
     }
 
-    fn update_filter(&mut self) {
-        let new_filtered =
-            matcher::find_matches(&self.filter_text, &self.indexed_items, &self.search_index);
-        self.filtered_indices = new_filtered;
-        if self.filtered_indices.is_empty() {
-            self.list_state.select(None);
-        } else {
-            self.list_state.select(Some(0));
-        }
-        // Rebuild display cache whenever the filtered set changes.
-        self.rebuild_display_cache();
-        self.refresh_details();
+    /// Defers the matcher until typing pauses for `FILTER_DEBOUNCE`.
+    fn schedule_filter_update(&mut self) {
+        self.filter_pending_since = Some(Instant::now());
     }
 
-    /// Rebuilds cached_display from the current filtered_indices.
-    /// Called only when the filter result set changes — not on every frame.
-    fn rebuild_display_cache(&mut self) {
-        self.cached_display = self
-            .filtered_indices
-            .iter()
-            .map(|&idx| {
-                let item = &self.indexed_items[idx];
-                let display = ui::display_name_for_item(&item.value, &item.id, &item.item_type);
-                // Pre-format the type prefix once so render borrows it as &str.
-                let type_prefix = format!("{} ", item.item_type);
-                (display, type_prefix)
-            })
-            .collect();
+    /// Time left before a deferred filter update is due, if one is pending.
+    fn filter_debounce_remaining(&self) -> Option<Duration> {
+        self.filter_pending_since
+            .map(|since| FILTER_DEBOUNCE.saturating_sub(since.elapsed()))
     }
 
-    fn apply_new_dataset(
-        &mut self,
-        indexed_items: Vec<data::IndexedItem>,
+    /// Applies a deferred filter update immediately, if one is pending.
+    fn flush_pending_filter(&mut self) {
+        if self.filter_pending_since.is_some() {
+            self.update_filter();
+        }
+    }
+
+    /// Appends a term to the filter and applies it right away.
+    fn append_filter_term(&mut self, term: &str) {
+        let current = self.filter_text.trim();
+        if current.is_empty() {
+            self.filter_text = term.to_string();
+        } else {
+            self.filter_text = format!("{} {}", current, term);
+        }
+        self.filter_cursor = self.filter_text.chars().count();
+        self.update_filter();
+    }
+
+    /// Bookmarks or un-bookmarks the selected item.
+    fn toggle_bookmark(&mut self) {
+        let Some(id) = self
+            .get_selected_item()
+            .map(|item| item.id_or_abstract().to_string())
+            .filter(|id| !id.is_empty())
+        else {
+            return;
+        };
+        self.bookmarks.toggle(&id);
+    }
+
+    /// Whether a bookmarked id exists in the loaded dataset.
+    pub fn is_known_id(&self, id: &str) -> bool {
+        self.search_index.by_id.contains_key(&id.to_lowercase())
+    }
+
+    fn open_bookmarks(&mut self) {
+        self.bookmark_list_state
+            .select((!self.bookmarks.ids().is_empty()).then_some(0));
+        self.show_bookmarks = true;
+    }
+
+    /// Jumps to the selected bookmark and closes the overlay.
+    /// Bookmarks missing from this dataset are left selected instead.
+    fn apply_bookmark_selection(&mut self) {
+        let Some(id) = self
+            .bookmark_list_state
+            .selected()
+            .and_then(|idx| self.bookmarks.ids().get(idx).cloned())
+        else {
+            return;
+        };
+        if !self.is_known_id(&id) {
+            return;
+        }
+        self.show_bookmarks = false;
+        self.jump_to_id(&id);
+    }
+
+    fn remove_selected_bookmark(&mut self) {
+        let Some(idx) = self.bookmark_list_state.selected() else {
+            return;
+        };
+        if let Some(id) = self.bookmarks.ids().get(idx).cloned() {
+            self.bookmarks.toggle(&id);
+        }
+        let len = self.bookmarks.ids().len();
+        self.bookmark_list_state
+            .select((len > 0).then(|| idx.min(len - 1)));
+    }
+
+    fn open_recent(&mut self) {
+        self.recent_list_state
+            .select((!self.recent.ids().is_empty()).then_some(0));
+        self.show_recent = true;
+    }
+
+    /// Jumps to the selected recently viewed item and closes the overlay.
+    /// Ids missing from this dataset are left selected instead.
+    fn apply_recent_selection(&mut self) {
+        let Some(id) = self
+            .recent_list_state
+            .selected()
+            .and_then(|idx| self.recent.ids().get(idx).cloned())
+        else {
+            return;
+        };
+        if !self.is_known_id(&id) {
+            return;
+        }
+        self.show_recent = false;
+        self.jump_to_id(&id);
+    }
+
+    /// Opens the list of warnings from loading the source directory.
+    fn open_warnings(&mut self) {
+        self.warnings_list_state
+            .select((!self.source_warnings.is_empty()).then_some(0));
+        self.show_help = false;
+        self.show_about = false;
+        self.show_warnings = true;
+    }
+
+    /// Checks every `copy-from` against the loaded ids and opens the report.
+    fn open_lint_report(&mut self) {
+        self.lint_entries = inheritance::dangling_copy_from(&self.indexed_items, &self.id_set);
+        self.lint_list_state
+            .select((!self.lint_entries.is_empty()).then_some(0));
+        self.show_help = false;
+        self.show_lint = true;
+    }
+
+    /// Jumps to the object of the selected lint entry and closes the report.
+    fn apply_lint_selection(&mut self) {
+        let Some(idx) = self
+            .lint_list_state
+            .selected()
+            .and_then(|row| self.lint_entries.get(row))
+            .map(|entry| entry.idx)
+        else {
+            return;
+        };
+        self.show_lint = false;
+        let id = self.indexed_items[idx].id_or_abstract().to_string();
+        if !id.is_empty() {
+            self.jump_to_id(&id);
+        }
+        // Several objects can share an id; select the one that was reported.
+        self.select_item(idx);
+    }
+
+    /// Replaces the filter with an exact id lookup and shows the result.
+    fn jump_to_id(&mut self, id: &str) {
+        self.filter_text = format!("i:{}", quote_filter_value(id));
+        self.filter_cursor = self.filter_text.chars().count();
+        self.update_filter();
+        // The lookup ignores case; prefer the object spelled exactly like `id`.
+        if let Some(&idx) = self.id_to_index.get(id) {
+            self.select_item(idx);
+        }
+        self.focus_pane(FocusPane::Details);
+    }
+
+    /// Selects item `idx` of indexed_items if the list shows it.
+    fn select_item(&mut self, idx: usize) {
+        if let Some(row) = self.filtered_indices.iter().position(|&i| i == idx)
+            && self.list_state.selected() != Some(row)
+        {
+            self.list_state.select(Some(row));
+            self.refresh_details();
+        }
+    }
+
+    /// Opens the type picker with the types from the `by_type` index.
+    fn open_type_picker(&mut self) {
+        // Index keys are lowercased; show the type as it is spelled in the data.
+        let mut entries: Vec<(String, usize)> = self
+            .search_index
+            .by_type
+            .values()
+            .filter_map(|indices| {
+                let first = *indices.iter().next()?;
+                Some((self.indexed_items[first].item_type.clone(), indices.len()))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        self.type_entries = entries;
+        self.type_picker_query.clear();
+        self.type_list_state
+            .select((!self.type_entries.is_empty()).then_some(0));
+        self.show_type_picker = true;
+    }
+
+    /// Type picker entries matching the typed query.
+    pub fn visible_type_entries(&self) -> Vec<&(String, usize)> {
+        let query = self.type_picker_query.to_lowercase();
+        self.type_entries
+            .iter()
+            .filter(|(item_type, _)| item_type.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    /// Narrows the type picker list and resets its selection.
+    fn edit_type_picker_query(&mut self, edit: impl FnOnce(&mut String)) {
+        edit(&mut self.type_picker_query);
+        let has_entries = !self.visible_type_entries().is_empty();
+        self.type_list_state.select(has_entries.then_some(0));
+    }
+
+    /// Adds `t:'<type>'` for the selected picker entry and closes the picker.
+    fn apply_type_picker_selection(&mut self) {
+        let Some(item_type) = self
+            .type_list_state
+            .selected()
+            .and_then(|idx| self.visible_type_entries().get(idx).map(|(t, _)| t.clone()))
+        else {
+            return;
+        };
+        self.show_type_picker = false;
+        self.append_filter_term(&format!("t:'{}'", item_type));
+        self.focus_pane(FocusPane::List);
+    }
+
+    /// Starts list type-ahead: typed characters select the next row whose name starts with them.
+    fn start_type_ahead(&mut self) {
+        self.focus_pane(FocusPane::List);
+        self.type_ahead = Some(TypeAhead {
+            query: String::new(),
+            matched: true,
+            last_key: Instant::now(),
+        });
+    }
+
+    /// Time left before type-ahead ends on its own, if it is active.
+    fn type_ahead_remaining(&self) -> Option<Duration> {
+        self.type_ahead
+            .as_ref()
+            .map(|type_ahead| TYPE_AHEAD_TIMEOUT.saturating_sub(type_ahead.last_key.elapsed()))
+    }
+
+    /// Edits the type-ahead prefix and selects the first matching row, starting at
+    /// the current one so a longer prefix keeps the selection where it still matches.
+    fn edit_type_ahead(&mut self, edit: impl FnOnce(&mut String)) {
+        let Some(type_ahead) = &mut self.type_ahead else {
+            return;
+        };
+        edit(&mut type_ahead.query);
+        type_ahead.last_key = Instant::now();
+        self.type_ahead_select(0);
+    }
+
+    /// Moves to the next row matching the type-ahead prefix, after the current one.
+    fn type_ahead_next(&mut self) {
+        let Some(type_ahead) = &mut self.type_ahead else {
+            return;
+        };
+        type_ahead.last_key = Instant::now();
+        self.type_ahead_select(1);
+    }
+
+    /// Selects the first row at or after `selected + skip` (wrapping) whose name
+    /// starts with the type-ahead prefix, ignoring case.
+    fn type_ahead_select(&mut self, skip: usize) {
+        let Some(type_ahead) = &mut self.type_ahead else {
+            return;
+        };
+        let len = self.cached_display.len();
+        if type_ahead.query.is_empty() || len == 0 {
+            type_ahead.matched = true;
+            return;
+        }
+        let prefix = type_ahead.query.to_lowercase();
+        let start = self.list_state.selected().unwrap_or(0) + skip;
+        let found = (0..len).map(|offset| (start + offset) % len).find(|&row| {
+            self.cached_display[row]
+                .display
+                .to_lowercase()
+                .starts_with(&prefix)
+        });
+        type_ahead.matched = found.is_some();
+        if let Some(row) = found
+            && self.list_state.selected() != Some(row)
+        {
+            self.list_state.select(Some(row));
+            self.refresh_details();
+        }
+    }
+
+    fn open_history_search(&mut self) {
+        self.history_search_query.clear();
+        self.history_index = None;
+        self.show_history_search = true;
+        let has_entries = !self.visible_history_matches().is_empty();
+        self.history_list_state.select(has_entries.then_some(0));
+    }
+
+    /// History entries matching the typed query, best first.
+    pub fn visible_history_matches(&self) -> Vec<&str> {
+        rank_history(&self.filter_history, &self.history_search_query)
+    }
+
+    /// Narrows the history search list and resets its selection.
+    fn edit_history_search_query(&mut self, edit: impl FnOnce(&mut String)) {
+        edit(&mut self.history_search_query);
+        let has_entries = !self.visible_history_matches().is_empty();
+        self.history_list_state.select(has_entries.then_some(0));
+    }
+
+    /// Replaces the filter with the selected history entry and closes the overlay.
+    fn apply_history_search_selection(&mut self) {
+        let Some(entry) = self.history_list_state.selected().and_then(|idx| {
+            self.visible_history_matches()
+                .get(idx)
+                .map(|e| e.to_string())
+        }) else {
+            return;
+        };
+        self.show_history_search = false;
+        self.filter_text = entry;
+        self.filter_move_to_end();
+        self.update_filter();
+        self.focus_pane(FocusPane::List);
+    }
+
+    /// Shows or hides abstract templates in the list.
+    fn toggle_hide_abstracts(&mut self) {
+        self.hide_abstracts = !self.hide_abstracts;
+        self.update_filter();
+    }
+
+    /// Returns the online page URL of the selected item, if it has an id.
+    /// Moves the moving end of the details selection by `lines` and `columns`,
+    /// starting a selection at the top of the visible text if there is none.
+    fn extend_details_selection(&mut self, lines: isize, columns: isize) {
+        let Some(last_line) = self.details_wrapped_annotated.len().checked_sub(1) else {
+            return;
+        };
+        let offset = self.details_scroll_state.offset();
+        let selection = self.details_selection.get_or_insert_with(|| {
+            let top = ui::TextPos {
+                line: (offset.y as usize).min(last_line),
+                column: 0,
+            };
+            ui::DetailsSelection {
+                anchor: top,
+                cursor: top,
+            }
+        });
+        let line = selection
+            .cursor
+            .line
+            .saturating_add_signed(lines)
+            .min(last_line);
+        let line_width: usize = self.details_wrapped_annotated[line]
+            .iter()
+            .map(|span| span.span.width())
+            .sum();
+        selection.cursor = ui::TextPos {
+            line,
+            column: selection
+                .cursor
+                .column
+                .saturating_add_signed(columns)
+                .min(line_width.saturating_sub(1)),
+        };
+
+        // Keep the moving end on screen.
+        let height = self.details_content_area.map_or(0, |area| area.height) as usize;
+        let top = offset.y as usize;
+        let new_top = if line < top {
+            line
+        } else if height > 0 && line >= top + height {
+            line + 1 - height
+        } else {
+            top
+        };
+        if new_top != top {
+            self.details_scroll_state
+                .set_offset(ratatui::layout::Position::new(offset.x, new_top as u16));
+        }
+    }
+
+    /// Queues copying the details selection to the clipboard.
+    fn copy_details_selection(&mut self) {
+        let Some(selection) = &self.details_selection else {
+            return;
+        };
+        let text = ui::selection_text(
+            &self.details_wrapped_annotated,
+            &self.details_wrap_continued,
+            selection,
+        );
+        self.status_message = Some(format!("Copied {} characters", text.chars().count()));
+        self.pending_action = Some(AppAction::CopyToClipboard(text));
+    }
+
+    /// Counts the listed items sharing link `span_id`'s value at `path`, reusing
+    /// the count of a path and value hovered since the filter last ran.
+    fn update_hovered_value_count(&mut self, path: &str, span_id: usize) {
+        let value = link_value(&self.details_annotated, span_id);
+        let (items, filtered) = (&self.indexed_items, &self.filtered_indices);
+        let count = *self
+            .value_counts
+            .entry((path.to_string(), value.clone()))
+            .or_insert_with(|| count_field_value(items, filtered, path, &value));
+        self.hovered_value_count = Some(ValueCount {
+            path: path.to_string(),
+            value,
+            count,
+        });
+    }
+
+    /// Previews the object link `span_id` names, if its value is a known id other
+    /// than the shown item's. Hovering the same id again reuses the last preview.
+    /// Finding the object is one `id_to_index` lookup, cheap enough to run on
+    /// every hover change without throttling.
+    fn update_hovered_preview(&mut self, span_id: usize, anchor: (u16, u16)) {
+        let id = link_value(&self.details_annotated, span_id);
+        if let Some(preview) = self.hovered_preview.as_mut()
+            && preview.id == id
+        {
+            preview.anchor = anchor;
+            return;
+        }
+        self.hovered_preview = None;
+        let Some(&idx) = self.id_to_index.get(&id) else {
+            return;
+        };
+        if self.cached_details_item_idx == Some(idx) {
+            return;
+        }
+        let item = &self.indexed_items[idx];
+        self.hovered_preview = Some(IdPreview {
+            title: ui::display_name_for_item(&item.value, &item.id, &item.item_type),
+            fields: id_preview_fields(item),
+            anchor,
+            id,
+        });
+    }
+
+    /// Queues copying the dot-path of the hovered details value, e.g.
+    /// `pocket_data.ammo_restriction`, ready to paste as a classifier.
+    fn copy_hovered_path(&mut self) {
+        let Some(path) = self.hovered_key_path.clone() else {
+            return;
+        };
+        self.status_message = Some(format!("Copied path {}", path));
+        self.pending_action = Some(AppAction::CopyToClipboard(path));
+    }
+
+    /// Queues opening the selected item's source file in an editor, or says
+    /// why there is none.
+    fn open_selected_in_editor(&mut self) {
+        let Some(item) = self.get_selected_item() else {
+            return;
+        };
+        match &item.source {
+            Some(location) => self.pending_action = Some(AppAction::OpenInEditor(location.clone())),
+            None => {
+                self.status_message = Some("No source file: not loaded with --source".to_string())
+            }
+        }
+    }
+
+    fn selected_item_url(&self) -> Option<String> {
+        let item = self.get_selected_item()?;
+        data::item_url(
+            &self.download_source.item_url,
+            &self.game_version_key,
+            &item.item_type,
+            &item.id,
+        )
+    }
+
+    /// Returns the (id, type) of the selected item, if it has an id.
+    fn selected_item_key(&self) -> Option<(String, String)> {
+        self.get_selected_item()
+            .filter(|item| !item.id.is_empty())
+            .map(|item| (item.id.clone(), item.item_type.clone()))
+    }
+
+    fn update_filter(&mut self) {
+        let keep = self.selected_item_key();
+        self.update_filter_keeping(keep);
+    }
+
+    /// Re-runs the filter, reselecting the item identified by `keep` if it still matches.
+    fn update_filter_keeping(&mut self, keep: Option<(String, String)>) {
+        self.filter_pending_since = None;
+        let outcome = matcher::find_matches_with_options(
+            &self.filter_text,
+            &self.indexed_items,
+            &self.search_index,
+            &self.match_options,
+        );
+        self.filtered_indices = self.apply_search_target(outcome.indices);
+        self.hovered_value_count = None;
+        self.value_counts.clear();
+        if self.hide_abstracts {
+            let items = &self.indexed_items;
+            self.filtered_indices
+                .retain(|&idx| !items[idx].is_abstract());
+        }
+        self.filter_slow_path = outcome.slow_path;
+        self.filter_slow_path_miss = outcome.slow_path_miss;
+        self.filter_unknown_classifiers = outcome.unknown_classifiers;
+        self.limited_total = outcome.limited_total;
+        self.match_count = None;
+        if outcome.count_only {
+            if outcome.error.is_none() {
+                self.match_count = Some(self.filtered_indices.len());
+            }
+            self.filtered_indices.clear();
+        }
+        self.filter_error = outcome.error;
+        let kept_pos = keep.and_then(|(id, item_type)| {
+            self.filtered_indices.iter().position(|&idx| {
+                let item = &self.indexed_items[idx];
+                item.id == id && item.item_type == item_type
+            })
+        });
+        // Row of the selection within the viewport, so a kept item stays where it was.
+        let row = self
+            .list_state
+            .selected()
+            .map_or(0, |sel| sel.saturating_sub(self.list_state.offset()));
+        if self.filtered_indices.is_empty() {
+            self.list_state.select(None);
+        } else if let Some(pos) = kept_pos {
+            self.list_state.select(Some(pos));
+            *self.list_state.offset_mut() = pos.saturating_sub(row);
+        } else {
+            self.list_state.select(Some(0));
+            *self.list_state.offset_mut() = 0;
+        }
+        // Rebuild display cache whenever the filtered set changes.
+        self.rebuild_display_cache();
+        self.refresh_details();
+    }
+
+    /// Narrows the matches of the filter in this dataset to the ones wanted by
+    /// `search_target`, evaluating the filter against the compare dataset too.
+    fn apply_search_target(&self, indices: Vec<usize>) -> Vec<usize> {
+        let Some(other) = &self.compare else {
+            return indices;
+        };
+        if self.search_target == compare::SearchTarget::This || self.filter_text.trim().is_empty() {
+            return indices;
+        }
+        let other_options = matcher::MatchOptions {
+            aliases: self.match_options.aliases.clone(),
+            ..Default::default()
+        };
+        let other_matches = matcher::find_matches_with_options(
+            &self.filter_text,
+            &other.items,
+            &other.index,
+            &other_options,
+        );
+        let mut candidates = match self.search_target {
+            compare::SearchTarget::Other => (0..self.indexed_items.len()).collect(),
+            _ => indices,
+        };
+        compare::retain_with_matching_counterpart(
+            &mut candidates,
+            &self.indexed_items,
+            other,
+            &other_matches.indices,
+        );
+        candidates
+    }
+
+    /// Rebuilds cached_display from the current filtered_indices.
+    /// Called only when the filter result set changes — not on every frame.
+    fn rebuild_display_cache(&mut self) {
+        let columns = if self.show_columns {
+            self.list_columns.as_slice()
+        } else {
+            &[]
+        };
+        let terms = matcher::parse_query(&self.filter_text);
+        self.cached_display = self
+            .filtered_indices
+            .iter()
+            .map(|&idx| {
+                let item = &self.indexed_items[idx];
+                let display = ui::display_name_for_item(&item.value, &item.id, &item.item_type);
+                let name_matches = ui::name_match_ranges(&display, &terms);
+                // Pre-format the type prefix once so render borrows it as &str.
+                let type_prefix = format!("{} ", item.item_type);
+                ui::ListRow {
+                    display,
+                    type_prefix,
+                    is_abstract: item.is_abstract(),
+                    warned: self.match_options.warned.contains(&idx),
+                    name_matches,
+                    columns: columns
+                        .iter()
+                        .map(|path| ui::column_value(&item.value, path))
+                        .collect(),
+                }
+            })
+            .collect();
+        self.cached_column_widths = if self.show_columns {
+            ui::column_widths(&self.cached_display, &self.list_columns)
+        } else {
+            Vec::new()
+        };
+    }
+
+    /// Switches the list between plain names and the columns view.
+    fn toggle_columns(&mut self) {
+        self.show_columns = !self.show_columns;
+        self.rebuild_display_cache();
+    }
+
+    fn apply_new_dataset(
+        &mut self,
+        indexed_items: Vec<data::IndexedItem>,
         search_index: search_index::SearchIndex,
         total_items: usize,
         index_time_ms: f64,
@@ -568,16 +2104,52 @@ impl AppState {
     ) {
         let filter_text = self.filter_text.clone();
         let filter_cursor = self.filter_cursor.min(filter_text.chars().count());
+        let keep = self.selected_item_key();
+        // The load may have downloaded into the cache; measure it again on demand.
+        self.cache_size = None;
+
+        let marked_keys: foldhash::HashSet<(&str, &str)> = self
+            .marked
+            .iter()
+            .map(|&idx| {
+                let item = &self.indexed_items[idx];
+                (item.item_type.as_str(), item.id_or_abstract())
+            })
+            .collect();
+        // Marks follow their objects into the new dataset.
+        let marked = indexed_items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| {
+                marked_keys.contains(&(item.item_type.as_str(), item.id_or_abstract()))
+            })
+            .map(|(idx, _)| idx)
+            .collect();
 
         let id_set = indexed_items
             .iter()
             .filter(|item| !item.id.is_empty())
             .map(|item| item.id.clone())
             .collect();
-
+        let id_to_index = dataset::index_by_id(&indexed_items);
+
+        self.match_options.warned =
+            data::items_with_warnings(&indexed_items, &self.source_warnings);
+        self.match_options.compare = self
+            .compare
+            .as_ref()
+            .map(|other| compare::compare_statuses(&indexed_items, other))
+            .unwrap_or_default();
         self.indexed_items = indexed_items;
         self.search_index = search_index;
+        self.marked = marked;
+        // Item indices don't carry over to the new dataset.
+        self.pinned_item = None;
+        self.show_diff = false;
+        self.show_lint = false;
+        self.lint_entries.clear();
         self.id_set = id_set;
+        self.id_to_index = id_to_index;
         self.total_items = total_items;
         // New dataset means all item indices are stale — force a re-render.
         self.cached_details_item_idx = None;
@@ -586,11 +2158,12 @@ impl AppState {
         self.game_version_key = game_version_key;
         self.filter_text = filter_text;
         self.filter_cursor = filter_cursor;
-        self.update_filter();
+        self.update_filter_keeping(keep);
     }
 
     fn start_progress(&mut self, title: impl Into<String>, stages: &[&str]) {
         self.show_progress = true;
+        self.progress_cancel_requested = false;
         self.progress_title = title.into();
         self.progress_stages = stages
             .iter()
@@ -598,53 +2171,343 @@ impl AppState {
                 label: (*label).to_string(),
                 ratio: 0.0,
                 done: false,
+                indeterminate: false,
+                detail: None,
             })
             .collect();
     }
 
-    fn update_stage(&mut self, label: &str, ratio: f64) {
+    /// Version picker entries whose label contains the typed query.
+    pub fn visible_version_entries(&self) -> Vec<&VersionEntry> {
+        let query = self.version_picker_query.to_lowercase();
+        self.version_entries
+            .iter()
+            .filter(|entry| entry.label.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    /// Narrows the version picker list and resets its selection.
+    fn edit_version_picker_query(&mut self, edit: impl FnOnce(&mut String)) {
+        edit(&mut self.version_picker_query);
+        let has_entries = !self.visible_version_entries().is_empty();
+        self.version_list_state.select(has_entries.then_some(0));
+    }
+
+    /// Moves the version picker selection by `delta` rows, stopping at the first
+    /// and last entries.
+    fn move_version_selection(&mut self, delta: isize) {
+        let Some(last) = self.visible_version_entries().len().checked_sub(1) else {
+            return;
+        };
+        let current = self.version_list_state.selected().unwrap_or(0).min(last);
+        self.version_list_state
+            .select(Some(current.saturating_add_signed(delta).min(last)));
+    }
+
+    /// Moves `label` to `ratio`, or marks it indeterminate when `ratio` is `None`.
+    fn update_stage(&mut self, label: &str, ratio: Option<f64>) {
         if let Some(stage) = self
             .progress_stages
             .iter_mut()
             .find(|stage| stage.label == label)
         {
-            stage.ratio = ratio.clamp(0.0, 1.0);
+            stage.indeterminate = ratio.is_none();
+            stage.ratio = ratio.unwrap_or(0.0).clamp(0.0, 1.0);
             if stage.ratio >= 1.0 {
                 stage.done = true;
             }
         }
     }
 
-    fn finish_stage(&mut self, label: &str) {
-        self.update_stage(label, 1.0);
-    }
-
-    fn clear_progress(&mut self) {
-        self.show_progress = false;
-        self.progress_title.clear();
-        self.progress_stages.clear();
+    fn set_stage_detail(&mut self, label: &str, detail: String) {
+        if let Some(stage) = self
+            .progress_stages
+            .iter_mut()
+            .find(|stage| stage.label == label)
+        {
+            stage.detail = Some(detail);
+        }
     }
 
-    #[cfg(test)]
-    #[allow(dead_code)]
-    fn clear_filter(&mut self) {
-        self.filter_text.clear();
-        self.filter_cursor = 0;
-        self.update_filter();
+    fn open_help(&mut self) {
+        self.show_help = true;
+        self.confirm_clear_cache = false;
+        self.cache_clear_result = None;
+        // Walking the cache is slow; only a load can have grown it since.
+        if self.cache_size.is_none() {
+            self.cache_size = data::get_cache_dir().ok().map(|dir| data::dir_size(&dir));
+        }
     }
-}
-
-fn main() -> Result<()> {
-    let args = Args::parse();
-    let app_version = format!("v{}", env!("CARGO_PKG_VERSION"));
 
-    // Theme selection
-    let theme_name = args.theme.as_deref().unwrap_or("dracula");
+    /// Deletes the downloaded datasets once a second `x` confirms it; the loaded
+    /// one stays in memory. Refused while a version is loading into the cache.
+    fn clear_download_cache(&mut self) {
+        if self.background_load.is_some() {
+            self.confirm_clear_cache = false;
+            self.cache_clear_result = Some("can't clear while a version is loading".to_string());
+            return;
+        }
+        if !self.confirm_clear_cache {
+            self.confirm_clear_cache = true;
+            return;
+        }
+        self.confirm_clear_cache = false;
+        let dir = match data::get_cache_dir() {
+            Ok(dir) => dir,
+            Err(err) => {
+                self.cache_clear_result = Some(format!("could not clear: {:#}", err));
+                return;
+            }
+        };
+        let before = self.cache_size.unwrap_or_else(|| data::dir_size(&dir));
+        let result = data::clear_cache(&dir);
+        let after = data::dir_size(&dir);
+        self.cache_size = Some(after);
+        self.cache_clear_result = Some(match result {
+            Ok(()) => format!(
+                "cleared, {} freed",
+                format_bytes(before.saturating_sub(after))
+            ),
+            Err(err) => format!("could not clear: {:#}", err),
+        });
+    }
+
+    /// Drains pending input during a load, latching Esc as a cancel request.
+    fn poll_progress_cancel(&mut self) -> ControlFlow<()> {
+        while !self.progress_cancel_requested && matches!(event::poll(Duration::ZERO), Ok(true)) {
+            if let Ok(Event::Key(key)) = event::read()
+                && key.kind == KeyEventKind::Press
+                && key.code == KeyCode::Esc
+            {
+                self.progress_cancel_requested = true;
+            }
+        }
+        if self.progress_cancel_requested {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn finish_stage(&mut self, label: &str) {
+        self.update_stage(label, Some(1.0));
+    }
+
+    /// Shows loader progress; returns the outcome once the load is done.
+    fn apply_load_message(
+        &mut self,
+        message: loader::LoadMessage,
+    ) -> Option<Result<Box<loader::LoadedData>>> {
+        match message {
+            loader::LoadMessage::Progress {
+                stage,
+                ratio,
+                detail,
+            } => {
+                self.update_stage(stage, ratio);
+                if let Some(detail) = detail {
+                    self.set_stage_detail(stage, detail);
+                }
+                None
+            }
+            loader::LoadMessage::Done(result) => Some(result),
+        }
+    }
+
+    fn install_loaded_data(
+        &mut self,
+        loaded: loader::LoadedData,
+        version: &str,
+        file_path: Option<&str>,
+        data_source: String,
+    ) {
+        if let Some(warnings) = loaded.source_warnings {
+            self.source_warnings = warnings;
+        }
+        self.apply_new_dataset(
+            loaded.indexed_items,
+            loaded.search_index,
+            loaded.total_items,
+            loaded.index_time_ms,
+            resolve_game_version_label(version, file_path, &loaded.build),
+            version.to_string(),
+        );
+        self.data_source = data_source;
+        self.from_download = file_path.is_none() && self.source_dir.is_none();
+        if self.from_download {
+            self.remember_version(version);
+        }
+        if let Some(warning) = loaded.warning {
+            self.status_message = Some(warning);
+        }
+    }
+
+    /// Queues loading the current dataset again: the source directory, or the
+    /// downloaded version. Files and standard input have nothing to reload.
+    fn request_reload(&mut self) {
+        let version = if self.source_dir.is_some() {
+            "local".to_string()
+        } else if self.from_download {
+            self.game_version_key.clone()
+        } else {
+            return;
+        };
+        self.pending_action = Some(AppAction::Reload(version));
+    }
+
+    /// Records `version` as the one to reopen for the current download source.
+    fn remember_version(&mut self, version: &str) {
+        self.versions_by_source
+            .insert(self.download_source.name.clone(), version.to_string());
+    }
+
+    fn install_compare_data(&mut self, loaded: loader::LoadedData, version: &str) {
+        self.set_compare(Some(compare::CompareDataset {
+            version: version.to_string(),
+            items: loaded.indexed_items,
+            index: loaded.search_index,
+        }));
+    }
+
+    /// Takes in whatever the background load has sent. Once it is done the new
+    /// dataset replaces the current one, or the error modal opens.
+    fn pump_background_load(&mut self) {
+        loop {
+            let Some(message) = self
+                .background_load
+                .as_ref()
+                .and_then(|load| load.task.try_recv())
+            else {
+                return;
+            };
+            let Some(result) = self.apply_load_message(message) else {
+                continue;
+            };
+            let Some(load) = self.background_load.take() else {
+                return;
+            };
+            self.clear_progress();
+            match result {
+                Ok(loaded) if load.compare => self.install_compare_data(*loaded, &load.version),
+                Ok(loaded) => {
+                    self.install_loaded_data(*loaded, &load.version, None, load.data_source)
+                }
+                Err(err) if err.is::<data::Cancelled>() => {}
+                Err(err) => {
+                    self.load_failure = Some(LoadFailure {
+                        version: load.version,
+                        message: format!("{:#}", err),
+                        compare: load.compare,
+                    });
+                }
+            }
+            return;
+        }
+    }
+
+    /// Stops the background load; the current dataset stays.
+    fn cancel_background_load(&mut self) {
+        if self.background_load.take().is_some() {
+            self.clear_progress();
+        }
+    }
+
+    fn clear_progress(&mut self) {
+        self.show_progress = false;
+        self.progress_title.clear();
+        self.progress_stages.clear();
+    }
+
+    #[cfg(test)]
+    #[allow(dead_code)]
+    fn clear_filter(&mut self) {
+        self.filter_text.clear();
+        self.filter_cursor = 0;
+        self.update_filter();
+    }
+}
+
+fn main() -> Result<()> {
+    let mut args = Args::parse();
+    if args.file.is_none() {
+        args.file = args.input.take();
+    }
+    let app_version = format!("v{}", env!("CARGO_PKG_VERSION"));
+    let config_path = data::get_config_dir()?.join("config.toml");
+    let config = config::Config::load(&config_path)?;
+    args.data_source = match &args.source_url {
+        Some(url) => data::DataSource::with_base_url(url),
+        None => config.data_source.clone(),
+    };
+    if let Some(template) = args.item_url.take() {
+        args.data_source.item_url = template;
+    }
+
+    if args.stats {
+        return print_stats(&args, &mut io::stdout().lock());
+    }
+
+    if args.lint {
+        let dangling = print_lint(&args, &mut io::stdout().lock())?;
+        if dangling > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.json_only {
+        let query = args.query.as_deref().unwrap_or("");
+        let aliases = config.classifier_aliases();
+        let found = run_headless(&args, query, &aliases, &mut io::stdout().lock())?;
+        if found == 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let data_dir = data::get_data_dir()?;
+    let session_path = data_dir.join("session.toml");
+    let last_session = if args.no_restore {
+        session::Session::default()
+    } else {
+        session::Session::load(&session_path)
+    };
+    // Without --game, switching to another download source reopens the version
+    // last loaded from it; a plain launch keeps the default.
+    if args.game.is_none() {
+        args.game = last_session
+            .version_after_switch_to(&args.data_source.name)
+            .map(str::to_string);
+    }
+
+    // Theme selection: an explicit flag wins, then the last session's (if still valid).
+    let theme_name = match args.theme.as_deref() {
+        Some(name) => name,
+        None => last_session
+            .theme
+            .as_deref()
+            .filter(|name| theme::Theme::from_str(name).is_ok())
+            .unwrap_or("dracula"),
+    };
     let theme_enum = theme::Theme::from_str(theme_name).map_err(anyhow::Error::msg)?;
-    let theme = theme_enum.config();
+    // NO_COLOR (https://no-color.org) yields to an explicit --theme. The restored
+    // theme name is kept so the session doesn't remember monochrome.
+    let no_color = args.no_color
+        || (args.theme.is_none() && std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()));
+    let theme = if no_color {
+        theme::Theme::Monochrome.config()
+    } else {
+        theme_enum.config()
+    };
 
     if args.game_versions {
-        let builds = data::fetch_builds(args.force)?;
+        if args.offline {
+            for version in data::list_cached_versions(&args.data_source.cache_dir()?) {
+                println!("{} (cached)", version);
+            }
+            return Ok(());
+        }
+        let builds = data::fetch_builds(&args.data_source, args.force)?;
         for build in builds {
             let type_ = if build.prerelease {
                 "Nightly"
@@ -657,14 +2520,17 @@ fn main() -> Result<()> {
     }
 
     let cache_dir = data::get_cache_dir()?;
-    let data_dir = data::get_data_dir()?;
     let history_path = data_dir.join("history.txt");
+    let bookmarks_path = data_dir.join("bookmarks.txt");
 
     if args.config {
         println!("App Paths:");
         println!("  Cache:   {}", cache_dir.display());
         println!("  Data:    {}", data_dir.display());
         println!("  History: {}", history_path.display());
+        println!("  Bookmarks: {}", bookmarks_path.display());
+        println!("  Session: {}", session_path.display());
+        println!("  Config:  {}", config_path.display());
         return Ok(());
     }
 
@@ -678,6 +2544,25 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    if args.cache_info {
+        let usage = data::cache_usage(&cache_dir);
+        println!("Cache: {}", cache_dir.display());
+        let width = usage.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, size) in &usage {
+            println!("  {:<width$}  {:>10}", name, format_bytes(*size));
+        }
+        let total: u64 = usage.iter().map(|(_, size)| size).sum();
+        println!("Total: {}", format_bytes(total));
+        return Ok(());
+    }
+
+    if args.clear_cache {
+        let total = data::dir_size(&cache_dir);
+        data::clear_cache(&cache_dir)?;
+        println!("Cache cleared ({} freed).", format_bytes(total));
+        return Ok(());
+    }
+
     if let Some(source_dir) = &args.source {
         let path = std::path::Path::new(source_dir);
         if !path.exists() {
@@ -688,6 +2573,13 @@ fn main() -> Result<()> {
         }
     }
 
+    // Stdin has to be drained before raw mode takes over the terminal.
+    let stdin_root = if args.file.as_deref() == Some(data::STDIN_PATH) {
+        Some(data::read_root(io::stdin().lock())?)
+    } else {
+        None
+    };
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -707,7 +2599,7 @@ fn main() -> Result<()> {
         if args.source.is_some() {
             "local".to_string()
         } else {
-            args.game.clone()
+            args.game().to_string()
         },
         app_version,
         args.force,
@@ -716,9 +2608,55 @@ fn main() -> Result<()> {
         history_path,
         args.source.clone(),
     );
+    app.download_source.clone_from(&args.data_source);
+    app.offline = args.offline;
+    app.bookmarks = bookmarks::Bookmarks::load(bookmarks_path);
+    app.recent = recent::RecentItems::from_ids(last_session.recent.iter().cloned());
+    app.versions_by_source.clone_from(&last_session.versions);
+    app.match_options.aliases = config.classifier_aliases();
+    app.list_columns.clone_from(&config.columns);
+    app.header_fields.clone_from(&config.header_fields);
+    app.group_digits = config.group_digits;
+    app.prose_strings = config.prose_strings;
+    app.indent_width = config.indent_width.clamp(1, 4);
+    app.zebra_stripes = config.zebra_stripes;
+    app.excluded_fields.clone_from(&config.excluded_fields);
+    app.auto_filter = config.auto_filter;
+    app.wrap_navigation = config.wrap_navigation;
+    app.scroll_lines = config.scroll_lines.max(1);
+    app.page_overlap = config.page_overlap;
+    // An explicit --query wins over the restored filter.
+    let initial_filter = args.query.as_deref().unwrap_or(&last_session.filter);
+    if !initial_filter.is_empty() {
+        app.filter_text = initial_filter.to_string();
+        app.filter_move_to_end();
+    }
 
     let res = (|| -> Result<()> {
-        load_initial_data(&mut terminal, &mut app, &args)?;
+        match load_initial_data(&mut terminal, &mut app, &args, stdin_root) {
+            Ok(()) => {}
+            // Cancelling the first load leaves nothing to browse, so just quit.
+            Err(err) if err.is::<data::Cancelled>() => return Ok(()),
+            // A failed download can be retried from the error modal.
+            Err(err) if args.file.is_none() && args.source.is_none() => {
+                app.clear_progress();
+                app.load_failure = Some(LoadFailure {
+                    version: args.game().to_string(),
+                    message: format!("{:#}", err),
+                    compare: false,
+                });
+            }
+            Err(err) => return Err(err),
+        }
+        if app.load_failure.is_none()
+            && let Some(version) = &args.compare
+        {
+            handle_action(
+                &mut terminal,
+                &mut app,
+                AppAction::LoadCompare(version.clone()),
+            )?;
+        }
         run_app(&mut terminal, &mut app)
     })();
 
@@ -731,9 +2669,206 @@ fn main() -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
+    if app.history_unsaved_since.is_some() {
+        app.save_history();
+    }
+    if !args.no_restore {
+        let session = session::Session {
+            filter: app.filter_text.clone(),
+            theme: Some(theme_name.to_string()),
+            recent: app.recent.ids().to_vec(),
+            versions: app.versions_by_source.clone(),
+            source: Some(app.download_source.name.clone()),
+        };
+        // Losing the session is not worth failing the exit for.
+        let _ = session.save(&session_path);
+    }
+
     res
 }
 
+/// Loads and indexes the dataset selected by `args` without a terminal.
+/// Source warnings are printed to stderr and returned.
+fn load_headless_items(args: &Args) -> Result<(Vec<data::IndexedItem>, Vec<data::SourceWarning>)> {
+    let mut warnings = Vec::new();
+    let root = if let Some(source_dir) = &args.source {
+        let root = data::load_from_source(source_dir, &mut warnings)?;
+        for warning in &warnings {
+            eprintln!("{}", warning);
+        }
+        root
+    } else if let Some(file) = &args.file {
+        data::load_root(file)?
+    } else {
+        load_version_root(args, args.game())?
+    };
+    Ok((index_root_items(root)?, warnings))
+}
+
+/// Loads `version` from the download cache, fetching it first unless offline.
+fn load_version_root(args: &Args, version: &str) -> Result<data::Root> {
+    if args.offline {
+        let path = data::cached_game_data_path(&args.data_source, version)?;
+        return data::load_root(&path.to_string_lossy());
+    }
+    let (root, warning) =
+        data::load_game_data_with_progress(&args.data_source, version, args.force, |_| {
+            ControlFlow::Continue(())
+        })?;
+    if let Some(warning) = warning {
+        eprintln!("{}", warning);
+    }
+    Ok(root)
+}
+
+fn index_root_items(root: data::Root) -> Result<Vec<data::IndexedItem>> {
+    let mut sources = root.sources.into_iter();
+    let mut indexed_items = root
+        .data
+        .into_iter()
+        .map(|raw| {
+            let mut item = data::index_raw_item(raw)?;
+            item.source = sources.next();
+            Ok(item)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    data::sort_indexed_items(&mut indexed_items);
+    Ok(indexed_items)
+}
+
+/// Loads and indexes the `--compare` dataset, if one was asked for.
+fn load_compare_dataset(args: &Args) -> Result<Option<compare::CompareDataset>> {
+    let Some(version) = &args.compare else {
+        return Ok(None);
+    };
+    eprintln!("Loading {} for comparison...", version);
+    let items = index_root_items(load_version_root(args, version)?)?;
+    let index = search_index::SearchIndex::build(&items);
+    Ok(Some(compare::CompareDataset {
+        version: version.clone(),
+        items,
+        index,
+    }))
+}
+
+/// Writes the object count, estimated memory use and index sizes of the dataset.
+fn print_stats(args: &Args, out: &mut impl io::Write) -> Result<()> {
+    let (indexed_items, _) = load_headless_items(args)?;
+    let search_index = search_index::SearchIndex::build(&indexed_items);
+    write_stats(&indexed_items, &search_index.stats(), out)
+}
+
+/// Writes one line per object whose `copy-from` names a missing parent.
+/// Returns the number of such objects.
+fn print_lint(args: &Args, out: &mut impl io::Write) -> Result<usize> {
+    let (indexed_items, _) = load_headless_items(args)?;
+    let ids = indexed_items
+        .iter()
+        .filter(|item| !item.id.is_empty())
+        .map(|item| item.id.clone())
+        .collect();
+    let dangling = inheritance::dangling_copy_from(&indexed_items, &ids);
+    for entry in &dangling {
+        writeln!(
+            out,
+            "{}",
+            lint_line(&indexed_items[entry.idx], &entry.parent)
+        )?;
+    }
+    Ok(dangling.len())
+}
+
+/// `type id: copy-from 'parent' not found`, plus the source location when known.
+fn lint_line(item: &data::IndexedItem, parent: &str) -> String {
+    let mut line = format!(
+        "{} {}: copy-from '{}' not found",
+        item.item_type,
+        item.id_or_abstract(),
+        parent
+    );
+    if let Some(source) = &item.source {
+        line.push_str(&format!(" ({}:{})", source.path.display(), source.line));
+    }
+    line
+}
+
+fn write_stats(
+    items: &[data::IndexedItem],
+    stats: &search_index::IndexStats,
+    out: &mut impl io::Write,
+) -> Result<()> {
+    let value_bytes: usize = items
+        .iter()
+        .map(|item| data::estimate_value_bytes(&item.value))
+        .sum();
+    let raw_bytes: usize = items
+        .iter()
+        .filter_map(|item| item.raw.as_deref())
+        .map(str::len)
+        .sum();
+    writeln!(out, "Objects:      {}", items.len())?;
+    writeln!(out, "JSON values:  ~{}", format_bytes(value_bytes as u64))?;
+    writeln!(out, "Source text:  {}", format_bytes(raw_bytes as u64))?;
+    writeln!(out, "Index:")?;
+    let total = stats.total();
+    for (name, map) in [
+        ("ids", stats.by_id),
+        ("types", stats.by_type),
+        ("categories", stats.by_category),
+        ("words", stats.words),
+        ("keys", stats.by_key),
+        ("total", total),
+    ] {
+        writeln!(
+            out,
+            "  {:<11} {:>8} distinct, {:>9} postings",
+            name, map.keys, map.postings
+        )?;
+    }
+    Ok(())
+}
+
+/// Loads the dataset without a terminal and writes the items matching `query`
+/// to `out` as a JSON array (or just the number, for a `count:` query).
+/// Returns the number of matches.
+fn run_headless(
+    args: &Args,
+    query: &str,
+    aliases: &matcher::ClassifierAliases,
+    out: &mut impl io::Write,
+) -> Result<usize> {
+    let (indexed_items, warnings) = load_headless_items(args)?;
+    let search_index = search_index::SearchIndex::build(&indexed_items);
+
+    let compare = load_compare_dataset(args)?;
+    let options = matcher::MatchOptions {
+        aliases: aliases.clone(),
+        warned: data::items_with_warnings(&indexed_items, &warnings),
+        compare: compare
+            .as_ref()
+            .map(|other| compare::compare_statuses(&indexed_items, other))
+            .unwrap_or_default(),
+    };
+    let outcome =
+        matcher::find_matches_with_options(query, &indexed_items, &search_index, &options);
+    if let Some(error) = outcome.error {
+        anyhow::bail!("Invalid query: {}", error);
+    }
+    if outcome.count_only {
+        writeln!(out, "{}", outcome.indices.len())?;
+        return Ok(outcome.indices.len());
+    }
+
+    let values: Vec<&serde_json::Value> = outcome
+        .indices
+        .iter()
+        .map(|&idx| &indexed_items[idx].value)
+        .collect();
+    serde_json::to_writer_pretty(&mut *out, &values)?;
+    writeln!(out)?;
+    Ok(values.len())
+}
+
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut AppState,
@@ -747,6 +2882,34 @@ where
         if app.should_quit {
             break;
         }
+        app.retry_history_save();
+
+        // A version switch loads in the background; keep drawing its progress.
+        if app.background_load.is_some() {
+            app.pump_background_load();
+            if !event::poll(LOAD_POLL_INTERVAL)? {
+                terminal.draw(|f| ui::ui(f, app))?;
+                continue;
+            }
+        }
+
+        // Wake up once the debounce window elapses to apply the pending filter.
+        if let Some(timeout) = app.filter_debounce_remaining()
+            && !event::poll(timeout)?
+        {
+            app.flush_pending_filter();
+            terminal.draw(|f| ui::ui(f, app))?;
+            continue;
+        }
+
+        // Likewise, drop the type-ahead prompt once typing pauses.
+        if let Some(timeout) = app.type_ahead_remaining()
+            && !event::poll(timeout)?
+        {
+            app.type_ahead = None;
+            terminal.draw(|f| ui::ui(f, app))?;
+            continue;
+        }
 
         match event::read()? {
             Event::Key(key) => {
@@ -782,33 +2945,138 @@ fn handle_key_event(
 ) {
     fn apply_filter_edit(app: &mut AppState, edit: impl FnOnce(&mut AppState)) {
         edit(app);
-        app.update_filter();
+        app.schedule_filter_update();
     }
 
     if matches!(kind, KeyEventKind::Release) {
         return;
     }
+    app.status_message = None;
+    app.hovered_preview = None;
+
+    // Anything acting on the results needs them to match the typed filter.
+    if app.input_mode != InputMode::Filtering {
+        app.flush_pending_filter();
+    }
+
+    if app.load_failure.is_some() {
+        match code {
+            KeyCode::Char('r') | KeyCode::Enter => app.pending_action = Some(AppAction::RetryLoad),
+            KeyCode::Esc => app.load_failure = None,
+            _ => {}
+        }
+        return;
+    }
+
+    if app.type_ahead_remaining() == Some(Duration::ZERO) {
+        app.type_ahead = None;
+    }
+    if app.type_ahead.is_some() {
+        match code {
+            KeyCode::Char('\'') => return app.type_ahead_next(),
+            KeyCode::Char(c)
+                if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                return app.edit_type_ahead(|query| query.push(c));
+            }
+            KeyCode::Backspace => {
+                return app.edit_type_ahead(|query| {
+                    query.pop();
+                });
+            }
+            KeyCode::Esc | KeyCode::Enter => {
+                app.type_ahead = None;
+                return;
+            }
+            // Anything else ends type-ahead and does what it normally does.
+            _ => app.type_ahead = None,
+        }
+    }
 
     if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('g') {
         app.show_help = false;
+        app.show_about = false;
         app.show_version_picker = false;
+        app.show_bookmarks = false;
+        app.show_recent = false;
+        app.show_lint = false;
+        app.show_warnings = false;
         app.focus_pane(FocusPane::List);
         app.history_index = None;
         app.pending_action = Some(AppAction::OpenVersionPicker);
         return;
     }
 
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('p') {
+        app.show_help = false;
+        app.show_about = false;
+        app.show_version_picker = false;
+        app.show_bookmarks = false;
+        app.show_recent = false;
+        app.show_lint = false;
+        app.show_warnings = false;
+        app.history_index = None;
+        app.open_type_picker();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('y') {
+        app.show_help = false;
+        app.show_about = false;
+        app.show_version_picker = false;
+        app.show_bookmarks = false;
+        app.show_recent = false;
+        app.show_lint = false;
+        app.show_warnings = false;
+        app.show_type_picker = false;
+        app.open_history_search();
+        return;
+    }
+
     if (modifiers.contains(KeyModifiers::CONTROL) || modifiers.contains(KeyModifiers::SUPER))
         && code == KeyCode::Char('r')
     {
-        if app.source_dir.is_some() {
-            app.pending_action = Some(AppAction::ReloadSource);
-        }
+        app.request_reload();
         return;
     }
 
+    // While the completion popup is open it takes Tab, Up/Down and Esc; other keys close it.
+    if app.input_mode == InputMode::Filtering
+        && let Some(completion) = &app.completion
+    {
+        let last = completion.candidates.len().saturating_sub(1);
+        let selected = app.completion_list_state.selected().unwrap_or(0);
+        match code {
+            KeyCode::Tab => return apply_filter_edit(app, AppState::accept_completion),
+            KeyCode::Up => {
+                return app
+                    .completion_list_state
+                    .select(Some(selected.saturating_sub(1)));
+            }
+            KeyCode::Down => {
+                return app
+                    .completion_list_state
+                    .select(Some((selected + 1).min(last)));
+            }
+            KeyCode::Esc => {
+                app.completion = None;
+                return;
+            }
+            _ => app.completion = None,
+        }
+    }
+
     if code == KeyCode::Tab || code == KeyCode::BackTab {
-        if code == KeyCode::BackTab || modifiers.contains(KeyModifiers::SHIFT) {
+        let backward = code == KeyCode::BackTab || modifiers.contains(KeyModifiers::SHIFT);
+        // Inside the details pane Tab walks the links first, then moves on.
+        if app.focused_pane == FocusPane::Details
+            && !app.show_help
+            && !app.show_version_picker
+            && app.cycle_focused_link(!backward)
+        {
+            return;
+        }
+        if backward {
             app.focus_prev_pane();
         } else {
             app.focus_next_pane();
@@ -817,51 +3085,336 @@ fn handle_key_event(
     }
 
     if app.show_help {
-        if matches!(code, KeyCode::Char('?') | KeyCode::Esc) {
-            app.show_help = false;
+        if code != KeyCode::Char('x') {
+            app.confirm_clear_cache = false;
+        }
+        match code {
+            KeyCode::Char('?') | KeyCode::Esc => app.show_help = false,
+            KeyCode::Char('x') => app.clear_download_cache(),
+            KeyCode::Char('l') => app.open_lint_report(),
+            KeyCode::Char('a') => {
+                app.show_help = false;
+                app.show_about = true;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_about {
+        if matches!(code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('a')) {
+            app.show_about = false;
         }
         return;
     }
 
     if app.show_version_picker {
         match code {
+            KeyCode::Esc if !app.version_picker_query.is_empty() => {
+                app.version_picker_query.clear();
+                app.version_list_state
+                    .select((!app.version_entries.is_empty()).then_some(0));
+            }
             KeyCode::Esc => app.show_version_picker = false,
-            KeyCode::Up => app.version_list_state.select_previous(),
-            KeyCode::Down => app.version_list_state.select_next(),
+            KeyCode::Up => app.move_version_selection(-1),
+            KeyCode::Down => app.move_version_selection(1),
+            KeyCode::PageUp => app.move_version_selection(-10),
+            KeyCode::PageDown => app.move_version_selection(10),
+            KeyCode::Home => app.move_version_selection(isize::MIN),
+            KeyCode::End => app.move_version_selection(isize::MAX),
             KeyCode::Enter => {
-                if let Some(idx) = app.version_list_state.selected()
-                    && let Some(entry) = app.version_entries.get(idx)
-                {
-                    app.pending_action = Some(AppAction::SwitchVersion(entry.version.clone()));
+                let version = app.version_list_state.selected().and_then(|idx| {
+                    app.visible_version_entries()
+                        .get(idx)
+                        .map(|entry| entry.version.clone())
+                });
+                if let Some(version) = version {
+                    app.pending_action = Some(AppAction::SwitchVersion(version));
+                }
+            }
+            // Compares with the highlighted version, or stops comparing with it.
+            KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+                let version = app.version_list_state.selected().and_then(|idx| {
+                    app.visible_version_entries()
+                        .get(idx)
+                        .map(|entry| entry.version.clone())
+                });
+                let Some(version) = version else {
+                    return;
+                };
+                if app.compare_version() == Some(version.as_str()) {
+                    app.show_version_picker = false;
+                    app.set_compare(None);
+                } else {
+                    app.pending_action = Some(AppAction::LoadCompare(version));
                 }
             }
+            KeyCode::Backspace => app.edit_version_picker_query(|query| {
+                query.pop();
+            }),
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                app.edit_version_picker_query(|query| query.push(c));
+            }
             _ => {}
         }
         return;
     }
 
-    match app.input_mode {
-        InputMode::Normal => match code {
-            KeyCode::Char('q') => app.should_quit = true,
-            KeyCode::Char('/') => app.focus_pane(FocusPane::Filter),
-            KeyCode::Char('?') => app.show_help = true,
-            KeyCode::Up if !modifiers.contains(KeyModifiers::CONTROL) => {
-                if app.focused_pane == FocusPane::Details {
-                    app.scroll_details_up();
-                } else {
-                    app.move_selection(-1);
-                }
-            }
-            KeyCode::Down if !modifiers.contains(KeyModifiers::CONTROL) => {
-                if app.focused_pane == FocusPane::Details {
-                    app.scroll_details_down();
-                } else {
-                    app.move_selection(1);
-                }
-            }
-            KeyCode::Home => {
-                if app.focused_pane == FocusPane::Details {
-                    app.details_scroll_state = ScrollViewState::default();
+    if app.show_bookmarks {
+        match code {
+            KeyCode::Esc => app.show_bookmarks = false,
+            KeyCode::Up => app.bookmark_list_state.select_previous(),
+            KeyCode::Down => app.bookmark_list_state.select_next(),
+            KeyCode::Enter => app.apply_bookmark_selection(),
+            KeyCode::Delete | KeyCode::Backspace => app.remove_selected_bookmark(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_recent {
+        match code {
+            KeyCode::Esc => app.show_recent = false,
+            KeyCode::Up => app.recent_list_state.select_previous(),
+            KeyCode::Down => app.recent_list_state.select_next(),
+            KeyCode::Enter => app.apply_recent_selection(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_warnings {
+        match code {
+            KeyCode::Esc => app.show_warnings = false,
+            KeyCode::Up => app.warnings_list_state.select_previous(),
+            KeyCode::Down => app.warnings_list_state.select_next(),
+            KeyCode::PageUp => app.warnings_list_state.scroll_up_by(10),
+            KeyCode::PageDown => app.warnings_list_state.scroll_down_by(10),
+            KeyCode::Home => app.warnings_list_state.select_first(),
+            KeyCode::End => app.warnings_list_state.select_last(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_lint {
+        match code {
+            KeyCode::Esc => app.show_lint = false,
+            KeyCode::Up => app.lint_list_state.select_previous(),
+            KeyCode::Down => app.lint_list_state.select_next(),
+            KeyCode::PageUp => app.lint_list_state.scroll_up_by(10),
+            KeyCode::PageDown => app.lint_list_state.scroll_down_by(10),
+            KeyCode::Enter => app.apply_lint_selection(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_history_search {
+        match code {
+            KeyCode::Esc => app.show_history_search = false,
+            KeyCode::Up => app.history_list_state.select_previous(),
+            KeyCode::Down => app.history_list_state.select_next(),
+            KeyCode::Enter => app.apply_history_search_selection(),
+            KeyCode::Backspace => app.edit_history_search_query(|query| {
+                query.pop();
+            }),
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                app.edit_history_search_query(|query| query.push(c));
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if app.show_type_picker {
+        match code {
+            KeyCode::Esc => app.show_type_picker = false,
+            KeyCode::Up => app.type_list_state.select_previous(),
+            KeyCode::Down => app.type_list_state.select_next(),
+            KeyCode::PageUp => app.type_list_state.scroll_up_by(10),
+            KeyCode::PageDown => app.type_list_state.scroll_down_by(10),
+            KeyCode::Enter => app.apply_type_picker_selection(),
+            KeyCode::Backspace => app.edit_type_picker_query(|query| {
+                query.pop();
+            }),
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                app.edit_type_picker_query(|query| query.push(c));
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if code == KeyCode::Esc && app.background_load.is_some() {
+        app.cancel_background_load();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('t') {
+        app.toggle_raw_json();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::ALT) && code == KeyCode::Char('t') {
+        app.toggle_table();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('f') {
+        app.toggle_resolved();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('s') {
+        app.toggle_bookmark();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('v') {
+        app.show_help = false;
+        app.show_about = false;
+        app.open_bookmarks();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::ALT) && code == KeyCode::Char('r') {
+        app.show_help = false;
+        app.show_about = false;
+        app.open_recent();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::ALT) && code == KeyCode::Char('w') {
+        app.open_warnings();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::ALT) && code == KeyCode::Char('e') {
+        app.export_marked();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('k') {
+        app.toggle_pin();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('d') {
+        app.toggle_diff();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('b') {
+        app.toggle_hide_abstracts();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('x') {
+        app.toggle_columns();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('n') {
+        app.toggle_details_wrap();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('l') {
+        app.layout_mode = app.layout_mode.next();
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('o') {
+        app.pending_action = app.selected_item_url().map(AppAction::OpenInBrowser);
+        return;
+    }
+
+    if modifiers.contains(KeyModifiers::ALT) && code == KeyCode::Char('o') {
+        app.cycle_search_target();
+        return;
+    }
+
+    // Ctrl+E moves the cursor while typing a filter.
+    if modifiers.contains(KeyModifiers::CONTROL)
+        && code == KeyCode::Char('e')
+        && app.input_mode != InputMode::Filtering
+    {
+        app.open_selected_in_editor();
+        return;
+    }
+
+    match app.input_mode {
+        InputMode::Normal => match code {
+            KeyCode::Char('q') => app.should_quit = true,
+            KeyCode::Char('/') => app.focus_pane(FocusPane::Filter),
+            KeyCode::Char('?') => app.open_help(),
+            KeyCode::Char('\'') => app.start_type_ahead(),
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
+                if app.focused_pane == FocusPane::Details
+                    && modifiers.contains(KeyModifiers::SHIFT) =>
+            {
+                let (lines, columns) = match code {
+                    KeyCode::Up => (-1, 0),
+                    KeyCode::Down => (1, 0),
+                    KeyCode::Left => (0, -1),
+                    _ => (0, 1),
+                };
+                app.extend_details_selection(lines, columns);
+            }
+            KeyCode::Char('y')
+                if app.focused_pane == FocusPane::Details && app.details_selection.is_some() =>
+            {
+                app.copy_details_selection();
+            }
+            KeyCode::Esc if app.details_selection.is_some() => app.details_selection = None,
+            KeyCode::Char(' ') if app.focused_pane == FocusPane::List => app.toggle_mark(),
+            KeyCode::Char(']') if app.focused_pane == FocusPane::List => app.jump_type_group(true),
+            KeyCode::Char('[') if app.focused_pane == FocusPane::List => {
+                app.jump_type_group(false);
+            }
+            KeyCode::Esc if app.focused_pane == FocusPane::List && !app.marked.is_empty() => {
+                app.marked.clear();
+            }
+            KeyCode::Char('c')
+                if app.focused_pane == FocusPane::Details
+                    && !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                app.append_hovered_link();
+            }
+            KeyCode::Char('p')
+                if app.focused_pane == FocusPane::Details
+                    && !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                app.copy_hovered_path();
+            }
+            KeyCode::Enter if app.focused_pane == FocusPane::Details => {
+                let jump_to_id = modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+                app.activate_focused_link(jump_to_id);
+            }
+            KeyCode::Up if !modifiers.contains(KeyModifiers::CONTROL) => {
+                if app.focused_pane == FocusPane::Details {
+                    app.scroll_details_by_lines(app.scroll_lines, false);
+                } else {
+                    app.move_selection(-1);
+                }
+            }
+            KeyCode::Down if !modifiers.contains(KeyModifiers::CONTROL) => {
+                if app.focused_pane == FocusPane::Details {
+                    app.scroll_details_by_lines(app.scroll_lines, true);
+                } else {
+                    app.move_selection(1);
+                }
+            }
+            KeyCode::Left if app.focused_pane == FocusPane::Details && !app.details_wrap => {
+                app.details_scroll_state.scroll_left();
+            }
+            KeyCode::Right if app.focused_pane == FocusPane::Details && !app.details_wrap => {
+                app.details_scroll_state.scroll_right();
+            }
+            KeyCode::Home => {
+                if app.focused_pane == FocusPane::Details {
+                    app.details_scroll_state = ScrollViewState::default();
                 } else {
                     app.list_state.select(Some(0));
                     app.refresh_details();
@@ -880,9 +3433,10 @@ fn handle_key_event(
             }
             KeyCode::PageUp => {
                 if app.focused_pane == FocusPane::Details {
-                    app.details_scroll_state.scroll_page_up();
+                    let visible = app.details_content_area.map_or(1, |a| a.height);
+                    app.scroll_details_by_lines(app.page_step(visible), false);
                 } else {
-                    let page_size = app.list_area.map(|a| a.height).unwrap_or(10) as i32;
+                    let page_size = app.page_step(app.list_area.map(|a| a.height).unwrap_or(10));
                     let current = app.list_state.selected().unwrap_or(0);
                     let new_sel = current.saturating_sub(page_size as usize);
                     app.list_state.select(Some(new_sel));
@@ -891,9 +3445,10 @@ fn handle_key_event(
             }
             KeyCode::PageDown => {
                 if app.focused_pane == FocusPane::Details {
-                    app.details_scroll_state.scroll_page_down();
+                    let visible = app.details_content_area.map_or(1, |a| a.height);
+                    app.scroll_details_by_lines(app.page_step(visible), true);
                 } else {
-                    let page_size = app.list_area.map(|a| a.height).unwrap_or(10) as i32;
+                    let page_size = app.page_step(app.list_area.map(|a| a.height).unwrap_or(10));
                     let current = app.list_state.selected().unwrap_or(0);
                     let len = app.filtered_indices.len();
                     if len > 0 {
@@ -903,16 +3458,9 @@ fn handle_key_event(
                     }
                 }
             }
-            KeyCode::Char('r')
-                if modifiers.contains(KeyModifiers::CONTROL)
-                    || modifiers.contains(KeyModifiers::SUPER) =>
-            {
-                if app.source_dir.is_some() {
-                    app.pending_action = Some(AppAction::ReloadSource);
-                }
-            }
             KeyCode::Char(c)
-                if c.is_alphanumeric()
+                if app.auto_filter
+                    && c.is_alphanumeric()
                     && !modifiers.contains(KeyModifiers::CONTROL)
                     && !modifiers.contains(KeyModifiers::ALT) =>
             {
@@ -924,16 +3472,18 @@ fn handle_key_event(
         },
         InputMode::Filtering => match code {
             KeyCode::Enter => {
+                app.flush_pending_filter();
                 if !app.filter_text.trim().is_empty()
                     && app.filter_history.last() != Some(&app.filter_text)
                 {
-                    app.filter_history.push(app.filter_text.clone());
+                    app.push_history(app.filter_text.clone());
                     app.save_history();
                 }
                 app.history_index = None;
                 app.focus_pane(FocusPane::List);
             }
             KeyCode::Esc => {
+                app.flush_pending_filter();
                 app.history_index = None;
                 app.focus_pane(FocusPane::List);
             }
@@ -952,32 +3502,32 @@ fn handle_key_event(
             KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
                 app.history_index = None;
                 apply_filter_edit(app, |app| app.filter_add_char(c));
+                app.update_completions();
             }
             KeyCode::Backspace => {
                 app.history_index = None;
                 apply_filter_edit(app, AppState::filter_backspace);
+                app.update_completions();
             }
             KeyCode::Delete => {
                 app.history_index = None;
                 apply_filter_edit(app, AppState::filter_delete);
             }
-            KeyCode::Up => {
-                if !app.filter_history.is_empty() {
-                    match app.history_index {
-                        None => {
-                            app.stashed_input = app.filter_text.clone();
-                            app.history_index = Some(app.filter_history.len() - 1);
-                        }
-                        Some(idx) if idx > 0 => {
-                            app.history_index = Some(idx - 1);
-                        }
-                        _ => {}
+            KeyCode::Up if !app.filter_history.is_empty() => {
+                match app.history_index {
+                    None => {
+                        app.stashed_input = app.filter_text.clone();
+                        app.history_index = Some(app.filter_history.len() - 1);
                     }
-                    if let Some(idx) = app.history_index {
-                        app.filter_text = app.filter_history[idx].clone();
-                        app.filter_move_to_end();
-                        app.update_filter();
+                    Some(idx) if idx > 0 => {
+                        app.history_index = Some(idx - 1);
                     }
+                    _ => {}
+                }
+                if let Some(idx) = app.history_index {
+                    app.filter_text = app.filter_history[idx].clone();
+                    app.filter_move_to_end();
+                    app.update_filter();
                 }
             }
             KeyCode::Down => {
@@ -1001,19 +3551,36 @@ fn handle_key_event(
         },
     }
 }
-/// Fields that should never trigger any clickable navigation.
-const EXCLUDED_FIELDS: &[&str] = &[
-    "id",
-    "abstract",
-    "description",
-    "name",
-    "__filename",
-    "//",
-    "//2",
-    "rows",
-];
 
-const SCROLL_LINES: u16 = 1;
+/// `EXPORT_FILE` in `dir`, or the first `cbn-tui-export-N.json` there that
+/// doesn't exist, so an export never replaces an earlier one.
+fn unused_export_path(dir: &std::path::Path) -> std::path::PathBuf {
+    let first = dir.join(EXPORT_FILE);
+    if !first.exists() {
+        return first;
+    }
+    let stem = EXPORT_FILE.trim_end_matches(".json");
+    (2..)
+        .map(|n| dir.join(format!("{stem}-{n}.json")))
+        .find(|path| !path.exists())
+        .unwrap_or(first)
+}
+
+/// Pretty-prints `value` like `serde_json::to_string_pretty`, with `indent` spaces per level.
+fn pretty_json(value: &serde_json::Value, indent: usize) -> serde_json::Result<String> {
+    let indent = " ".repeat(indent);
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    serde::Serialize::serialize(
+        value,
+        &mut serde_json::Serializer::with_formatter(&mut out, formatter),
+    )?;
+    // The serializer only writes valid UTF-8.
+    Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Idle time after the last filter keystroke before the matcher runs.
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(80);
 
 fn pane_at(app: &AppState, column: u16, row: u16) -> Option<FocusPane> {
     if let Some(area) = app.filter_area
@@ -1035,6 +3602,10 @@ fn pane_at(app: &AppState, column: u16, row: u16) -> Option<FocusPane> {
 }
 
 fn handle_mouse_event(app: &mut AppState, mouse: event::MouseEvent) -> bool {
+    if !matches!(mouse.kind, event::MouseEventKind::Moved) {
+        app.flush_pending_filter();
+        app.hovered_preview = None;
+    }
     let hovered_pane = pane_at(app, mouse.column, mouse.row);
     let mut is_valid_target = false;
     let mut new_hover_id = None;
@@ -1042,16 +3613,12 @@ fn handle_mouse_event(app: &mut AppState, mouse: event::MouseEvent) -> bool {
     let mut target_id = None;
 
     if let Some(span) = ui::hit_test_details(app, mouse.column, mouse.row)
-        && let Some(path) = &span.key_context
+        && let Some(path_str) = link_path(span, &app.excluded_fields)
     {
-        let path_str = path.as_ref();
-        let first_part = path_str.split('.').next().unwrap_or("");
-        if !EXCLUDED_FIELDS.contains(&first_part) && span.span_id.is_some() {
-            is_valid_target = true;
-            new_hover_id = span.span_id;
-            target_path = path_str.to_string();
-            target_id = span.span_id;
-        }
+        is_valid_target = true;
+        new_hover_id = span.span_id;
+        target_path = path_str.to_string();
+        target_id = span.span_id;
     }
 
     let mut transitioned = false;
@@ -1059,10 +3626,25 @@ fn handle_mouse_event(app: &mut AppState, mouse: event::MouseEvent) -> bool {
     if matches!(
         mouse.kind,
         event::MouseEventKind::Moved | event::MouseEventKind::Drag(_)
-    ) && app.hovered_span_id != new_hover_id
-    {
-        app.hovered_span_id = new_hover_id;
-        transitioned = true;
+    ) {
+        if app.hovered_span_id != new_hover_id {
+            app.hovered_span_id = new_hover_id;
+            app.hovered_path = is_valid_target.then(|| target_path.clone());
+            if let Some(id) = new_hover_id {
+                if is_valid_target {
+                    app.update_hovered_value_count(&target_path, id);
+                }
+                app.update_hovered_preview(id, (mouse.column, mouse.row));
+            } else {
+                app.hovered_preview = None;
+            }
+            transitioned = true;
+        }
+        let key_path = hovered_key_path(app, mouse.column, mouse.row);
+        if app.hovered_key_path != key_path {
+            app.hovered_key_path = key_path;
+            transitioned = true;
+        }
     }
 
     if matches!(
@@ -1074,7 +3656,7 @@ fn handle_mouse_event(app: &mut AppState, mouse: event::MouseEvent) -> bool {
             match pane {
                 FocusPane::List => {
                     if !app.filtered_indices.is_empty() {
-                        for _ in 0..SCROLL_LINES {
+                        for _ in 0..app.scroll_lines {
                             if scroll_down {
                                 app.list_state.select_next();
                             } else {
@@ -1087,7 +3669,9 @@ fn handle_mouse_event(app: &mut AppState, mouse: event::MouseEvent) -> bool {
                     }
                 }
                 FocusPane::Details => {
-                    app.scroll_details_by_lines(SCROLL_LINES, scroll_down);
+                    app.scroll_details_by_lines(app.scroll_lines, scroll_down);
+                    // The content moved under the mouse.
+                    app.hovered_key_path = hovered_key_path(app, mouse.column, mouse.row);
                     transitioned = true;
                 }
                 FocusPane::Filter => {}
@@ -1095,7 +3679,43 @@ fn handle_mouse_event(app: &mut AppState, mouse: event::MouseEvent) -> bool {
         }
     }
 
+    if let event::MouseEventKind::Drag(event::MouseButton::Left) = mouse.kind
+        && let Some(pos) = ui::details_position_at(app, mouse.column, mouse.row)
+        && let Some(selection) = &mut app.details_selection
+    {
+        selection.cursor = pos;
+        transitioned = true;
+    }
+
+    if let event::MouseEventKind::Down(event::MouseButton::Left) = mouse.kind
+        && let Some(area) = app.details_minimap_area
+        && area.contains((mouse.column, mouse.row).into())
+    {
+        app.scroll_details_to_minimap(mouse.row);
+        app.focus_pane(FocusPane::Details);
+        return true;
+    }
+
     if let event::MouseEventKind::Down(event::MouseButton::Left) = mouse.kind {
+        // Alt-Click copies the value's path instead of following it.
+        if mouse.modifiers.contains(KeyModifiers::ALT)
+            && let Some(path) = hovered_key_path(app, mouse.column, mouse.row)
+        {
+            app.hovered_key_path = Some(path);
+            app.copy_hovered_path();
+            return true;
+        }
+        // A click starts a new selection (a caret) on plain text and drops the old one.
+        let caret = ui::details_position_at(app, mouse.column, mouse.row)
+            .filter(|_| !is_valid_target)
+            .map(|pos| ui::DetailsSelection {
+                anchor: pos,
+                cursor: pos,
+            });
+        if app.details_selection != caret {
+            app.details_selection = caret;
+            transitioned = true;
+        }
         if let Some(pane) = hovered_pane {
             let previous_focus = app.focused_pane;
             let previous_mode = app.input_mode;
@@ -1133,55 +3753,26 @@ fn handle_mouse_event(app: &mut AppState, mouse: event::MouseEvent) -> bool {
             let new_cursor = ui::filter_cursor_for_column(&app.filter_text, target_column);
             if new_cursor != app.filter_cursor {
                 app.filter_cursor = new_cursor;
+                app.completion = None;
                 transitioned = true;
             }
         }
 
-        if is_valid_target {
-            let mut full_value = String::new();
-            if let Some(id) = target_id {
-                for line in &app.details_annotated {
-                    for span in line {
-                        if span.span_id == Some(id) {
-                            full_value.push_str(&span.span.content);
-                        }
-                    }
-                }
-            }
-
-            let clean_val = full_value.trim();
-            let mut unescaped_val = clean_val.to_string();
-            if clean_val.starts_with('"') && clean_val.ends_with('"') && clean_val.len() >= 2 {
-                if let Ok(s) = serde_json::from_str::<String>(clean_val) {
-                    unescaped_val = s;
-                } else {
-                    unescaped_val = clean_val[1..clean_val.len() - 1].to_string();
-                }
-            }
-
-            let escaped = unescaped_val.replace('\\', "\\\\").replace('\'', "\\'");
-            let final_val = format!("'{}'", escaped);
+        if let Some(link) = app
+            .header_links
+            .iter()
+            .find(|link| link.area.contains((mouse.column, mouse.row).into()))
+        {
+            let term = format!("{}:{}", link.classifier, quote_filter_value(&link.value));
+            app.append_filter_term(&term);
+            app.focus_pane(FocusPane::Filter);
+            transitioned = true;
+        }
 
+        if is_valid_target && let Some(id) = target_id {
             // ID navigation (i:<id>) triggered by Ctrl-Click
-            if mouse.modifiers.contains(KeyModifiers::CONTROL) {
-                app.filter_text = format!("i:{}", final_val);
-                app.filter_cursor = app.filter_text.chars().count();
-                app.update_filter();
-                app.focus_pane(FocusPane::Details);
-            } else {
-                // Normal click: property-specific filtering
-                let filter_addition = format!("{}:{}", target_path, final_val);
-                let current = app.filter_text.trim();
-                if current.is_empty() {
-                    app.filter_text = filter_addition;
-                } else {
-                    app.filter_text = format!("{} {}", current, filter_addition);
-                }
-                app.filter_cursor = app.filter_text.chars().count();
-                app.update_filter();
-                app.focus_pane(FocusPane::Filter);
-            }
-
+            let jump_to_id = mouse.modifiers.contains(KeyModifiers::CONTROL);
+            follow_link(app, &target_path, id, jump_to_id);
             transitioned = true;
         }
     }
@@ -1189,10 +3780,143 @@ fn handle_mouse_event(app: &mut AppState, mouse: event::MouseEvent) -> bool {
     transitioned
 }
 
+/// Dot-path of the details span at the given screen position, link or not.
+fn hovered_key_path(app: &AppState, column: u16, row: u16) -> Option<String> {
+    ui::hit_test_details(app, column, row)?
+        .key_context
+        .as_deref()
+        .map(str::to_string)
+}
+
+/// Returns the dot-path of a span if it can be followed as a link, i.e. it is a
+/// value outside the `excluded_fields`.
+fn link_path<'a>(span: &'a ui::AnnotatedSpan, excluded_fields: &[String]) -> Option<&'a str> {
+    let path = span.key_context.as_deref()?;
+    let first_part = path.split('.').next().unwrap_or("");
+    let excluded = excluded_fields.iter().any(|field| field == first_part);
+    (!excluded && span.span_id.is_some()).then_some(path)
+}
+
+/// Applies a link: either filters by `path:'value'` or jumps to the value as an id.
+fn follow_link(app: &mut AppState, target_path: &str, span_id: usize, jump_to_id: bool) {
+    let unescaped_val = link_value(&app.details_annotated, span_id);
+    if jump_to_id {
+        app.jump_to_id(&unescaped_val);
+    } else {
+        // Property-specific filtering
+        let final_val = quote_filter_value(&unescaped_val);
+        app.append_filter_term(&format!("{}:{}", target_path, final_val));
+        app.focus_pane(FocusPane::Filter);
+    }
+}
+
+/// The value of link `span_id`, joined across wrapped lines and unquoted.
+/// The type and the first few top-level fields with a plain value, for an id preview.
+fn id_preview_fields(item: &data::IndexedItem) -> Vec<(String, String)> {
+    let mut fields = vec![("type".to_string(), item.item_type.clone())];
+    let Some(object) = item.value.as_object() else {
+        return fields;
+    };
+    let scalars = object.iter().filter_map(|(key, value)| {
+        if matches!(key.as_str(), "id" | "type" | "description") {
+            return None;
+        }
+        let text = match value {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => value.to_string(),
+            // Translatable names are objects; show their singular form.
+            serde_json::Value::Object(_) if key == "name" => ui::name_value(value)?,
+            _ => return None,
+        };
+        Some((key.clone(), text))
+    });
+    fields.extend(scalars.take(ID_PREVIEW_FIELDS));
+    fields
+}
+
+fn link_value(annotated: &[Vec<ui::AnnotatedSpan>], span_id: usize) -> String {
+    let mut full_value = String::new();
+    for span in annotated.iter().flatten() {
+        if span.span_id == Some(span_id) {
+            full_value.push_str(&span.span.content);
+        }
+    }
+
+    let clean_val = full_value.trim();
+    if clean_val.starts_with('"') && clean_val.ends_with('"') && clean_val.len() >= 2 {
+        if let Ok(s) = serde_json::from_str::<String>(clean_val) {
+            return s;
+        }
+        return clean_val[1..clean_val.len() - 1].to_string();
+    }
+    clean_val.to_string()
+}
+
+/// How many of the items at `indices` have exactly `value` at `path`.
+fn count_field_value(
+    items: &[data::IndexedItem],
+    indices: &[usize],
+    path: &str,
+    value: &str,
+) -> usize {
+    indices
+        .iter()
+        .filter(|&&idx| matcher::matches_field(&items[idx].value, path, value, true))
+        .count()
+}
+
+/// Scores `candidate` as a fuzzy match for `query`, ignoring case. Returns `None`
+/// unless every query character appears in order. Consecutive matches and matches
+/// at the start of a word score higher; skipped characters cost a little.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut prev_match: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = pos + candidate[pos..].iter().position(|&c| c == q)?;
+        score += 1;
+        if prev_match.is_some_and(|prev| prev + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(candidate[found - 1], ' ' | ':' | '.' | '_' | '\'') {
+            score += 3;
+        }
+        score -= (found - pos) as i64;
+        prev_match = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+/// Ranks distinct history entries against `query`: best score first, more recent
+/// first on ties. An empty query lists everything newest first. `history` itself
+/// is left untouched.
+fn rank_history<'a>(history: &'a [String], query: &str) -> Vec<&'a str> {
+    let mut seen = foldhash::HashSet::default();
+    let mut ranked: Vec<(i64, &str)> = history
+        .iter()
+        .rev()
+        .filter(|entry| seen.insert(entry.as_str()))
+        .filter_map(|entry| Some((fuzzy_score(query, entry)?, entry.as_str())))
+        .collect();
+    // Stable sort keeps newer entries ahead of older ones with the same score.
+    ranked.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    ranked.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Wraps a value in single quotes for an exact-match filter term.
+fn quote_filter_value(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('\'', "\\'");
+    format!("'{}'", escaped)
+}
+
 fn load_initial_data<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut AppState,
     args: &Args,
+    preloaded: Option<data::Root>,
 ) -> Result<()>
 where
     B::Error: Send + Sync + 'static,
@@ -1200,9 +3924,16 @@ where
     let version = if args.source.is_some() {
         "local"
     } else {
-        &args.game
+        args.game()
     };
-    load_game_data_with_ui(terminal, app, args.file.as_deref(), version, args.force)
+    load_game_data_with_ui(
+        terminal,
+        app,
+        args.file.as_deref(),
+        preloaded,
+        version,
+        args.force,
+    )
 }
 
 fn handle_action<B: ratatui::backend::Backend>(
@@ -1215,8 +3946,21 @@ where
 {
     match action {
         AppAction::OpenVersionPicker => {
-            let builds = fetch_builds_with_ui(terminal, app, app.force_download)?;
-            app.version_entries = build_version_entries(builds);
+            app.version_entries = if app.offline {
+                cached_version_entries(data::list_cached_versions(
+                    &app.download_source.cache_dir()?,
+                ))
+            } else {
+                let builds = match fetch_builds_with_ui(terminal, app, app.force_download) {
+                    Err(err) if err.is::<data::Cancelled>() => {
+                        app.clear_progress();
+                        return Ok(());
+                    }
+                    result => result?,
+                };
+                build_version_entries(builds)
+            };
+            app.version_picker_query.clear();
             let selected = app
                 .version_entries
                 .iter()
@@ -1233,56 +3977,228 @@ where
         AppAction::SwitchVersion(version) => {
             app.show_version_picker = false;
             if version == app.game_version_key {
+                app.cancel_background_load();
                 return Ok(());
             }
-            load_game_data_with_ui(terminal, app, None, &version, app.force_download)?;
+            if app.total_items == 0 {
+                // Nothing to browse meanwhile, so keep the modal.
+                load_version_with_retry(terminal, app, &version, 1, false)?;
+            } else {
+                start_background_load(app, &version, false)?;
+            }
+        }
+        AppAction::RetryLoad => {
+            if let Some(failure) = app.load_failure.take() {
+                load_version_with_retry(
+                    terminal,
+                    app,
+                    &failure.version,
+                    RETRY_ATTEMPTS,
+                    failure.compare,
+                )?;
+            }
+        }
+        AppAction::LoadCompare(version) => {
+            app.show_version_picker = false;
+            if app.total_items == 0 {
+                load_version_with_retry(terminal, app, &version, 1, true)?;
+            } else {
+                start_background_load(app, &version, true)?;
+            }
         }
-        AppAction::ReloadSource => {
-            if app.source_dir.is_some() {
-                app.source_warnings.clear();
-                load_game_data_with_ui(terminal, app, None, "local", app.force_download)?;
+        AppAction::Reload(version) => {
+            if version == "local" {
+                let result =
+                    load_game_data_with_ui(terminal, app, None, None, "local", app.force_download);
+                ignore_cancelled(app, result)?;
+            } else if app.total_items == 0 {
+                load_version_with_retry(terminal, app, &version, 1, false)?;
+            } else {
+                start_background_load(app, &version, false)?;
             }
         }
+        AppAction::OpenInBrowser(url) => {
+            // A missing browser shouldn't take the whole TUI down.
+            let _ = open::that_detached(url);
+        }
+        AppAction::OpenInEditor(location) => open_in_editor(terminal, app, &location)?,
+        AppAction::CopyToClipboard(text) => {
+            // OSC 52: the terminal owns the clipboard, so this also works over SSH.
+            execute!(io::stdout(), CopyToClipboard::to_clipboard_from(text))?;
+        }
+        AppAction::ExportMarked(dir) => app.export_marked_to(&dir),
     }
 
     Ok(())
 }
 
-fn fetch_builds_with_ui<B: ratatui::backend::Backend>(
+/// Opens `location` in `$VISUAL`/`$EDITOR`, handing it the terminal until it
+/// exits. Without either, the file goes to the OS default application.
+fn open_in_editor<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut AppState,
-    force: bool,
-) -> Result<Vec<data::BuildInfo>>
+    location: &data::SourceLocation,
+) -> Result<()>
 where
     B::Error: Send + Sync + 'static,
 {
-    app.start_progress("Loading versions", &["Downloading"]);
-    terminal.draw(|f| ui::ui(f, app))?;
-
-    let mut last_ratio = -1.0;
-    let mut last_draw = Instant::now();
-    let mut draw_error: Option<anyhow::Error> = None;
-    let builds = data::fetch_builds_with_progress(force, |progress| {
-        let ratio = progress_ratio(progress);
-        let elapsed_ok = last_draw.elapsed() >= Duration::from_millis(120);
-        let ratio_ok = (ratio - last_ratio).abs() >= 0.01;
-        let should_draw = if progress.total.is_some() {
-            ratio_ok || elapsed_ok
-        } else {
-            elapsed_ok
-        };
-        if !should_draw {
-            return;
+    let Some(mut command) = editor::configured_editor()
+        .and_then(|editor| editor::editor_command(&editor, &location.path, location.line))
+    else {
+        if let Err(err) = open::that_detached(&location.path) {
+            app.status_message = Some(format!(
+                "Could not open {}: {}",
+                location.path.display(),
+                err
+            ));
         }
-        if draw_error.is_none() {
+        return Ok(());
+    };
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    let status = command.status();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+
+    match status {
+        Ok(status) if !status.success() => {
+            app.status_message = Some(format!("Editor exited with {}", status));
+        }
+        Err(err) => app.status_message = Some(format!("Could not start editor: {}", err)),
+        Ok(_) => {}
+    }
+    Ok(())
+}
+
+/// Downloads and loads `version`, making up to `attempts` tries with exponential backoff,
+/// as the dataset compared against if `compare` is set.
+/// On failure the current dataset is kept and the error modal is shown instead of
+/// returning the error; a cancelled load (or wait) is treated as success.
+fn load_version_with_retry<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut AppState,
+    version: &str,
+    attempts: u32,
+    compare: bool,
+) -> Result<()>
+where
+    B::Error: Send + Sync + 'static,
+{
+    let mut last_error = None;
+    for attempt in 0..attempts {
+        if !wait_before_retry(terminal, app, attempt, attempts)? {
+            app.clear_progress();
+            return Ok(());
+        }
+        // A cancelled load never reached `apply_new_dataset`, so the current data stays.
+        let result = if compare {
+            load_compare_with_ui(terminal, app, version)
+        } else {
+            load_game_data_with_ui(terminal, app, None, None, version, app.force_download)
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(err) if err.is::<data::Cancelled>() => {
+                app.clear_progress();
+                return Ok(());
+            }
+            Err(err) => last_error = Some(err),
+        }
+    }
+
+    app.clear_progress();
+    if let Some(err) = last_error {
+        app.load_failure = Some(LoadFailure {
+            version: version.to_string(),
+            message: format!("{:#}", err),
+            compare,
+        });
+    }
+    Ok(())
+}
+
+/// Shows a countdown for the backoff before `attempt`. Returns `false` if the user cancelled.
+fn wait_before_retry<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut AppState,
+    attempt: u32,
+    attempts: u32,
+) -> Result<bool>
+where
+    B::Error: Send + Sync + 'static,
+{
+    let delay = retry_backoff(attempt);
+    if delay.is_zero() {
+        return Ok(true);
+    }
+
+    let title = format!("Retrying ({}/{})", attempt + 1, attempts);
+    app.start_progress(title, &["Waiting"]);
+    let start = Instant::now();
+    while start.elapsed() < delay {
+        app.update_stage(
+            "Waiting",
+            Some(start.elapsed().as_secs_f64() / delay.as_secs_f64()),
+        );
+        terminal.draw(|f| ui::ui(f, app))?;
+        if app.poll_progress_cancel().is_break() {
+            return Ok(false);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Ok(true)
+}
+
+/// Treats a user-cancelled load as success, closing the progress modal.
+fn ignore_cancelled(app: &mut AppState, result: Result<()>) -> Result<()> {
+    match result {
+        Err(err) if err.is::<data::Cancelled>() => {
+            app.clear_progress();
+            Ok(())
+        }
+        other => other,
+    }
+}
+
+fn fetch_builds_with_ui<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut AppState,
+    force: bool,
+) -> Result<Vec<data::BuildInfo>>
+where
+    B::Error: Send + Sync + 'static,
+{
+    app.start_progress("Loading versions", &["Downloading"]);
+    terminal.draw(|f| ui::ui(f, app))?;
+
+    let mut last_ratio = -1.0;
+    let mut last_draw = Instant::now();
+    let mut draw_error: Option<anyhow::Error> = None;
+    let source = app.download_source.clone();
+    let builds = data::fetch_builds_with_progress(&source, force, |progress| {
+        let ratio = progress_ratio(progress);
+        let elapsed_ok = last_draw.elapsed() >= Duration::from_millis(120);
+        let ratio_ok = ratio.is_some_and(|ratio| (ratio - last_ratio).abs() >= 0.01);
+        let should_draw = if progress.total.is_some() {
+            ratio_ok || elapsed_ok
+        } else {
+            elapsed_ok
+        };
+        if !should_draw {
+            return ControlFlow::Continue(());
+        }
+        if draw_error.is_none() {
             app.update_stage("Downloading", ratio);
             if let Err(err) = terminal.draw(|f| ui::ui(f, app)) {
                 draw_error = Some(anyhow::Error::from(err));
             } else {
                 last_draw = Instant::now();
-                last_ratio = ratio;
+                last_ratio = ratio.unwrap_or(last_ratio);
             }
         }
+        app.poll_progress_cancel()
     })?;
 
     if let Some(err) = draw_error {
@@ -1296,179 +4212,160 @@ where
     Ok(builds)
 }
 
+/// Loads `version` (or the given file, stdin data or source dir) on a worker
+/// thread while the progress modal is shown. Esc cancels with [`data::Cancelled`].
 fn load_game_data_with_ui<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut AppState,
     file_path: Option<&str>,
+    preloaded: Option<data::Root>,
     version: &str,
     force: bool,
 ) -> Result<()>
 where
     B::Error: Send + Sync + 'static,
 {
-    let root = if version == "local" && app.source_dir.is_some() {
-        let source_dir = app.source_dir.clone().unwrap();
-        app.start_progress(
-            "Loading local data",
-            &["Loading files", "Parsing", "Indexing"],
-        );
-        terminal.draw(|f| ui::ui(f, app))?;
-        let root = data::load_from_source(&source_dir, &mut app.source_warnings)?;
-        app.finish_stage("Loading files");
-        terminal.draw(|f| ui::ui(f, app))?;
-        root
-    } else if let Some(file) = file_path {
-        app.start_progress("Loading data", &["Parsing", "Indexing"]);
-        terminal.draw(|f| ui::ui(f, app))?;
-        data::load_root(file)?
-    } else {
-        app.start_progress("Loading data", &["Downloading", "Parsing", "Indexing"]);
-        terminal.draw(|f| ui::ui(f, app))?;
-
-        let mut last_ratio = -1.0;
-        let mut last_draw = Instant::now();
-        let mut draw_error: Option<anyhow::Error> = None;
-        let path = data::fetch_game_data_with_progress(version, force, |progress| {
-            let ratio = progress_ratio(data::DownloadProgress {
-                downloaded: progress.downloaded,
-                total: progress.total,
-            });
-            let elapsed_ok = last_draw.elapsed() >= Duration::from_millis(120);
-            let ratio_ok = (ratio - last_ratio).abs() >= 0.01;
-            let should_draw = if progress.total.is_some() {
-                ratio_ok || elapsed_ok
-            } else {
-                elapsed_ok
-            };
-            if !should_draw {
-                return;
-            }
-            if draw_error.is_none() {
-                app.update_stage("Downloading", ratio);
-                if let Err(err) = terminal.draw(|f| ui::ui(f, app)) {
-                    draw_error = Some(anyhow::Error::from(err));
-                } else {
-                    last_draw = Instant::now();
-                    last_ratio = ratio;
-                }
-            }
-        })?;
-
-        if let Some(err) = draw_error {
-            return Err(err);
-        }
-
-        app.finish_stage("Downloading");
-        terminal.draw(|f| ui::ui(f, app))?;
-        data::load_root(&path.to_string_lossy())?
-    };
+    // The modal takes over the progress display; a background switch is superseded.
+    app.background_load = None;
+    let (source, data_source) = load_source(app, file_path, preloaded, version, force)?;
+    app.start_progress(source.title(), source.stages());
+    terminal.draw(|f| ui::ui(f, app))?;
 
-    app.finish_stage("Parsing");
+    let loaded = wait_for_load(terminal, app, loader::LoadTask::spawn(source))?;
+    app.install_loaded_data(*loaded, version, file_path, data_source);
     terminal.draw(|f| ui::ui(f, app))?;
+    app.clear_progress();
 
-    let game_version_label = resolve_game_version_label(version, file_path, &root);
-    let total_items = root.data.len();
-    let (indexed_items, search_index, index_time_ms) =
-        build_index_with_progress(terminal, app, root.data)?;
-    app.apply_new_dataset(
-        indexed_items,
-        search_index,
-        total_items,
-        index_time_ms,
-        game_version_label,
-        version.to_string(),
+    Ok(())
+}
+
+/// Loads `version` on a worker thread as the dataset compared against, keeping
+/// the main one, while the progress modal is shown.
+fn load_compare_with_ui<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut AppState,
+    version: &str,
+) -> Result<()>
+where
+    B::Error: Send + Sync + 'static,
+{
+    app.background_load = None;
+    let (source, _) = load_source(app, None, None, version, app.force_download)?;
+    app.start_progress(
+        format!("Loading {} for comparison", version),
+        source.stages(),
     );
+    terminal.draw(|f| ui::ui(f, app))?;
 
-    app.finish_stage("Indexing");
+    let loaded = wait_for_load(terminal, app, loader::LoadTask::spawn(source))?;
+    app.install_compare_data(*loaded, version);
     terminal.draw(|f| ui::ui(f, app))?;
     app.clear_progress();
 
     Ok(())
 }
 
-fn build_index_with_progress<B: ratatui::backend::Backend>(
+/// Shows the progress of `task` in the modal until it is done. Esc cancels with
+/// [`data::Cancelled`].
+fn wait_for_load<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut AppState,
-    data: Vec<Value>,
-) -> Result<(Vec<data::IndexedItem>, search_index::SearchIndex, f64)>
+    task: loader::LoadTask,
+) -> Result<Box<loader::LoadedData>>
 where
     B::Error: Send + Sync + 'static,
 {
-    let total = data.len();
-    let start = Instant::now();
-    let mut last_draw = Instant::now();
-    let mut indexed_items: Vec<data::IndexedItem> = Vec::with_capacity(total);
-
-    for (idx, v) in data.into_iter().enumerate() {
-        let id = v
-            .get("id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let type_ = v
-            .get("type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        indexed_items.push(data::IndexedItem {
-            value: v,
-            id,
-            item_type: type_,
-        });
-
-        if total > 0 && (idx % 500 == 0 || idx + 1 == total) {
-            let ratio = (idx + 1) as f64 / total as f64 * 0.4;
-            app.update_stage("Indexing", ratio);
-            if last_draw.elapsed() >= Duration::from_millis(120) || idx + 1 == total {
-                terminal.draw(|f| ui::ui(f, app))?;
-                last_draw = Instant::now();
-            }
+    loop {
+        if let Some(message) = task.recv_timeout(LOAD_POLL_INTERVAL)
+            && let Some(result) = app.apply_load_message(message)
+        {
+            return result;
+        }
+        terminal.draw(|f| ui::ui(f, app))?;
+        if app.poll_progress_cancel().is_break() {
+            return Err(data::Cancelled.into());
         }
     }
+}
 
-    indexed_items.sort_by(|a, b| a.item_type.cmp(&b.item_type).then_with(|| a.id.cmp(&b.id)));
-
-    let mut draw_error: Option<anyhow::Error> = None;
-    let mut last_ratio = -1.0;
-    let search_index =
-        search_index::SearchIndex::build_with_progress(&indexed_items, |processed, total_items| {
-            let ratio = if total_items > 0 {
-                0.4 + 0.6 * (processed as f64 / total_items as f64)
-            } else {
-                1.0
-            };
-            let ratio_ok = (ratio - last_ratio).abs() >= 0.01;
-            let elapsed_ok = last_draw.elapsed() >= Duration::from_millis(120);
-            let should_draw = ratio_ok || elapsed_ok || processed == total_items;
-            if draw_error.is_none() && should_draw {
-                app.update_stage("Indexing", ratio);
-                if let Err(err) = terminal.draw(|f| ui::ui(f, app)) {
-                    draw_error = Some(anyhow::Error::from(err));
-                } else {
-                    last_draw = Instant::now();
-                    last_ratio = ratio;
-                }
-            }
-        });
+/// Starts loading `version` on a worker thread while the current dataset stays
+/// browsable; progress goes to the status bar and `run_app` installs the result,
+/// as the dataset compared against if `compare` is set.
+fn start_background_load(app: &mut AppState, version: &str, compare: bool) -> Result<()> {
+    app.background_load = None;
+    let (source, data_source) = load_source(app, None, None, version, app.force_download)?;
+    let title = if compare {
+        format!("Loading {} for comparison", version)
+    } else {
+        format!("Loading {}", version)
+    };
+    app.start_progress(title, source.stages());
+    app.show_progress = false;
+    app.background_load = Some(BackgroundLoad {
+        task: loader::LoadTask::spawn(source),
+        version: version.to_string(),
+        data_source,
+        compare,
+    });
+    Ok(())
+}
 
-    if let Some(err) = draw_error {
-        return Err(err);
+/// Picks where `version` is loaded from, along with the description shown in the about overlay.
+fn load_source(
+    app: &AppState,
+    file_path: Option<&str>,
+    preloaded: Option<data::Root>,
+    version: &str,
+    force: bool,
+) -> Result<(loader::LoadSource, String)> {
+    if let Some(root) = preloaded {
+        return Ok((
+            loader::LoadSource::Preloaded(root),
+            "standard input".to_string(),
+        ));
     }
-
-    let index_time_ms = start.elapsed().as_secs_f64() * 1000.0;
-    Ok((indexed_items, search_index, index_time_ms))
+    if version == "local"
+        && let Some(source_dir) = &app.source_dir
+    {
+        return Ok((
+            loader::LoadSource::SourceDir(source_dir.clone()),
+            format!("source dir {}", source_dir),
+        ));
+    }
+    if let Some(file) = file_path {
+        return Ok((
+            loader::LoadSource::File(file.to_string()),
+            format!("file {}", file),
+        ));
+    }
+    if app.offline {
+        let path = data::cached_game_data_path(&app.download_source, version)?;
+        let data_source = format!("offline cache {}", path.display());
+        return Ok((loader::LoadSource::Cached(path), data_source));
+    }
+    let cached = data::cached_game_data_path(&app.download_source, version)
+        .map_or_else(|_| "unknown".to_string(), |path| path.display().to_string());
+    Ok((
+        loader::LoadSource::Download {
+            source: app.download_source.clone(),
+            version: version.to_string(),
+            force,
+        },
+        format!("download cache {}", cached),
+    ))
 }
 
-fn resolve_game_version_label(version: &str, file_path: Option<&str>, root: &data::Root) -> String {
+fn resolve_game_version_label(
+    version: &str,
+    file_path: Option<&str>,
+    build: &data::BuildInfo,
+) -> String {
     if file_path.is_some() && version == "nightly" {
-        root.build.tag_name.clone()
-    } else if !version.is_empty()
-        && version != root.build.build_number
-        && version != root.build.tag_name
-    {
-        format!("{}:{}", version, root.build.tag_name)
+        build.tag_name.clone()
+    } else if !version.is_empty() && version != build.build_number && version != build.tag_name {
+        format!("{}:{}", version, build.tag_name)
     } else {
-        root.build.tag_name.clone()
+        build.tag_name.clone()
     }
 }
 
@@ -1478,11 +4375,13 @@ fn build_version_entries(builds: Vec<data::BuildInfo>) -> Vec<VersionEntry> {
         label: "stable".to_string(),
         version: "stable".to_string(),
         detail: None,
+        kind: None,
     });
     entries.push(VersionEntry {
         label: "nightly".to_string(),
         version: "nightly".to_string(),
         detail: None,
+        kind: None,
     });
 
     for build in builds {
@@ -1493,21 +4392,80 @@ fn build_version_entries(builds: Vec<data::BuildInfo>) -> Vec<VersionEntry> {
             label: build.build_number.clone(),
             version: build.build_number,
             detail: None,
+            kind: Some(if build.prerelease {
+                BuildKind::Nightly
+            } else {
+                BuildKind::Stable
+            }),
         });
     }
 
     entries
 }
 
-fn progress_ratio(progress: data::DownloadProgress) -> f64 {
-    if let Some(total) = progress.total
-        && total > 0
-    {
-        return progress.downloaded as f64 / total as f64;
+/// Version picker entries for --offline mode, taken from the cache directory.
+fn cached_version_entries(versions: Vec<String>) -> Vec<VersionEntry> {
+    versions
+        .into_iter()
+        .map(|version| VersionEntry {
+            label: version.clone(),
+            version,
+            detail: Some("cached".to_string()),
+            kind: None,
+        })
+        .collect()
+}
+
+/// Formats a byte count with a binary unit, e.g. `12.3 MB`.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
     }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Describes a download: bytes so far (of total), speed, and ETA when the total is known.
+fn format_transfer(downloaded: u64, total: Option<u64>, bytes_per_sec: f64) -> String {
+    let speed = format!("{}/s", format_bytes(bytes_per_sec as u64));
+    let Some(total) = total.filter(|&total| total > 0) else {
+        return format!("{} • {}", format_bytes(downloaded), speed);
+    };
+    let eta = if bytes_per_sec >= 1.0 {
+        let secs = (total.saturating_sub(downloaded) as f64 / bytes_per_sec).ceil() as u64;
+        format!("{}:{:02}", secs / 60, secs % 60)
+    } else {
+        "--:--".to_string()
+    };
+    format!(
+        "{} / {} • {} • ETA {}",
+        format_bytes(downloaded),
+        format_bytes(total),
+        speed,
+        eta
+    )
+}
 
-    let downloaded = progress.downloaded as f64;
-    downloaded / (downloaded + 1_000_000.0)
+/// Share of a download done so far; `None` when the server sent no length.
+fn progress_ratio(progress: data::DownloadProgress) -> Option<f64> {
+    let total = progress.total.filter(|&total| total > 0)?;
+    Some(progress.downloaded as f64 / total as f64)
+}
+
+/// Returns an empty scratch directory unique to this test process.
+#[cfg(test)]
+fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("cbn_test_{}_{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create test dir");
+    dir
 }
 
 #[cfg(test)]
@@ -1522,7 +4480,7 @@ mod tests {
         let json_str = r#"{"id": "test", "val": 123, "active": true}"#;
         let style = theme::Theme::Dracula.config().json_style;
         let annotated = ui::highlight_json_annotated(json_str, &style);
-        let highlighted = ui::annotated_to_text(&annotated, None);
+        let highlighted = ui::annotated_to_text(&annotated, None, None, None);
 
         let mut found_id = false;
         let mut found_val = false;
@@ -1530,13 +4488,13 @@ mod tests {
 
         for line in &highlighted.lines {
             for span in &line.spans {
-                if span.content.contains("\"id\"") && span.style.fg == Some(style.key) {
+                if span.content.contains("\"id\"") && span.style.fg == style.key.fg {
                     found_id = true;
                 }
-                if span.content.contains("123") && span.style.fg == Some(style.number) {
+                if span.content.contains("123") && span.style.fg == style.number.fg {
                     found_val = true;
                 }
-                if span.content.contains("true") && span.style.fg == Some(style.boolean) {
+                if span.content.contains("true") && span.style.fg == style.boolean.fg {
                     found_true = true;
                 }
             }
@@ -1589,11 +4547,67 @@ mod tests {
                 value: json!({"id": "1"}),
                 id: "1".to_string(),
                 item_type: "type".to_string(),
+                raw: None,
+                source: None,
             },
             data::IndexedItem {
                 value: json!({"id": "2"}),
                 id: "2".to_string(),
                 item_type: "type".to_string(),
+                raw: None,
+                source: None,
+            },
+        ];
+        let search_index = search_index::SearchIndex::build(&indexed_items);
+        let theme = theme::Theme::Dracula.config();
+
+        let mut app = AppState::new(
+            indexed_items,
+            search_index,
+            theme,
+            "v1".to_string(),
+            "v1".to_string(),
+            "v1".to_string(),
+            false,
+            2,
+            0.0,
+            std::path::PathBuf::from("/tmp/history.txt"),
+            None,
+        );
+
+        assert_eq!(app.list_state.selected(), Some(0));
+        handle_key_event(
+            &mut app,
+            KeyCode::Down,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.list_state.selected(), Some(1));
+        handle_key_event(
+            &mut app,
+            KeyCode::Up,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_handle_key_event_filtering() {
+        let indexed_items = vec![
+            data::IndexedItem {
+                value: json!({"id": "apple"}),
+                id: "apple".to_string(),
+                item_type: "fruit".to_string(),
+                raw: None,
+                source: None,
+            },
+            data::IndexedItem {
+                value: json!({"id": "banana"}),
+                id: "banana".to_string(),
+                item_type: "fruit".to_string(),
+                raw: None,
+                source: None,
             },
         ];
         let search_index = search_index::SearchIndex::build(&indexed_items);
@@ -1613,88 +4627,1195 @@ mod tests {
             None,
         );
 
-        assert_eq!(app.list_state.selected(), Some(0));
         handle_key_event(
             &mut app,
-            KeyCode::Down,
+            KeyCode::Char('/'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.input_mode, InputMode::Filtering);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('a'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.filter_text, "a");
+        app.flush_pending_filter();
+        assert_eq!(app.filtered_indices.len(), 2);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('p'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.filter_text, "ap");
+        app.flush_pending_filter();
+        assert_eq!(app.filtered_indices.len(), 1);
+        assert_eq!(app.filtered_indices[0], 0);
+    }
+
+    #[test]
+    fn test_filter_change_keeps_selected_id() {
+        let mut app = make_mouse_test_app(12);
+        app.list_state.select(Some(10));
+        app.refresh_details();
+        assert_eq!(app.get_selected_item().unwrap().id, "item_10");
+
+        // Narrowing to item_1 and item_10..item_11 keeps item_10 selected.
+        app.filter_text = "item_1".to_string();
+        app.update_filter();
+        assert_eq!(app.filtered_indices, vec![1, 10, 11]);
+        assert_eq!(app.get_selected_item().unwrap().id, "item_10");
+        assert_eq!(app.list_state.offset(), 0);
+
+        // Once it no longer matches, selection falls back to the first result.
+        app.filter_text = "item_11".to_string();
+        app.update_filter();
+        assert_eq!(app.list_state.selected(), Some(0));
+        assert_eq!(app.get_selected_item().unwrap().id, "item_11");
+    }
+
+    #[test]
+    fn test_filter_change_keeps_selected_row_in_view() {
+        let mut app = make_mouse_test_app(30);
+        app.list_state.select(Some(25));
+        *app.list_state.offset_mut() = 20;
+
+        // item_25 moves from index 25 to 6 but stays on the same visible row.
+        app.filter_text = "item_2".to_string();
+        app.update_filter();
+        assert_eq!(app.get_selected_item().unwrap().id, "item_25");
+        assert_eq!(app.list_state.selected(), Some(6));
+        assert_eq!(app.list_state.offset(), 1);
+    }
+
+    #[test]
+    fn test_hide_abstracts_toggle() {
+        let indexed_items = vec![
+            data::IndexedItem {
+                value: json!({"abstract": "gun_base", "type": "GUN"}),
+                id: String::new(),
+                item_type: "GUN".to_string(),
+                raw: None,
+                source: None,
+            },
+            data::IndexedItem {
+                value: json!({"id": "rifle", "type": "GUN", "copy-from": "gun_base"}),
+                id: "rifle".to_string(),
+                item_type: "GUN".to_string(),
+                raw: None,
+                source: None,
+            },
+        ];
+        let mut app = make_test_app(indexed_items);
+        app.update_filter();
+        assert_eq!(app.filtered_indices, vec![0, 1]);
+        assert!(app.cached_display[0].is_abstract);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('b'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert!(app.hide_abstracts);
+        assert_eq!(app.filtered_indices, vec![1]);
+        assert_eq!(app.cached_display.len(), 1);
+
+        app.toggle_hide_abstracts();
+        assert_eq!(app.filtered_indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_filter_debounce_commits_on_enter() {
+        let mut app = make_mouse_test_app(3);
+        app.focus_pane(FocusPane::Filter);
+        for c in "item_1".chars() {
+            handle_key_event(
+                &mut app,
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+                KeyEventKind::Press,
+            );
+        }
+
+        // Text and cursor update at once; the matcher waits for the debounce.
+        assert_eq!(app.filter_text, "item_1");
+        assert_eq!(app.filter_cursor, 6);
+        assert!(app.filter_pending_since.is_some());
+        assert_eq!(app.filtered_indices.len(), 3);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Enter,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert!(app.filter_pending_since.is_none());
+        assert_eq!(app.filtered_indices, vec![1]);
+        assert_eq!(app.focused_pane, FocusPane::List);
+    }
+
+    #[test]
+    fn test_handle_key_event_autofocus_filter() {
+        let indexed_items = vec![data::IndexedItem {
+            value: json!({"id": "1"}),
+            id: "1".to_string(),
+            item_type: "t".to_string(),
+            raw: None,
+            source: None,
+        }];
+        let search_index = search_index::SearchIndex::build(&indexed_items);
+        let theme = theme::Theme::Dracula.config();
+
+        let mut app = AppState::new(
+            indexed_items,
+            search_index,
+            theme,
+            "v1".to_string(),
+            "v1".to_string(),
+            "v1".to_string(),
+            false,
+            1,
+            0.0,
+            std::path::PathBuf::from("/tmp/history.txt"),
+            None,
+        );
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('t'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.input_mode, InputMode::Filtering);
+        assert_eq!(app.filter_text, "t");
+    }
+
+    #[test]
+    fn test_filter_history() {
+        let indexed_items = vec![data::IndexedItem {
+            value: json!({"id": "1"}),
+            id: "1".to_string(),
+            item_type: "t".to_string(),
+            raw: None,
+            source: None,
+        }];
+        let search_index = search_index::SearchIndex::build(&indexed_items);
+        let theme = theme::Theme::Dracula.config();
+        let history_path = std::path::PathBuf::from("/tmp/cbn_test_history.txt");
+        if history_path.exists() {
+            let _ = fs::remove_file(&history_path);
+        }
+
+        let mut app = AppState::new(
+            indexed_items,
+            search_index,
+            theme,
+            "v1".to_string(),
+            "v1".to_string(),
+            "v1".to_string(),
+            false,
+            1,
+            0.0,
+            history_path.clone(),
+            None,
+        );
+
+        app.input_mode = InputMode::Filtering;
+        app.filter_text = "test_query".to_string();
+        handle_key_event(
+            &mut app,
+            KeyCode::Enter,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+
+        assert_eq!(app.filter_history.len(), 1);
+        assert_eq!(app.filter_history[0], "test_query");
+
+        app.input_mode = InputMode::Filtering;
+        app.filter_text = "".to_string();
+        handle_key_event(
+            &mut app,
+            KeyCode::Up,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.filter_text, "test_query");
+
+        let _ = fs::remove_file(&history_path);
+    }
+
+    #[test]
+    fn test_focus_cycling() {
+        let mut app = make_mouse_test_app(1);
+        assert_eq!(app.focused_pane, FocusPane::List);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Tab,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.focused_pane, FocusPane::Details);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Tab,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.focused_pane, FocusPane::Filter);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Tab,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.focused_pane, FocusPane::List);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Tab,
+            KeyModifiers::SHIFT,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.focused_pane, FocusPane::Filter);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::BackTab,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.focused_pane, FocusPane::Details);
+    }
+
+    fn make_link_test_app() -> AppState {
+        let indexed_items = vec![
+            data::IndexedItem {
+                value: json!({"id": "rifle", "copy-from": "base", "flags": ["A", "B"]}),
+                id: "rifle".to_string(),
+                item_type: "t".to_string(),
+                raw: None,
+                source: None,
+            },
+            data::IndexedItem {
+                value: json!({"id": "base"}),
+                id: "base".to_string(),
+                item_type: "t".to_string(),
+                raw: None,
+                source: None,
+            },
+        ];
+        make_test_app(indexed_items)
+    }
+
+    fn focused_link_text(app: &AppState) -> Option<String> {
+        let id = app.focused_link_id?;
+        app.details_annotated
+            .iter()
+            .flatten()
+            .find(|s| s.span_id == Some(id))
+            .map(|s| s.span.content.to_string())
+    }
+
+    #[test]
+    fn test_details_tab_cycles_links() {
+        let mut app = make_link_test_app();
+        app.focus_pane(FocusPane::Details);
+        let press = |app: &mut AppState, code: KeyCode, modifiers: KeyModifiers| {
+            handle_key_event(app, code, modifiers, KeyEventKind::Press)
+        };
+
+        // "id" is excluded, so the first link is the copy-from value.
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(focused_link_text(&app).as_deref(), Some("\"base\""));
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(focused_link_text(&app).as_deref(), Some("\"A\""));
+        press(&mut app, KeyCode::BackTab, KeyModifiers::NONE);
+        assert_eq!(focused_link_text(&app).as_deref(), Some("\"base\""));
+
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(focused_link_text(&app).as_deref(), Some("\"B\""));
+        assert_eq!(app.focused_pane, FocusPane::Details);
+
+        // Past the last link, Tab leaves the pane as usual.
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.focused_link_id, None);
+        assert_eq!(app.focused_pane, FocusPane::Filter);
+    }
+
+    #[test]
+    fn test_ctrl_o_opens_selected_item_url() {
+        let mut app = make_link_test_app();
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('o'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        match app.pending_action.take() {
+            Some(AppAction::OpenInBrowser(url)) => {
+                assert_eq!(url, "https://cataclysmbn-guide.com/v1/t/rifle");
+            }
+            other => panic!("unexpected action: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_letters_start_filtering_unless_auto_filter_is_off() {
+        let type_char = |app: &mut AppState, c: char| {
+            handle_key_event(
+                app,
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+                KeyEventKind::Press,
+            )
+        };
+
+        let mut app = make_link_test_app();
+        app.focus_pane(FocusPane::List);
+        let filter = app.filter_text.clone();
+        type_char(&mut app, 'g');
+        assert_eq!(app.input_mode, InputMode::Filtering);
+        assert_eq!(app.filter_text, format!("{}g", filter));
+
+        let mut app = make_link_test_app();
+        app.auto_filter = false;
+        app.focus_pane(FocusPane::List);
+        type_char(&mut app, 'g');
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.focused_pane, FocusPane::List);
+        assert_eq!(app.filter_text, filter);
+
+        type_char(&mut app, '/');
+        assert_eq!(app.input_mode, InputMode::Filtering);
+        type_char(&mut app, 'g');
+        assert_eq!(app.filter_text, format!("{}g", filter));
+    }
+
+    #[test]
+    fn test_up_down_wrap_only_when_enabled() {
+        let arrow = |app: &mut AppState, code: KeyCode| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press)
+        };
+
+        for wrap in [false, true] {
+            let mut app = make_link_test_app();
+            app.wrap_navigation = wrap;
+            app.focus_pane(FocusPane::List);
+            assert_eq!(app.filtered_indices.len(), 2);
+
+            app.list_state.select(Some(0));
+            arrow(&mut app, KeyCode::Up);
+            assert_eq!(app.list_state.selected(), Some(if wrap { 1 } else { 0 }));
+
+            app.list_state.select(Some(1));
+            arrow(&mut app, KeyCode::Down);
+            assert_eq!(app.list_state.selected(), Some(if wrap { 0 } else { 1 }));
+
+            // Away from the ends both behave the same.
+            app.list_state.select(Some(0));
+            arrow(&mut app, KeyCode::Down);
+            assert_eq!(app.list_state.selected(), Some(1));
+        }
+    }
+
+    #[test]
+    fn test_shift_arrows_select_and_y_copies() {
+        let mut app = make_link_test_app();
+        let style = theme::Theme::Dracula.config().json_style;
+        let annotated = ui::highlight_json_annotated("{\n  \"id\": \"rifle\"\n}", &style);
+        (app.details_wrapped_annotated, app.details_wrap_continued) =
+            ui::wrap_annotated_lines(&annotated, 40);
+        app.focus_pane(FocusPane::Details);
+        let key = |app: &mut AppState, code: KeyCode, modifiers: KeyModifiers| {
+            handle_key_event(app, code, modifiers, KeyEventKind::Press)
+        };
+
+        key(&mut app, KeyCode::Down, KeyModifiers::SHIFT);
+        for _ in 0..3 {
+            key(&mut app, KeyCode::Right, KeyModifiers::SHIFT);
+        }
+        let selection = app.details_selection.unwrap();
+        assert_eq!(selection.anchor, ui::TextPos { line: 0, column: 0 });
+        assert_eq!(selection.cursor, ui::TextPos { line: 1, column: 3 });
+
+        key(&mut app, KeyCode::Char('y'), KeyModifiers::NONE);
+        match app.pending_action.take() {
+            Some(AppAction::CopyToClipboard(text)) => assert_eq!(text, "{\n  \"i"),
+            other => panic!("unexpected action: {:?}", other),
+        }
+        assert_eq!(app.focused_pane, FocusPane::Details);
+
+        key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(app.details_selection.is_none());
+    }
+
+    #[test]
+    fn test_ctrl_e_opens_source_file_or_explains() {
+        let mut app = make_link_test_app();
+        app.input_mode = InputMode::Normal;
+        let ctrl_e = |app: &mut AppState| {
+            handle_key_event(
+                app,
+                KeyCode::Char('e'),
+                KeyModifiers::CONTROL,
+                KeyEventKind::Press,
+            )
+        };
+
+        ctrl_e(&mut app);
+        assert!(app.pending_action.is_none());
+        assert!(app.status_message.is_some());
+        // Any later key clears the message.
+        handle_key_event(
+            &mut app,
+            KeyCode::Down,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert!(app.status_message.is_none());
+
+        let location = data::SourceLocation {
+            path: "data/json/items/gun.json".into(),
+            line: 12,
+        };
+        for item in &mut app.indexed_items {
+            item.source = Some(location.clone());
+        }
+        ctrl_e(&mut app);
+        match app.pending_action.take() {
+            Some(AppAction::OpenInEditor(opened)) => assert_eq!(opened, location),
+            other => panic!("unexpected action: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_brackets_jump_between_type_groups() {
+        let mut app = make_mouse_test_app(7);
+        for (item, item_type) in app
+            .indexed_items
+            .iter_mut()
+            .zip(["AMMO", "AMMO", "GUN", "GUN", "GUN", "MONSTER", "MONSTER"])
+        {
+            item.item_type = item_type.to_string();
+        }
+        app.focus_pane(FocusPane::List);
+        let press = |app: &mut AppState, c| {
+            handle_key_event(
+                app,
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+                KeyEventKind::Press,
+            );
+            app.list_state.selected()
+        };
+
+        assert_eq!(press(&mut app, ']'), Some(2));
+        assert_eq!(press(&mut app, ']'), Some(5));
+        // No group follows the last one.
+        assert_eq!(press(&mut app, ']'), Some(5));
+
+        // From inside a group, `[` goes to the top of the previous group.
+        app.list_state.select(Some(6));
+        assert_eq!(press(&mut app, '['), Some(2));
+        app.list_state.select(Some(3));
+        assert_eq!(press(&mut app, '['), Some(0));
+        assert_eq!(press(&mut app, '['), Some(0));
+        assert_eq!(app.get_selected_item().unwrap().id, "item_0");
+
+        // Only listed rows count: with the guns filtered out, AMMO is followed by MONSTER.
+        app.filtered_indices = vec![0, 1, 5, 6];
+        app.list_state.select(Some(1));
+        assert_eq!(press(&mut app, ']'), Some(2));
+        assert_eq!(app.get_selected_item().unwrap().id, "item_5");
+    }
+
+    #[test]
+    fn test_version_entries_tag_stable_and_nightly_builds() {
+        let build = |number: &str, prerelease| data::BuildInfo {
+            build_number: number.to_string(),
+            tag_name: number.to_string(),
+            prerelease,
+            created_at: String::new(),
+        };
+        let entries = build_version_entries(vec![
+            build("2024-06-12", true),
+            build("v0.9.1", false),
+            build("nightly", true),
+        ]);
+        let kinds: Vec<(&str, Option<BuildKind>)> = entries
+            .iter()
+            .map(|entry| (entry.label.as_str(), entry.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("stable", None),
+                ("nightly", None),
+                ("2024-06-12", Some(BuildKind::Nightly)),
+                ("v0.9.1", Some(BuildKind::Stable)),
+            ]
+        );
+
+        let mut app = make_mouse_test_app(1);
+        app.version_entries = entries;
+        app.version_list_state.select(Some(0));
+        app.show_version_picker = true;
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("2024-06-12 (nightly)"));
+        assert!(screen.contains("v0.9.1 (stable)"));
+    }
+
+    #[test]
+    fn test_version_picker_pages_and_jumps_to_ends() {
+        let mut app = make_mouse_test_app(1);
+        app.version_entries = (0..25)
+            .map(|i| VersionEntry {
+                label: format!("build {}", i),
+                version: format!("v{}", i),
+                detail: None,
+                kind: None,
+            })
+            .collect();
+        app.version_list_state.select(Some(0));
+        app.show_version_picker = true;
+        let mut press = |code| {
+            handle_key_event(&mut app, code, KeyModifiers::NONE, KeyEventKind::Press);
+            app.version_list_state.selected()
+        };
+
+        assert_eq!(press(KeyCode::PageDown), Some(10));
+        assert_eq!(press(KeyCode::PageDown), Some(20));
+        assert_eq!(press(KeyCode::PageDown), Some(24));
+        assert_eq!(press(KeyCode::Down), Some(24));
+        assert_eq!(press(KeyCode::PageUp), Some(14));
+        assert_eq!(press(KeyCode::Home), Some(0));
+        assert_eq!(press(KeyCode::Up), Some(0));
+        assert_eq!(press(KeyCode::End), Some(24));
+
+        // The clamped selection is the one Enter switches to.
+        press(KeyCode::Enter);
+        assert!(matches!(
+            app.pending_action,
+            Some(AppAction::SwitchVersion(ref version)) if version == "v24"
+        ));
+    }
+
+    #[test]
+    fn test_version_picker_filters_by_typed_text() {
+        let mut app = make_mouse_test_app(1);
+        app.version_entries = ["stable", "2024-05-01 nightly", "2024-06-12 nightly", "0.G"]
+            .iter()
+            .map(|label| VersionEntry {
+                label: label.to_string(),
+                version: label.to_string(),
+                detail: None,
+                kind: None,
+            })
+            .collect();
+        app.version_list_state.select(Some(3));
+        app.show_version_picker = true;
+        let press = |app: &mut AppState, code| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press);
+        };
+        let labels = |app: &AppState| -> Vec<String> {
+            app.visible_version_entries()
+                .iter()
+                .map(|entry| entry.label.clone())
+                .collect()
+        };
+
+        for c in "2024-0".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert_eq!(app.version_picker_query, "2024-0");
+        assert_eq!(labels(&app), ["2024-05-01 nightly", "2024-06-12 nightly"]);
+        assert_eq!(app.version_list_state.selected(), Some(0));
+
+        press(&mut app, KeyCode::Char('6'));
+        assert_eq!(labels(&app), ["2024-06-12 nightly"]);
+        press(&mut app, KeyCode::End);
+        assert_eq!(app.version_list_state.selected(), Some(0));
+
+        // Enter switches to the selected match, not the unfiltered row.
+        press(&mut app, KeyCode::Enter);
+        assert!(matches!(
+            app.pending_action,
+            Some(AppAction::SwitchVersion(ref version)) if version == "2024-06-12 nightly"
+        ));
+
+        press(&mut app, KeyCode::Char('x'));
+        assert!(labels(&app).is_empty());
+        assert_eq!(app.version_list_state.selected(), None);
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(labels(&app).len(), 1);
+
+        // Esc clears the text first, then closes the picker.
+        press(&mut app, KeyCode::Esc);
+        assert!(app.version_picker_query.is_empty());
+        assert_eq!(labels(&app).len(), 4);
+        assert!(app.show_version_picker);
+        press(&mut app, KeyCode::Esc);
+        assert!(!app.show_version_picker);
+    }
+
+    #[test]
+    fn test_type_picker_lists_distinct_types() {
+        let indexed_items = ["GUN", "AMMO", "GUN", "MONSTER", "GUN"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| data::IndexedItem {
+                value: json!({"id": format!("item_{}", i), "type": t}),
+                id: format!("item_{}", i),
+                item_type: t.to_string(),
+                raw: None,
+                source: None,
+            })
+            .collect::<Vec<_>>();
+        let mut app = make_test_app(indexed_items);
+        let press = |app: &mut AppState, code: KeyCode, modifiers: KeyModifiers| {
+            handle_key_event(app, code, modifiers, KeyEventKind::Press)
+        };
+
+        press(&mut app, KeyCode::Char('p'), KeyModifiers::CONTROL);
+        assert!(app.show_type_picker);
+        assert_eq!(
+            app.type_entries,
+            vec![
+                ("AMMO".to_string(), 1),
+                ("GUN".to_string(), 3),
+                ("MONSTER".to_string(), 1),
+            ]
+        );
+
+        // Typing narrows the list in place instead of going to the filter.
+        press(&mut app, KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(app.visible_type_entries().len(), 1);
+        assert_eq!(app.filter_text, "");
+
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(!app.show_type_picker);
+        assert_eq!(app.filter_text, "t:'MONSTER'");
+        assert_eq!(app.filtered_indices.len(), 1);
+    }
+
+    #[test]
+    fn test_details_enter_follows_focused_link() {
+        let mut app = make_link_test_app();
+        app.focus_pane(FocusPane::Details);
+        handle_key_event(
+            &mut app,
+            KeyCode::Tab,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        handle_key_event(
+            &mut app,
+            KeyCode::Enter,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.filter_text, "copy-from:'base'");
+        assert_eq!(app.focused_pane, FocusPane::Filter);
+
+        let mut app = make_link_test_app();
+        app.focus_pane(FocusPane::Details);
+        handle_key_event(
+            &mut app,
+            KeyCode::Tab,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        handle_key_event(
+            &mut app,
+            KeyCode::Enter,
+            KeyModifiers::ALT,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.filter_text, "i:'base'");
+        assert_eq!(app.filtered_indices, vec![1]);
+        // Selection changed, so the focused link resets.
+        assert_eq!(app.focused_link_id, None);
+    }
+
+    #[test]
+    fn test_format_transfer() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.5 MB");
+
+        // 1 MB of 4 MB at 512 KB/s leaves 6 seconds.
+        assert_eq!(
+            format_transfer(1024 * 1024, Some(4 * 1024 * 1024), 512.0 * 1024.0),
+            "1.0 MB / 4.0 MB • 512.0 KB/s • ETA 0:06"
+        );
+        assert_eq!(
+            format_transfer(0, Some(1024), 0.0),
+            "0 B / 1.0 KB • 0 B/s • ETA --:--"
+        );
+        // Unknown total: no ETA.
+        assert_eq!(format_transfer(2048, None, 1024.0), "2.0 KB • 1.0 KB/s");
+    }
+
+    #[test]
+    fn test_retry_backoff_schedule() {
+        let schedule: Vec<Duration> = (0..RETRY_ATTEMPTS).map(retry_backoff).collect();
+        assert_eq!(
+            schedule,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_millis(1000),
+            ]
+        );
+        assert_eq!(retry_backoff(4), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_download_without_length_is_indeterminate() {
+        let mut app = make_mouse_test_app(1);
+        app.start_progress("Loading data", &["Downloading", "Parsing"]);
+        let progress = |total| {
+            progress_ratio(data::DownloadProgress {
+                downloaded: 500,
+                total,
+            })
+        };
+        assert_eq!(progress(Some(1000)), Some(0.5));
+        assert_eq!(progress(Some(0)), None);
+
+        let message = |ratio| loader::LoadMessage::Progress {
+            stage: "Downloading",
+            ratio,
+            detail: None,
+        };
+        assert!(app.apply_load_message(message(progress(None))).is_none());
+        let stage = &app.progress_stages[0];
+        assert!(stage.indeterminate && !stage.done);
+        assert_eq!(stage.ratio, 0.0);
+
+        // Frames advance with each draw while the bar is shown.
+        app.show_progress = true;
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        assert_eq!(app.progress_frame, 2);
+
+        app.finish_stage("Downloading");
+        let stage = &app.progress_stages[0];
+        assert!(!stage.indeterminate && stage.done);
+    }
+
+    #[test]
+    fn test_background_load_keeps_browsing_then_installs() {
+        let path = crate::test_dir("background_load").join("all.json");
+        fs::write(
+            &path,
+            r#"{"build_number": "v2", "data": [
+                {"type": "t", "id": "rifle"},
+                {"type": "t", "id": "pistol"},
+                {"type": "t", "id": "knife"}
+            ]}"#,
+        )
+        .unwrap();
+        let background_load = |path: &std::path::Path, compare: bool| {
+            let source = loader::LoadSource::File(path.to_string_lossy().into_owned());
+            BackgroundLoad {
+                task: loader::LoadTask::spawn(source),
+                version: "v2".to_string(),
+                data_source: "test".to_string(),
+                compare,
+            }
+        };
+        let wait = |app: &mut AppState| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while app.background_load.is_some() && Instant::now() < deadline {
+                app.pump_background_load();
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        };
+
+        // Esc drops the load; the current dataset stays.
+        let mut app = make_link_test_app();
+        app.start_progress("Loading v2", &["Parsing", "Indexing"]);
+        app.show_progress = false;
+        app.background_load = Some(background_load(&path, false));
+        handle_key_event(
+            &mut app,
+            KeyCode::Esc,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert!(app.background_load.is_none());
+        assert!(app.progress_stages.is_empty());
+        app.pump_background_load();
+        assert_eq!(app.total_items, 2);
+
+        // A version loaded for comparison leaves the current dataset alone.
+        app.start_progress("Loading v2", &["Parsing", "Indexing"]);
+        app.show_progress = false;
+        app.background_load = Some(background_load(&path, true));
+        // The cache it may be writing to can't be cleared meanwhile.
+        app.clear_download_cache();
+        app.clear_download_cache();
+        assert!(!app.confirm_clear_cache);
+        assert!(app.cache_clear_result.is_some());
+        wait(&mut app);
+        assert_eq!(app.compare_version(), Some("v2"));
+        assert_eq!(app.total_items, 2);
+
+        // Left alone, it replaces the dataset once done.
+        app.start_progress("Loading v2", &["Parsing", "Indexing"]);
+        app.show_progress = false;
+        app.background_load = Some(background_load(&path, false));
+        app.filter_text = "rifle".to_string();
+        wait(&mut app);
+        assert!(app.background_load.is_none());
+        assert!(app.load_failure.is_none());
+        assert!(app.progress_stages.is_empty());
+        assert_eq!(app.total_items, 3);
+        assert_eq!(app.game_version_key, "v2");
+        assert_eq!(app.data_source, "test");
+        assert_eq!(app.filter_text, "rifle");
+    }
+
+    #[test]
+    fn test_reload_targets_the_current_version_when_not_in_source_mode() {
+        let loaded = || loader::LoadedData {
+            indexed_items: Vec::new(),
+            search_index: search_index::SearchIndex::new(),
+            total_items: 0,
+            index_time_ms: 0.0,
+            build: data::BuildInfo {
+                build_number: "2024-06-12".to_string(),
+                tag_name: "2024-06-12".to_string(),
+                prerelease: true,
+                created_at: String::new(),
+            },
+            source_warnings: None,
+            warning: None,
+        };
+        let ctrl_r = |app: &mut AppState| {
+            app.pending_action = None;
+            handle_key_event(
+                app,
+                KeyCode::Char('r'),
+                KeyModifiers::CONTROL,
+                KeyEventKind::Press,
+            );
+        };
+        let mut app = make_mouse_test_app(1);
+
+        app.install_loaded_data(loaded(), "nightly", None, "download".to_string());
+        ctrl_r(&mut app);
+        assert!(matches!(
+            app.pending_action,
+            Some(AppAction::Reload(ref version)) if version == "nightly"
+        ));
+
+        // A file can't be fetched again.
+        app.install_loaded_data(loaded(), "nightly", Some("all.json"), "file".to_string());
+        ctrl_r(&mut app);
+        assert!(app.pending_action.is_none());
+
+        app.source_dir = Some("data".to_string());
+        app.install_loaded_data(loaded(), "local", None, "source dir".to_string());
+        ctrl_r(&mut app);
+        assert!(matches!(
+            app.pending_action,
+            Some(AppAction::Reload(ref version)) if version == "local"
+        ));
+    }
+
+    #[test]
+    fn test_loaded_version_is_remembered_per_source() {
+        let loaded = || loader::LoadedData {
+            indexed_items: Vec::new(),
+            search_index: search_index::SearchIndex::new(),
+            total_items: 0,
+            index_time_ms: 0.0,
+            build: data::BuildInfo {
+                build_number: "2024-06-12".to_string(),
+                tag_name: "2024-06-12".to_string(),
+                prerelease: true,
+                created_at: String::new(),
+            },
+            source_warnings: None,
+            warning: None,
+        };
+        let mut app = make_mouse_test_app(1);
+        app.versions_by_source
+            .insert("dda".to_string(), "stable".to_string());
+
+        app.install_loaded_data(loaded(), "2024-06-12", None, "download".to_string());
+        // Files and source dirs aren't versions to reopen.
+        app.install_loaded_data(loaded(), "local", Some("all.json"), "file".to_string());
+        assert_eq!(
+            app.versions_by_source,
+            BTreeMap::from([
+                ("cbn".to_string(), "2024-06-12".to_string()),
+                ("dda".to_string(), "stable".to_string()),
+            ])
+        );
+
+        let path = crate::test_dir("version_memory").join("session.toml");
+        session::Session {
+            versions: app.versions_by_source.clone(),
+            source: Some("dda".to_string()),
+            ..Default::default()
+        }
+        .save(&path)
+        .unwrap();
+        // Switching back from dda reopens the version last loaded from cbn.
+        let restored = session::Session::load(&path);
+        assert_eq!(
+            restored.version_after_switch_to(data::DEFAULT_SOURCE_NAME),
+            Some("2024-06-12")
+        );
+    }
+
+    #[test]
+    fn test_load_failure_modal_keys() {
+        let mut app = make_mouse_test_app(3);
+        app.load_failure = Some(LoadFailure {
+            version: "nightly".to_string(),
+            message: "connection reset".to_string(),
+            compare: false,
+        });
+
+        // Other keys are swallowed while the modal is up.
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('x'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert!(app.filter_text.is_empty());
+        assert!(app.pending_action.is_none());
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('r'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert!(matches!(app.pending_action, Some(AppAction::RetryLoad)));
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Esc,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert!(app.load_failure.is_none());
+    }
+
+    #[test]
+    fn test_transfer_rate_uses_recent_window() {
+        let start = Instant::now();
+        let mut rate = TransferRate::default();
+        assert_eq!(rate.update(start, 0), 0.0);
+        assert_eq!(rate.update(start + Duration::from_secs(1), 1000), 1000.0);
+        // A burst after a stall is measured against the recent window only.
+        rate.update(start + Duration::from_secs(5), 1000);
+        let speed = rate.update(start + Duration::from_secs(6), 5000);
+        assert_eq!(speed, 4000.0);
+    }
+
+    #[test]
+    fn test_context_aware_navigation() {
+        let mut app = make_mouse_test_app(20);
+        app.list_area = Some(Rect::new(0, 0, 20, 10)); // Height 10
+        app.focused_pane = FocusPane::List;
+
+        // PageDown in List
+        handle_key_event(
+            &mut app,
+            KeyCode::PageDown,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.list_state.selected(), Some(10));
+
+        // Home in List
+        handle_key_event(
+            &mut app,
+            KeyCode::Home,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        // Switch focus to Details
+        app.focused_pane = FocusPane::Details;
+        assert_eq!(app.details_scroll_state.offset().y, 0);
+
+        // Down arrow in Details (scrolls)
+        handle_key_event(
+            &mut app,
+            KeyCode::Down,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.details_scroll_state.offset().y, 1);
+
+        // Home in Details (resets)
+        handle_key_event(
+            &mut app,
+            KeyCode::Home,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.details_scroll_state.offset().y, 0);
+
+        // Down arrow in Details (scrolls back)
+        handle_key_event(
+            &mut app,
+            KeyCode::Down,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.details_scroll_state.offset().y, 1);
+
+        // End in Details (scrolls to bottom)
+        handle_key_event(
+            &mut app,
+            KeyCode::End,
             KeyModifiers::NONE,
             KeyEventKind::Press,
         );
-        assert_eq!(app.list_state.selected(), Some(1));
+        // ScrollViewState::scroll_to_bottom might not move if no viewport is set,
+        // but it sets a flag or something? Actually, let's just skip this one too if it fails.
+        // Actually, if it's the same as page down, it might do nothing.
+    }
+
+    #[test]
+    fn test_input_shortcuts() {
+        let mut app = make_mouse_test_app(1);
+        app.focus_pane(FocusPane::Filter);
+        app.filter_text = "hello world".to_string();
+        app.filter_cursor = 11;
+
+        // Ctrl+A (Start of line)
         handle_key_event(
             &mut app,
-            KeyCode::Up,
-            KeyModifiers::NONE,
+            KeyCode::Char('a'),
+            KeyModifiers::CONTROL,
             KeyEventKind::Press,
         );
-        assert_eq!(app.list_state.selected(), Some(0));
+        assert_eq!(app.filter_cursor, 0);
+
+        // Ctrl+E (End of line)
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('e'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.filter_cursor, 11);
+
+        // Ctrl+W (Delete word)
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('w'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.filter_text, "hello ");
+        assert_eq!(app.filter_cursor, 6);
+
+        // Ctrl+U (Clear filter)
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('u'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.filter_text, "");
+        assert_eq!(app.filter_cursor, 0);
     }
 
     #[test]
-    fn test_handle_key_event_filtering() {
-        let indexed_items = vec![
-            data::IndexedItem {
-                value: json!({"id": "apple"}),
-                id: "apple".to_string(),
-                item_type: "fruit".to_string(),
-            },
-            data::IndexedItem {
-                value: json!({"id": "banana"}),
-                id: "banana".to_string(),
-                item_type: "fruit".to_string(),
-            },
-        ];
-        let search_index = search_index::SearchIndex::build(&indexed_items);
-        let theme = theme::Theme::Dracula.config();
+    fn test_esc_behavior() {
+        let mut app = make_mouse_test_app(1);
 
-        let mut app = AppState::new(
-            indexed_items,
-            search_index,
-            theme,
-            "v1".to_string(),
-            "v1".to_string(),
-            "v1".to_string(),
-            false,
-            2,
-            0.0,
-            std::path::PathBuf::from("/tmp/history.txt"),
-            None,
+        // Esc in Filtering focuses List (no longer clears text)
+        app.focus_pane(FocusPane::Filter);
+        app.filter_text = "abc".to_string();
+        handle_key_event(
+            &mut app,
+            KeyCode::Esc,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
         );
+        assert_eq!(app.filter_text, "abc");
+        assert_eq!(app.focused_pane, FocusPane::List);
 
+        // Esc in Normal Mode (List focused) does nothing (it no longer quits)
+        app.focus_pane(FocusPane::List);
+        app.should_quit = false;
         handle_key_event(
             &mut app,
-            KeyCode::Char('/'),
+            KeyCode::Esc,
             KeyModifiers::NONE,
             KeyEventKind::Press,
         );
-        assert_eq!(app.input_mode, InputMode::Filtering);
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_quit_behavior() {
+        let mut app = make_mouse_test_app(1);
 
+        // 'q' in Normal Mode quits
+        app.focus_pane(FocusPane::List);
+        app.should_quit = false;
         handle_key_event(
             &mut app,
-            KeyCode::Char('a'),
+            KeyCode::Char('q'),
             KeyModifiers::NONE,
             KeyEventKind::Press,
         );
-        assert_eq!(app.filter_text, "a");
-        assert_eq!(app.filtered_indices.len(), 2);
+        assert!(app.should_quit);
 
+        // 'q' in Filtering Mode adds 'q' to filter
+        app.focus_pane(FocusPane::Filter);
+        app.filter_text = "".to_string();
+        app.should_quit = false;
         handle_key_event(
             &mut app,
-            KeyCode::Char('p'),
+            KeyCode::Char('q'),
             KeyModifiers::NONE,
             KeyEventKind::Press,
         );
-        assert_eq!(app.filter_text, "ap");
-        assert_eq!(app.filtered_indices.len(), 1);
-        assert_eq!(app.filtered_indices[0], 0);
+        assert!(!app.should_quit);
+        assert_eq!(app.filter_text, "q");
     }
 
     #[test]
-    fn test_handle_key_event_autofocus_filter() {
+    fn test_handle_key_event_ignores_release_kind() {
         let indexed_items = vec![data::IndexedItem {
-            value: json!({"id": "1"}),
-            id: "1".to_string(),
-            item_type: "t".to_string(),
+            value: json!({"id": "apple"}),
+            id: "apple".to_string(),
+            item_type: "fruit".to_string(),
+            raw: None,
+            source: None,
         }];
         let search_index = search_index::SearchIndex::build(&indexed_items);
         let theme = theme::Theme::Dracula.config();
@@ -1715,28 +5836,26 @@ mod tests {
 
         handle_key_event(
             &mut app,
-            KeyCode::Char('t'),
+            KeyCode::Char('a'),
             KeyModifiers::NONE,
-            KeyEventKind::Press,
+            KeyEventKind::Release,
         );
-        assert_eq!(app.input_mode, InputMode::Filtering);
-        assert_eq!(app.filter_text, "t");
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.filter_text.is_empty());
     }
 
     #[test]
-    fn test_filter_history() {
+    fn test_refresh_details_populates_annotated() {
         let indexed_items = vec![data::IndexedItem {
             value: json!({"id": "1"}),
             id: "1".to_string(),
             item_type: "t".to_string(),
+            raw: None,
+            source: None,
         }];
         let search_index = search_index::SearchIndex::build(&indexed_items);
         let theme = theme::Theme::Dracula.config();
-        let history_path = std::path::PathBuf::from("/tmp/cbn_test_history.txt");
-        if history_path.exists() {
-            let _ = fs::remove_file(&history_path);
-        }
-
         let mut app = AppState::new(
             indexed_items,
             search_index,
@@ -1747,318 +5866,784 @@ mod tests {
             false,
             1,
             0.0,
-            history_path.clone(),
+            std::path::PathBuf::from("/tmp/history.txt"),
             None,
         );
 
-        app.input_mode = InputMode::Filtering;
-        app.filter_text = "test_query".to_string();
-        handle_key_event(
-            &mut app,
-            KeyCode::Enter,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        app.refresh_details();
+        assert!(!app.details_annotated.is_empty());
+
+        // Check content structure - "id" should be present in some line metadata
+        let found_id = app
+            .details_annotated
+            .iter()
+            .any(|line| line.iter().any(|s| s.span.content == "\"id\""));
+        assert!(found_id);
+    }
+
+    #[test]
+    fn test_toggle_raw_json_changes_rendered_lines() {
+        let raw = r#"{"type":"t","id":"1","flags":["A","B"]}"#;
+        let indexed_items = vec![data::IndexedItem {
+            value: serde_json::from_str(raw).unwrap(),
+            id: "1".to_string(),
+            item_type: "t".to_string(),
+            raw: Some(raw.into()),
+            source: None,
+        }];
+        let mut app = make_test_app(indexed_items);
+
+        let pretty_lines = app.details_annotated.len();
+        assert!(pretty_lines > 1);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert!(app.show_raw_json);
+        assert_eq!(app.details_annotated.len(), 1);
+
+        // The toggle sticks across selection refreshes.
+        app.refresh_details();
+        assert_eq!(app.details_annotated.len(), 1);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.details_annotated.len(), pretty_lines);
+    }
+
+    #[test]
+    fn test_table_view_lines_filter_by_path() {
+        let mut app = make_link_test_app();
+        app.list_state.select(Some(0));
+        app.refresh_details();
+        assert_eq!(app.get_selected_item().unwrap().id, "rifle");
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('t'),
+            KeyModifiers::ALT,
+            KeyEventKind::Press,
+        );
+        assert!(app.show_table);
+        let lines: Vec<String> = app
+            .details_annotated
+            .iter()
+            .map(|line| line.iter().map(|span| span.span.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "id = \"rifle\"",
+                "copy-from = \"base\"",
+                "flags[0] = \"A\"",
+                "flags[1] = \"B\""
+            ]
+        );
+
+        let value = &app.details_annotated[3][2];
+        let (path, id) = (
+            link_path(value, &app.excluded_fields).unwrap().to_string(),
+            value.span_id.unwrap(),
+        );
+        follow_link(&mut app, &path, id, false);
+        assert_eq!(app.filter_text, "flags:'B'");
+    }
+
+    #[test]
+    fn test_excluded_fields_decide_which_values_are_links() {
+        let mut app = make_link_test_app();
+        app.refresh_details();
+        let link_paths = |app: &AppState| -> Vec<String> {
+            app.details_link_ids()
+                .into_iter()
+                .filter_map(|id| {
+                    app.details_annotated
+                        .iter()
+                        .flatten()
+                        .find(|span| span.span_id == Some(id))
+                        .and_then(|span| span.key_context.as_deref().map(str::to_string))
+                })
+                .collect()
+        };
+        assert_eq!(link_paths(&app), ["copy-from", "flags", "flags"]);
+
+        // Someone who wants ids clickable but not flags.
+        app.excluded_fields = vec!["flags".to_string()];
+        assert_eq!(link_paths(&app), ["id", "copy-from"]);
+    }
+
+    #[test]
+    fn test_toggle_resolved_shows_inherited_fields() {
+        let indexed_items = vec![
+            data::IndexedItem {
+                value: json!({"type": "GUN", "abstract": "base", "range": 20}),
+                id: String::new(),
+                item_type: "GUN".to_string(),
+                raw: None,
+                source: None,
+            },
+            data::IndexedItem {
+                value: json!({"type": "GUN", "id": "rifle", "copy-from": "base"}),
+                id: "rifle".to_string(),
+                item_type: "GUN".to_string(),
+                raw: None,
+                source: None,
+            },
+        ];
+        let mut app = make_test_app(indexed_items);
+        app.list_state.select(Some(1));
+        app.refresh_details();
+        let shows_range = |app: &AppState| {
+            app.details_annotated
+                .iter()
+                .flatten()
+                .any(|s| s.span.content == "\"range\"")
+        };
+        assert!(!shows_range(&app));
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('f'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert!(app.show_resolved);
+        assert!(shows_range(&app));
+
+        // Raw source and resolved view are exclusive.
+        app.toggle_raw_json();
+        assert!(!app.show_resolved);
+        assert!(!shows_range(&app));
+    }
+
+    #[test]
+    fn test_type_ahead_selects_by_name_prefix() {
+        let indexed_items: Vec<data::IndexedItem> =
+            ["apple", "apricot", "banana", "blueberry", "cherry"]
+                .into_iter()
+                .map(|id| data::IndexedItem {
+                    value: json!({"id": id}),
+                    id: id.to_string(),
+                    item_type: "t".to_string(),
+                    raw: None,
+                    source: None,
+                })
+                .collect();
+        let mut app = make_test_app(indexed_items);
+        app.update_filter();
+        app.list_state.select(Some(0));
+        let press = |app: &mut AppState, code: KeyCode| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press)
+        };
+
+        press(&mut app, KeyCode::Char('\''));
+        assert!(app.type_ahead.is_some());
+        press(&mut app, KeyCode::Char('B'));
+        assert_eq!(app.list_state.selected(), Some(2));
+        press(&mut app, KeyCode::Char('l'));
+        assert_eq!(app.list_state.selected(), Some(3));
+        // A shorter prefix that still matches keeps the selection.
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(app.list_state.selected(), Some(3));
+        // `'` again moves to the next match, wrapping around.
+        press(&mut app, KeyCode::Char('\''));
+        assert_eq!(app.list_state.selected(), Some(2));
+        press(&mut app, KeyCode::Char('x'));
+        assert_eq!(app.list_state.selected(), Some(2));
+        assert!(!app.type_ahead.as_ref().unwrap().matched);
+
+        // None of it touched the filter.
+        assert!(app.filter_text.is_empty());
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        // After a pause, letters go to the filter again.
+        app.type_ahead.as_mut().unwrap().last_key -= TYPE_AHEAD_TIMEOUT;
+        press(&mut app, KeyCode::Char('c'));
+        assert!(app.type_ahead.is_none());
+        assert_eq!(app.filter_text, "c");
+        assert_eq!(app.input_mode, InputMode::Filtering);
+    }
+
+    #[test]
+    fn test_push_history_moves_duplicates_to_end() {
+        let mut app = make_mouse_test_app(1);
+        app.filter_history.clear();
+        for entry in ["t:gun", "zombie", "c:food"] {
+            app.push_history(entry.to_string());
+        }
+        app.push_history("t:gun".to_string());
+        assert_eq!(app.filter_history, vec!["zombie", "c:food", "t:gun"]);
+    }
+
+    #[test]
+    fn test_failed_history_save_is_retried() {
+        let dir = crate::test_dir("history_retry");
+        fs::create_dir_all(dir.join("history.txt")).unwrap();
+        let mut app = make_mouse_test_app(1);
+        app.history_path = dir.join("history.txt");
+        app.filter_history = vec!["t:gun".to_string()];
+
+        // The path is a directory, so the save fails and is remembered.
+        app.save_history();
+        let Some(since) = app.history_unsaved_since else {
+            panic!("failed save not recorded");
+        };
+        app.retry_history_save();
+        assert_eq!(app.history_unsaved_since, Some(since));
+
+        app.history_path = dir.join("retry/history.txt");
+        app.history_unsaved_since = Some(since - HISTORY_RETRY_INTERVAL);
+        app.retry_history_save();
+        assert!(app.history_unsaved_since.is_none());
+        assert_eq!(fs::read_to_string(&app.history_path).unwrap(), "t:gun");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_push_history_caps_entries() {
+        let mut app = make_mouse_test_app(1);
+        app.filter_history.clear();
+        for i in 0..MAX_HISTORY + 5 {
+            app.push_history(format!("query {}", i));
+        }
+        assert_eq!(app.filter_history.len(), MAX_HISTORY);
+        assert_eq!(app.filter_history[0], "query 5");
+        assert_eq!(
+            app.filter_history.last().map(String::as_str),
+            Some(format!("query {}", MAX_HISTORY + 4).as_str())
         );
+    }
 
-        assert_eq!(app.filter_history.len(), 1);
-        assert_eq!(app.filter_history[0], "test_query");
+    #[test]
+    fn test_load_history_drops_duplicates_and_caps_entries() {
+        let path = test_dir("history_load").join("history.txt");
+        let mut lines = vec!["t:gun".to_string(), "zombie".to_string()];
+        lines.extend((0..MAX_HISTORY).map(|i| format!("query {}", i)));
+        lines.push(String::new());
+        lines.push("t:gun".to_string());
+        fs::write(&path, lines.join("\n")).unwrap();
 
-        app.input_mode = InputMode::Filtering;
-        app.filter_text = "".to_string();
-        handle_key_event(
-            &mut app,
-            KeyCode::Up,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        let mut app = make_mouse_test_app(1);
+        app.history_path = path;
+        app.load_history();
+        assert_eq!(app.filter_history.len(), MAX_HISTORY);
+        assert_eq!(app.filter_history[0], "query 1");
+        assert_eq!(app.filter_history.last().unwrap(), "t:gun");
+        assert_eq!(
+            app.filter_history.iter().filter(|e| *e == "t:gun").count(),
+            1
         );
-        assert_eq!(app.filter_text, "test_query");
+    }
 
-        let _ = fs::remove_file(&history_path);
+    #[test]
+    fn test_fuzzy_score_prefers_tight_matches() {
+        assert_eq!(fuzzy_score("gzx", "t:gun"), None);
+        assert!(fuzzy_score("", "anything").is_some());
+        // Consecutive beats scattered, word starts beat mid-word.
+        assert!(fuzzy_score("gun", "t:gun") > fuzzy_score("gun", "t:gas unit"));
+        assert!(fuzzy_score("ammo", "ammo:9mm") > fuzzy_score("ammo", "c:grammo"));
+        // Case is ignored.
+        assert_eq!(fuzzy_score("GUN", "t:gun"), fuzzy_score("gun", "t:gun"));
     }
 
     #[test]
-    fn test_focus_cycling() {
-        let mut app = make_mouse_test_app(1);
-        assert_eq!(app.focused_pane, FocusPane::List);
+    fn test_rank_history_orders_by_score_then_recency() {
+        let history: Vec<String> = ["t:gun", "zombie", "t:GUN ammo:rpg", "t:gun", "mon_zombie"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
 
-        handle_key_event(
-            &mut app,
-            KeyCode::Tab,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        // Empty query: distinct entries, newest first.
+        assert_eq!(
+            rank_history(&history, ""),
+            vec!["mon_zombie", "t:gun", "t:GUN ammo:rpg", "zombie"]
         );
-        assert_eq!(app.focused_pane, FocusPane::Details);
-
-        handle_key_event(
-            &mut app,
-            KeyCode::Tab,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        assert_eq!(rank_history(&history, "zom"), vec!["zombie", "mon_zombie"]);
+        // Equal scores keep the more recent entry first.
+        assert_eq!(
+            rank_history(&history, "tgun"),
+            vec!["t:gun", "t:GUN ammo:rpg"]
         );
-        assert_eq!(app.focused_pane, FocusPane::Filter);
+        assert!(rank_history(&history, "xyz").is_empty());
+        // Ranking never reorders the history itself.
+        assert_eq!(history[0], "t:gun");
+    }
 
-        handle_key_event(
-            &mut app,
-            KeyCode::Tab,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
-        );
-        assert_eq!(app.focused_pane, FocusPane::List);
+    #[test]
+    fn test_history_search_applies_entry() {
+        let mut app = make_mouse_test_app(3);
+        app.filter_history = vec!["i:item_1".to_string(), "i:item_2".to_string()];
 
         handle_key_event(
             &mut app,
-            KeyCode::Tab,
-            KeyModifiers::SHIFT,
+            KeyCode::Char('y'),
+            KeyModifiers::CONTROL,
             KeyEventKind::Press,
         );
-        assert_eq!(app.focused_pane, FocusPane::Filter);
+        assert!(app.show_history_search);
+        for c in "m_1".chars() {
+            handle_key_event(
+                &mut app,
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+                KeyEventKind::Press,
+            );
+        }
+        assert_eq!(app.visible_history_matches(), vec!["i:item_1"]);
 
         handle_key_event(
             &mut app,
-            KeyCode::BackTab,
+            KeyCode::Enter,
             KeyModifiers::NONE,
             KeyEventKind::Press,
         );
-        assert_eq!(app.focused_pane, FocusPane::Details);
+        assert!(!app.show_history_search);
+        assert_eq!(app.filter_text, "i:item_1");
+        assert_eq!(app.filtered_indices.len(), 1);
+        assert_eq!(app.filter_history, vec!["i:item_1", "i:item_2"]);
     }
 
     #[test]
-    fn test_context_aware_navigation() {
-        let mut app = make_mouse_test_app(20);
-        app.list_area = Some(Rect::new(0, 0, 20, 10)); // Height 10
-        app.focused_pane = FocusPane::List;
+    fn test_lint_report_jumps_to_dangling_copy_from() {
+        let mut app = make_link_test_app();
+        app.indexed_items[1].value = json!({"id": "base", "copy-from": "gone"});
+        let press = |app: &mut AppState, code| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press)
+        };
 
-        // PageDown in List
-        handle_key_event(
-            &mut app,
-            KeyCode::PageDown,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        press(&mut app, KeyCode::Char('?'));
+        press(&mut app, KeyCode::Char('l'));
+        assert!(app.show_lint);
+        assert!(!app.show_help);
+        assert_eq!(
+            app.lint_entries,
+            [inheritance::DanglingCopyFrom {
+                idx: 1,
+                parent: "gone".into()
+            }]
+        );
+        assert_eq!(
+            lint_line(&app.indexed_items[1], "gone"),
+            "t base: copy-from 'gone' not found"
         );
-        assert_eq!(app.list_state.selected(), Some(10));
 
-        // Home in List
-        handle_key_event(
-            &mut app,
-            KeyCode::Home,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.show_lint);
+        assert_eq!(app.filter_text, "i:'base'");
+        assert_eq!(
+            app.get_selected_item().map(|item| item.id.as_str()),
+            Some("base")
         );
-        assert_eq!(app.list_state.selected(), Some(0));
+    }
 
-        // Switch focus to Details
-        app.focused_pane = FocusPane::Details;
-        assert_eq!(app.details_scroll_state.offset().y, 0);
+    #[test]
+    fn test_bookmarks_overlay_jumps_to_bookmark() {
+        let mut app = make_mouse_test_app(3);
+        let ctrl = |app: &mut AppState, c| {
+            handle_key_event(
+                app,
+                KeyCode::Char(c),
+                KeyModifiers::CONTROL,
+                KeyEventKind::Press,
+            )
+        };
+        let press = |app: &mut AppState, code| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press)
+        };
 
-        // Down arrow in Details (scrolls)
-        handle_key_event(
-            &mut app,
-            KeyCode::Down,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        app.list_state.select(Some(2));
+        ctrl(&mut app, 's');
+        assert_eq!(app.bookmarks.ids(), ["item_2"]);
+        // A bookmark from another dataset stays listed but can't be jumped to.
+        app.bookmarks.toggle("gone_item");
+
+        ctrl(&mut app, 'v');
+        assert!(app.show_bookmarks);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        assert!(app.show_bookmarks);
+        assert!(!app.is_known_id("gone_item"));
+
+        press(&mut app, KeyCode::Delete);
+        assert_eq!(app.bookmarks.ids(), ["item_2"]);
+        assert_eq!(app.bookmark_list_state.selected(), Some(0));
+
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.show_bookmarks);
+        assert_eq!(app.filter_text, "i:'item_2'");
+        assert_eq!(
+            app.get_selected_item().map(|item| item.id.as_str()),
+            Some("item_2")
         );
-        assert_eq!(app.details_scroll_state.offset().y, 1);
 
-        // Home in Details (resets)
-        handle_key_event(
-            &mut app,
-            KeyCode::Home,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
-        );
-        assert_eq!(app.details_scroll_state.offset().y, 0);
+        // Toggling again removes the bookmark.
+        ctrl(&mut app, 's');
+        assert!(app.bookmarks.ids().is_empty());
+    }
 
-        // Down arrow in Details (scrolls back)
-        handle_key_event(
-            &mut app,
-            KeyCode::Down,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+    #[test]
+    fn test_details_position_counts_wrapped_lines() {
+        assert_eq!(
+            ui::details_position(0, 120).as_deref(),
+            Some("line 1 of 120")
         );
-        assert_eq!(app.details_scroll_state.offset().y, 1);
-
-        // End in Details (scrolls to bottom)
-        handle_key_event(
-            &mut app,
-            KeyCode::End,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        assert_eq!(
+            ui::details_position(41, 120).as_deref(),
+            Some("line 42 of 120")
         );
-        // ScrollViewState::scroll_to_bottom might not move if no viewport is set,
-        // but it sets a flag or something? Actually, let's just skip this one too if it fails.
-        // Actually, if it's the same as page down, it might do nothing.
+        // An offset past the end, before the scroll view clamps it, stays in range.
+        assert_eq!(
+            ui::details_position(500, 120).as_deref(),
+            Some("line 120 of 120")
+        );
+        assert_eq!(ui::details_position(0, 0), None);
     }
 
     #[test]
-    fn test_input_shortcuts() {
+    fn test_warnings_overlay_lists_source_warnings() {
         let mut app = make_mouse_test_app(1);
-        app.focus_pane(FocusPane::Filter);
-        app.filter_text = "hello world".to_string();
-        app.filter_cursor = 11;
+        app.source_warnings = [
+            "Parse error in mods/a/items.json: expected `,` at line 3",
+            "Duplicate ID shadowed: rifle (GUN) in mods/b/guns.json",
+        ]
+        .into_iter()
+        .map(|message| data::SourceWarning {
+            message: message.to_string(),
+            item: None,
+        })
+        .collect();
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+        let mut screen = |app: &mut AppState| -> String {
+            terminal.draw(|f| ui::ui(f, app)).unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect()
+        };
+        assert!(screen(&mut app).contains("* Alt+W"));
 
-        // Ctrl+A (Start of line)
         handle_key_event(
             &mut app,
-            KeyCode::Char('a'),
-            KeyModifiers::CONTROL,
+            KeyCode::Char('w'),
+            KeyModifiers::ALT,
             KeyEventKind::Press,
         );
-        assert_eq!(app.filter_cursor, 0);
+        assert!(app.show_warnings);
+        let text = screen(&mut app);
+        assert!(text.contains("Source warnings (2)"));
+        assert!(text.contains("Parse error in mods/a/items.json: expected `,` at line 3"));
+        assert!(text.contains("Duplicate ID shadowed: rifle (GUN) in mods/b/guns.json"));
 
-        // Ctrl+E (End of line)
         handle_key_event(
             &mut app,
-            KeyCode::Char('e'),
-            KeyModifiers::CONTROL,
+            KeyCode::Down,
+            KeyModifiers::NONE,
             KeyEventKind::Press,
         );
-        assert_eq!(app.filter_cursor, 11);
-
-        // Ctrl+W (Delete word)
+        assert_eq!(app.warnings_list_state.selected(), Some(1));
         handle_key_event(
             &mut app,
-            KeyCode::Char('w'),
-            KeyModifiers::CONTROL,
+            KeyCode::Esc,
+            KeyModifiers::NONE,
             KeyEventKind::Press,
         );
-        assert_eq!(app.filter_text, "hello ");
-        assert_eq!(app.filter_cursor, 6);
+        assert!(!app.show_warnings);
 
-        // Ctrl+U (Clear filter)
+        // After a clean reload the overlay says there is nothing to fix.
+        app.source_warnings.clear();
         handle_key_event(
             &mut app,
-            KeyCode::Char('u'),
-            KeyModifiers::CONTROL,
+            KeyCode::Char('w'),
+            KeyModifiers::ALT,
             KeyEventKind::Press,
         );
-        assert_eq!(app.filter_text, "");
-        assert_eq!(app.filter_cursor, 0);
+        assert!(screen(&mut app).contains("The last load reported no warnings."));
     }
 
     #[test]
-    fn test_esc_behavior() {
-        let mut app = make_mouse_test_app(1);
+    fn test_empty_dataset_shows_how_to_load_data() {
+        let mut app = make_test_app(Vec::new());
+        app.source_warnings = vec![data::SourceWarning {
+            message: "items/broken.json: expected value at line 1".to_string(),
+            item: None,
+        }];
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+        let mut screen = |app: &mut AppState| -> String {
+            terminal.draw(|f| ui::ui(f, app)).unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.height)
+                .map(|y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
 
-        // Esc in Filtering focuses List (no longer clears text)
-        app.focus_pane(FocusPane::Filter);
-        app.filter_text = "abc".to_string();
-        handle_key_event(
-            &mut app,
-            KeyCode::Esc,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        let text = screen(&mut app);
+        assert!(text.contains("No game data loaded"));
+        assert!(text.contains("Ctrl+G pick a game version to download"));
+        assert!(text.contains("items/broken.json: expected value at line 1"));
+        assert!(text.contains("No data loaded"));
+
+        // The progress modal explains itself while the first load runs.
+        app.show_progress = true;
+        assert!(!screen(&mut app).contains("No game data loaded"));
+    }
+
+    #[test]
+    fn test_zebra_stripes_follow_list_position() {
+        let mut app = make_mouse_test_app(40);
+        app.zebra_stripes = true;
+        app.update_filter();
+        app.list_state.select(Some(30));
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+
+        let content = app.list_content_area.unwrap();
+        let offset = app.list_state.offset();
+        assert!(offset > 0);
+        let buffer = terminal.backend().buffer();
+        for y in content.y..content.bottom() {
+            let pos = offset + (y - content.y) as usize;
+            let expected = if pos == 30 {
+                app.theme.list_selected.bg
+            } else if pos % 2 == 1 {
+                app.theme.list_stripe().bg
+            } else {
+                app.theme.list_normal.bg
+            };
+            assert_eq!(
+                buffer[(content.x + 1, y)].bg,
+                expected.unwrap(),
+                "row {}",
+                pos
+            );
+        }
+    }
+
+    #[test]
+    fn test_marked_items_survive_filters_and_export() {
+        let mut app = make_mouse_test_app(4);
+        let press = |app: &mut AppState, code| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press)
+        };
+        let alt_e = |app: &mut AppState| {
+            handle_key_event(
+                app,
+                KeyCode::Char('e'),
+                KeyModifiers::ALT,
+                KeyEventKind::Press,
+            )
+        };
+
+        alt_e(&mut app);
+        assert!(app.pending_action.is_none());
+        assert!(app.status_message.is_some());
+
+        for idx in [3, 1, 2] {
+            app.list_state.select(Some(idx));
+            press(&mut app, KeyCode::Char(' '));
+        }
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.marked, BTreeSet::from([1, 3]));
+
+        // Marks stay on their objects when the filter hides them or the data reloads.
+        app.filter_text = "item_3".to_string();
+        app.update_filter();
+        assert_eq!(app.filtered_indices, [3]);
+        let items = app.indexed_items.clone();
+        let search_index = search_index::SearchIndex::build(&items[1..]);
+        app.apply_new_dataset(
+            items[1..].to_vec(),
+            search_index,
+            3,
+            0.0,
+            "v2".to_string(),
+            "v2".to_string(),
         );
-        assert_eq!(app.filter_text, "abc");
-        assert_eq!(app.focused_pane, FocusPane::List);
+        assert_eq!(app.marked, BTreeSet::from([0, 2]));
 
-        // Esc in Normal Mode (List focused) does nothing (it no longer quits)
-        app.focus_pane(FocusPane::List);
-        app.should_quit = false;
-        handle_key_event(
-            &mut app,
-            KeyCode::Esc,
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
+        alt_e(&mut app);
+        let Some(AppAction::ExportMarked(path)) = app.pending_action.take() else {
+            panic!("export not queued");
+        };
+        assert_eq!(path, std::env::current_dir().unwrap());
+        let dir = crate::test_dir("export");
+        let path = dir.join(EXPORT_FILE);
+        app.export_marked_to(&dir);
+        let exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(exported, json!([{"id": "item_1"}, {"id": "item_3"}]));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some(format!("Exported 2 items to {}", path.display()).as_str())
         );
-        assert!(!app.should_quit);
+        // A second export goes to a new file instead of replacing the first.
+        app.export_marked_to(&dir);
+        let second = dir.join("cbn-tui-export-2.json");
+        assert_eq!(
+            fs::read_to_string(&second).unwrap(),
+            fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some(format!("Exported 2 items to {}", second.display()).as_str())
+        );
+
+        app.focus_pane(FocusPane::List);
+        press(&mut app, KeyCode::Esc);
+        assert!(app.marked.is_empty());
     }
 
     #[test]
-    fn test_quit_behavior() {
-        let mut app = make_mouse_test_app(1);
+    fn test_recent_overlay_jumps_to_viewed_item() {
+        let mut app = make_mouse_test_app(3);
+        let press = |app: &mut AppState, code| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press)
+        };
 
-        // 'q' in Normal Mode quits
-        app.focus_pane(FocusPane::List);
-        app.should_quit = false;
-        handle_key_event(
-            &mut app,
-            KeyCode::Char('q'),
-            KeyModifiers::NONE,
-            KeyEventKind::Press,
-        );
-        assert!(app.should_quit);
+        for idx in [1, 2, 1] {
+            app.list_state.select(Some(idx));
+            app.refresh_details();
+        }
+        // The first item was shown when the app started.
+        assert_eq!(app.recent.ids(), ["item_1", "item_2", "item_0"]);
 
-        // 'q' in Filtering Mode adds 'q' to filter
-        app.focus_pane(FocusPane::Filter);
-        app.filter_text = "".to_string();
-        app.should_quit = false;
         handle_key_event(
             &mut app,
-            KeyCode::Char('q'),
-            KeyModifiers::NONE,
+            KeyCode::Char('r'),
+            KeyModifiers::ALT,
             KeyEventKind::Press,
         );
-        assert!(!app.should_quit);
-        assert_eq!(app.filter_text, "q");
+        assert!(app.show_recent);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.show_recent);
+        assert_eq!(app.filter_text, "i:'item_2'");
+        assert_eq!(app.recent.ids(), ["item_2", "item_1", "item_0"]);
     }
 
     #[test]
-    fn test_handle_key_event_ignores_release_kind() {
-        let indexed_items = vec![data::IndexedItem {
-            value: json!({"id": "apple"}),
-            id: "apple".to_string(),
-            item_type: "fruit".to_string(),
-        }];
-        let search_index = search_index::SearchIndex::build(&indexed_items);
-        let theme = theme::Theme::Dracula.config();
+    fn test_pin_and_diff_against_selection() {
+        let mut app = make_mouse_test_app(3);
+        let ctrl = |app: &mut AppState, c| {
+            handle_key_event(
+                app,
+                KeyCode::Char(c),
+                KeyModifiers::CONTROL,
+                KeyEventKind::Press,
+            )
+        };
 
-        let mut app = AppState::new(
-            indexed_items,
-            search_index,
-            theme,
-            "v1".to_string(),
-            "v1".to_string(),
-            "v1".to_string(),
-            false,
-            1,
-            0.0,
-            std::path::PathBuf::from("/tmp/history.txt"),
-            None,
-        );
+        // Nothing pinned: diff is a no-op.
+        ctrl(&mut app, 'd');
+        assert!(!app.show_diff);
 
-        handle_key_event(
-            &mut app,
-            KeyCode::Char('a'),
-            KeyModifiers::NONE,
-            KeyEventKind::Release,
+        ctrl(&mut app, 'k');
+        assert_eq!(app.pinned_label(), Some("item_0"));
+
+        app.list_state.select(Some(2));
+        app.refresh_details();
+        assert!(app.details_diff.is_none());
+
+        ctrl(&mut app, 'd');
+        let diff = app.details_diff.as_ref().expect("diff view");
+        assert!(
+            diff.rows
+                .iter()
+                .any(|row| row.kind == diff::DiffKind::Changed)
         );
+        // Links aren't navigable while diffing.
+        assert!(app.details_link_ids().is_empty());
 
-        assert_eq!(app.input_mode, InputMode::Normal);
-        assert!(app.filter_text.is_empty());
+        // Moving the selection re-diffs against the same pinned item.
+        app.list_state.select(Some(0));
+        app.refresh_details();
+        let diff = app.details_diff.as_ref().expect("diff view");
+        assert!(diff.rows.iter().all(|row| row.kind == diff::DiffKind::Same));
+
+        // Unpinning the pinned item also leaves the diff view.
+        ctrl(&mut app, 'k');
+        assert_eq!(app.pinned_item, None);
+        assert!(!app.show_diff);
+        assert!(app.details_diff.is_none());
     }
 
     #[test]
-    fn test_refresh_details_populates_annotated() {
+    fn test_details_preserve_source_key_order() {
+        let raw = serde_json::value::RawValue::from_string(
+            r#"{"type": "GUN", "id": "rifle", "abstract_base": "x", "category": "guns"}"#
+                .to_string(),
+        )
+        .unwrap();
+        let item = data::index_raw_item(raw).unwrap();
+        assert_eq!(item.id, "rifle");
+        assert_eq!(item.item_type, "GUN");
+
+        let app = make_test_app(vec![item]);
+        assert!(app.search_index.by_id.contains_key("rifle"));
+        assert!(app.search_index.by_type.contains_key("gun"));
+
+        let keys: Vec<&str> = app
+            .details_annotated
+            .iter()
+            .flatten()
+            .filter(|s| s.kind == ui::JsonSpanKind::Key)
+            .map(|s| s.span.content.as_ref())
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["\"type\"", "\"id\"", "\"abstract_base\"", "\"category\""]
+        );
+    }
+
+    #[test]
+    fn test_details_highlight_classifier_match_only() {
         let indexed_items = vec![data::IndexedItem {
-            value: json!({"id": "1"}),
-            id: "1".to_string(),
-            item_type: "t".to_string(),
+            value: json!({"id": "gun_1", "type": "gun", "name": "gun"}),
+            id: "gun_1".to_string(),
+            item_type: "gun".to_string(),
+            raw: None,
+            source: None,
         }];
-        let search_index = search_index::SearchIndex::build(&indexed_items);
-        let theme = theme::Theme::Dracula.config();
-        let mut app = AppState::new(
-            indexed_items,
-            search_index,
-            theme,
-            "v1".to_string(),
-            "v1".to_string(),
-            "v1".to_string(),
-            false,
-            1,
-            0.0,
-            std::path::PathBuf::from("/tmp/history.txt"),
-            None,
-        );
+        let mut app = make_test_app(indexed_items);
+        let match_bg = app.theme.search_match.bg;
 
-        app.refresh_details();
-        assert!(!app.details_annotated.is_empty());
+        app.filter_text = "t:gun".to_string();
+        app.update_filter();
 
-        // Check content structure - "id" should be present in some line metadata
-        let found_id = app
+        let highlighted: Vec<Option<&str>> = app
             .details_annotated
             .iter()
-            .any(|line| line.iter().any(|s| s.span.content == "\"id\""));
-        assert!(found_id);
+            .flatten()
+            .filter(|s| s.span.style.bg == match_bg)
+            .map(|s| s.key_context.as_deref())
+            .collect();
+        assert_eq!(highlighted, vec![Some("type")]);
+
+        // A bare word highlights every value containing it.
+        app.filter_text = "gun".to_string();
+        app.update_filter();
+        let highlighted = app
+            .details_annotated
+            .iter()
+            .flatten()
+            .filter(|s| s.span.style.bg == match_bg)
+            .count();
+        assert_eq!(highlighted, 3);
     }
 
     #[test]
@@ -2069,16 +6654,22 @@ mod tests {
                 value: json!({"id": "base_rifle"}),
                 id: "base_rifle".to_string(),
                 item_type: "t".to_string(),
+                raw: None,
+                source: None,
             },
             data::IndexedItem {
                 value: json!({"id": "other"}),
                 id: "other".to_string(),
                 item_type: "t".to_string(),
+                raw: None,
+                source: None,
             },
             data::IndexedItem {
                 value: json!({"name": "no_id"}),
                 id: "".to_string(),
                 item_type: "t".to_string(),
+                raw: None,
+                source: None,
             },
         ];
         let search_index = search_index::SearchIndex::build(&indexed_items);
@@ -2100,6 +6691,55 @@ mod tests {
         assert_eq!(app.id_set.len(), 2);
     }
 
+    #[test]
+    fn test_details_indent_width() {
+        let mut app = make_link_test_app();
+        let line_text = |app: &AppState, idx: usize| -> String {
+            app.details_annotated[idx]
+                .iter()
+                .map(|s| s.span.content.as_ref())
+                .collect()
+        };
+        app.list_state.select(Some(0));
+        app.refresh_details();
+        assert_eq!(line_text(&app, 4), "    \"A\",");
+
+        for (width, prefix) in [(1, "  "), (4, "        ")] {
+            app.indent_width = width;
+            app.cached_details_item_idx = None;
+            app.refresh_details();
+            assert_eq!(
+                line_text(&app, 2),
+                format!("{}\"copy-from\": \"base\",", &prefix[..width])
+            );
+            assert_eq!(line_text(&app, 4), format!("{}\"A\",", prefix));
+        }
+    }
+
+    #[test]
+    fn test_id_to_index_keeps_first_of_duplicates() {
+        let mut app = make_test_app(
+            [
+                json!({"id": "ROCK", "type": "GENERIC"}),
+                json!({"id": "rock", "type": "GENERIC"}),
+                json!({"id": "rock", "type": "terrain"}),
+                json!({"type": "GENERIC"}),
+            ]
+            .into_iter()
+            .map(data::IndexedItem::from_value)
+            .collect(),
+        );
+        assert_eq!(app.id_to_index.get("rock"), Some(&1));
+        assert_eq!(app.id_to_index.get("ROCK"), Some(&0));
+        assert_eq!(app.id_to_index.len(), 2);
+
+        // The case-insensitive lookup finds all three; the exact spelling is selected.
+        app.jump_to_id("rock");
+        assert_eq!(app.filtered_indices, [0, 1, 2]);
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(app.focused_pane, FocusPane::Details);
+    }
+
     fn mouse_event(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
         MouseEvent {
             kind,
@@ -2117,10 +6757,17 @@ mod tests {
                     value: json!({"id": id.clone()}),
                     id,
                     item_type: "t".to_string(),
+                    raw: None,
+                    source: None,
                 }
             })
             .collect::<Vec<_>>();
+        make_test_app(indexed_items)
+    }
+
+    fn make_test_app(indexed_items: Vec<data::IndexedItem>) -> AppState {
         let search_index = search_index::SearchIndex::build(&indexed_items);
+        let total_items = indexed_items.len();
         AppState::new(
             indexed_items,
             search_index,
@@ -2129,13 +6776,108 @@ mod tests {
             "v1".to_string(),
             "v1".to_string(),
             false,
-            items,
+            total_items,
             0.0,
             std::path::PathBuf::from("/tmp/h.txt"),
             None,
         )
     }
 
+    fn compare_dataset(values: &[serde_json::Value]) -> compare::CompareDataset {
+        let items: Vec<_> = values
+            .iter()
+            .cloned()
+            .map(data::IndexedItem::from_value)
+            .collect();
+        let index = search_index::SearchIndex::build(&items);
+        compare::CompareDataset {
+            version: "stable".to_string(),
+            items,
+            index,
+        }
+    }
+
+    #[test]
+    fn test_diff_against_compare_dataset() {
+        let mut app = make_mouse_test_app(3);
+        app.set_compare(Some(compare_dataset(&[
+            json!({"id": "item_0", "type": "t"}),
+            json!({"id": "item_1", "type": "t", "range": 5}),
+        ])));
+
+        app.filter_text = "other:missing".to_string();
+        app.update_filter();
+        assert_eq!(app.filtered_indices, vec![2]);
+        app.filter_text.clear();
+        app.update_filter();
+
+        // Nothing pinned, so Ctrl+D diffs against the other version.
+        app.list_state.select(Some(1));
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('d'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.diff_label(), Some("stable"));
+        assert_eq!(
+            app.selected_compare_status(),
+            Some(compare::CompareStatus::Changed)
+        );
+        let diff = app.details_diff.as_ref().expect("diff view");
+        assert!(diff.rows.iter().any(|row| row.kind != diff::DiffKind::Same));
+
+        // Without a counterpart the plain JSON is shown.
+        app.list_state.select(Some(2));
+        app.refresh_details();
+        assert!(app.details_diff.is_none());
+        assert_eq!(
+            app.selected_compare_status(),
+            Some(compare::CompareStatus::Missing)
+        );
+    }
+
+    #[test]
+    fn test_search_target_filters_against_compare_dataset() {
+        let mut app = make_test_app(
+            [
+                json!({"id": "item_0", "type": "t", "flags": ["FOO"]}),
+                json!({"id": "item_1", "type": "t", "flags": ["FOO"]}),
+                json!({"id": "item_2", "type": "t"}),
+            ]
+            .into_iter()
+            .map(data::IndexedItem::from_value)
+            .collect(),
+        );
+        app.set_compare(Some(compare_dataset(&[
+            json!({"id": "item_0", "type": "t", "flags": ["FOO"]}),
+            json!({"id": "item_1", "type": "t"}),
+            json!({"id": "item_2", "type": "t", "flags": ["FOO"]}),
+        ])));
+        app.filter_text = "flags:FOO".to_string();
+        app.update_filter();
+        assert_eq!(app.filtered_indices, vec![0, 1]);
+
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('o'),
+            KeyModifiers::ALT,
+            KeyEventKind::Press,
+        );
+        assert_eq!(app.search_target, compare::SearchTarget::Other);
+        assert_eq!(app.filtered_indices, vec![0, 2]);
+
+        app.cycle_search_target();
+        assert_eq!(app.search_target, compare::SearchTarget::Both);
+        assert_eq!(app.filtered_indices, vec![0]);
+
+        // Without a compare dataset the filter only ever runs here.
+        app.set_compare(None);
+        app.cycle_search_target();
+        assert_eq!(app.search_target, compare::SearchTarget::Both);
+        assert_eq!(app.filtered_indices, vec![0, 1]);
+    }
+
     #[test]
     fn test_mouse_click_list_selects_item_and_focuses_list() {
         let mut app = make_mouse_test_app(8);
@@ -2196,8 +6938,9 @@ mod tests {
     }
 
     #[test]
-    fn test_mouse_scroll_hovered_list_moves_by_constant() {
+    fn test_mouse_scroll_hovered_list_moves_by_scroll_lines() {
         let mut app = make_mouse_test_app(10);
+        app.scroll_lines = 3;
         app.list_area = Some(Rect::new(0, 0, 20, 10));
         app.list_content_area = Some(Rect::new(1, 1, 18, 8));
 
@@ -2205,20 +6948,518 @@ mod tests {
             handle_mouse_event(&mut app, mouse_event(MouseEventKind::ScrollDown, 2, 2));
 
         assert!(transitioned);
-        assert_eq!(app.list_state.selected(), Some(SCROLL_LINES as usize));
+        assert_eq!(app.list_state.selected(), Some(3));
         assert_eq!(app.focused_pane, FocusPane::List);
     }
 
     #[test]
-    fn test_mouse_scroll_hovered_details_moves_by_constant() {
+    fn test_mouse_scroll_hovered_details_moves_by_scroll_lines() {
         let mut app = make_mouse_test_app(1);
+        app.scroll_lines = 3;
         app.details_area = Some(Rect::new(20, 0, 40, 10));
 
         let transitioned =
             handle_mouse_event(&mut app, mouse_event(MouseEventKind::ScrollDown, 25, 1));
 
         assert!(transitioned);
-        assert_eq!(app.details_scroll_state.offset().y, SCROLL_LINES);
+        assert_eq!(app.details_scroll_state.offset().y, 3);
+    }
+
+    #[test]
+    fn test_page_keys_keep_page_overlap() {
+        let page = |app: &mut AppState, code: KeyCode| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press)
+        };
+
+        let mut app = make_mouse_test_app(30);
+        app.focus_pane(FocusPane::List);
+        app.list_area = Some(Rect::new(0, 0, 20, 10));
+        page(&mut app, KeyCode::PageDown);
+        assert_eq!(app.list_state.selected(), Some(10));
+
+        app.page_overlap = 2;
+        page(&mut app, KeyCode::PageDown);
+        assert_eq!(app.list_state.selected(), Some(18));
+        page(&mut app, KeyCode::PageUp);
+        assert_eq!(app.list_state.selected(), Some(10));
+
+        app.focus_pane(FocusPane::Details);
+        app.details_content_area = Some(Rect::new(20, 2, 40, 8));
+        page(&mut app, KeyCode::PageDown);
+        assert_eq!(app.details_scroll_state.offset().y, 6);
+
+        // Arrow keys in the details pane scroll by scroll_lines.
+        app.scroll_lines = 3;
+        page(&mut app, KeyCode::Down);
+        assert_eq!(app.details_scroll_state.offset().y, 9);
+        page(&mut app, KeyCode::Up);
+        assert_eq!(app.details_scroll_state.offset().y, 6);
+    }
+
+    #[test]
+    fn test_mouse_hover_nested_value_sets_path() {
+        let mut app = make_mouse_test_app(1);
+        let style = theme::Theme::Dracula.config().json_style;
+        let annotated = ui::highlight_json_annotated(r#"{"bash": {"str_min": 30}}"#, &style);
+        app.details_wrapped_annotated = ui::wrap_annotated_lines(&annotated, 40).0;
+        app.details_area = Some(Rect::new(0, 0, 42, 10));
+        app.details_content_area = Some(Rect::new(0, 0, 42, 10));
+
+        // `{"bash": {"str_min": 30}}` puts "30" at columns 21..23, plus 1 padding.
+        let transitioned = handle_mouse_event(&mut app, mouse_event(MouseEventKind::Moved, 22, 0));
+        assert!(transitioned);
+        assert_eq!(app.hovered_path.as_deref(), Some("bash.str_min"));
+
+        // Moving off the value clears the path.
+        handle_mouse_event(&mut app, mouse_event(MouseEventKind::Moved, 1, 0));
+        assert_eq!(app.hovered_path, None);
+    }
+
+    #[test]
+    fn test_copy_path_of_hovered_nested_value() {
+        let mut app = make_mouse_test_app(1);
+        let style = theme::Theme::Dracula.config().json_style;
+        let annotated = ui::highlight_json_annotated(
+            r#"{"pocket_data": {"ammo_restriction": {"9mm": 15}}}"#,
+            &style,
+        );
+        app.details_wrapped_annotated = ui::wrap_annotated_lines(&annotated, 60).0;
+        app.details_area = Some(Rect::new(0, 0, 62, 10));
+        app.details_content_area = Some(Rect::new(0, 0, 62, 10));
+        app.focus_pane(FocusPane::Details);
+        let copied = |app: &mut AppState| match app.pending_action.take() {
+            Some(AppAction::CopyToClipboard(text)) => Some(text),
+            _ => None,
+        };
+
+        // Hovering `"ammo_restriction"` (columns 18..36, plus 1 padding), then `p`.
+        handle_mouse_event(&mut app, mouse_event(MouseEventKind::Moved, 25, 0));
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('p'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(
+            copied(&mut app).as_deref(),
+            Some("pocket_data.ammo_restriction")
+        );
+
+        // Alt-Click on the value copies its path rather than filtering by it.
+        let mut click = mouse_event(MouseEventKind::Down(event::MouseButton::Left), 46, 0);
+        click.modifiers = KeyModifiers::ALT;
+        assert!(handle_mouse_event(&mut app, click));
+        assert_eq!(
+            copied(&mut app).as_deref(),
+            Some("pocket_data.ammo_restriction.9mm")
+        );
+        assert!(app.filter_text.is_empty());
+
+        // Nothing is copied away from any value.
+        handle_mouse_event(&mut app, mouse_event(MouseEventKind::Moved, 1, 0));
+        handle_key_event(
+            &mut app,
+            KeyCode::Char('p'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert_eq!(copied(&mut app), None);
+    }
+
+    #[test]
+    fn test_count_field_value_over_filtered_items() {
+        let items: Vec<data::IndexedItem> = [
+            json!({"id": "a", "material": "steel", "bash": {"str_min": 30}}),
+            json!({"id": "b", "material": ["wood", "steel"], "bash": {"str_min": 3}}),
+            json!({"id": "c", "material": "Steel", "bash": {"str_min": 30}}),
+            json!({"id": "d", "material": "steel"}),
+        ]
+        .into_iter()
+        .map(|value| data::IndexedItem {
+            id: value["id"].as_str().unwrap().to_string(),
+            item_type: "t".to_string(),
+            value,
+            raw: None,
+            source: None,
+        })
+        .collect();
+
+        // Exact matches only, within the listed items, counting arrays by element.
+        assert_eq!(
+            count_field_value(&items, &[0, 1, 2], "material", "steel"),
+            2
+        );
+        assert_eq!(
+            count_field_value(&items, &[0, 1, 2, 3], "material", "steel"),
+            3
+        );
+        assert_eq!(
+            count_field_value(&items, &[0, 1, 2, 3], "bash.str_min", "30"),
+            2
+        );
+        assert_eq!(count_field_value(&items, &[], "material", "steel"), 0);
+    }
+
+    #[test]
+    fn test_hovered_value_count_is_cached_per_path_and_value() {
+        let mut app = make_link_test_app();
+        app.refresh_details();
+        let flag_b = app
+            .details_annotated
+            .iter()
+            .flatten()
+            .find(|span| span.span.content == "\"B\"")
+            .and_then(|span| span.span_id)
+            .unwrap();
+
+        app.update_hovered_value_count("flags", flag_b);
+        let count = app.hovered_value_count.clone().unwrap();
+        assert_eq!((count.value.as_str(), count.count), ("B", 1));
+
+        // Every path and value hovered keeps its count until a new filter runs.
+        let flag_a = app
+            .details_annotated
+            .iter()
+            .flatten()
+            .find(|span| span.span.content == "\"A\"")
+            .and_then(|span| span.span_id)
+            .unwrap();
+        app.value_counts
+            .insert(("flags".to_string(), "B".to_string()), 7);
+        app.update_hovered_value_count("flags", flag_a);
+        assert_eq!(app.hovered_value_count.as_ref().unwrap().value, "A");
+        app.update_hovered_value_count("flags", flag_b);
+        assert_eq!(app.hovered_value_count.as_ref().unwrap().count, 7);
+        assert_eq!(app.value_counts.len(), 2);
+        app.update_filter();
+        assert!(app.hovered_value_count.is_none());
+        assert!(app.value_counts.is_empty());
+    }
+
+    #[test]
+    fn test_grouped_digits_click_uses_raw_value() {
+        let mut app = make_mouse_test_app(1);
+        let style = theme::Theme::Dracula.config().json_style;
+        app.details_annotated = ui::highlight_json_annotated(r#""weight": 1500000"#, &style);
+        let grouped = ui::group_number_spans(&app.details_annotated);
+        app.details_wrapped_annotated = ui::wrap_annotated_lines(&grouped, 40).0;
+        app.details_area = Some(Rect::new(0, 0, 42, 10));
+        app.details_content_area = Some(Rect::new(0, 0, 42, 10));
+
+        let shown = app.details_wrapped_annotated[0]
+            .iter()
+            .find(|s| s.kind == ui::JsonSpanKind::NumberValue)
+            .unwrap();
+        assert_eq!(shown.span.content, "1,500,000");
+        let stored = app.details_annotated[0]
+            .iter()
+            .find(|s| s.span_id == shown.span_id)
+            .unwrap();
+        assert_eq!(stored.span.content, "1500000");
+
+        // `"weight": 1,500,000` puts the number at columns 10..19, plus 1 padding.
+        handle_mouse_event(
+            &mut app,
+            mouse_event(MouseEventKind::Down(MouseButton::Left), 14, 0),
+        );
+        assert_eq!(app.filter_text, "weight:'1500000'");
+    }
+
+    #[test]
+    fn test_c_adds_hovered_value_like_a_click() {
+        let setup = || {
+            let mut app = make_mouse_test_app(1);
+            let style = theme::Theme::Dracula.config().json_style;
+            app.details_annotated =
+                ui::highlight_json_annotated(r#"{"bash": {"str_min": "a b"}}"#, &style);
+            app.details_wrapped_annotated = ui::wrap_annotated_lines(&app.details_annotated, 40).0;
+            app.details_area = Some(Rect::new(0, 0, 42, 10));
+            app.details_content_area = Some(Rect::new(0, 0, 42, 10));
+            app.focus_pane(FocusPane::Details);
+            app
+        };
+        let press_c = |app: &mut AppState| {
+            handle_key_event(
+                app,
+                KeyCode::Char('c'),
+                KeyModifiers::NONE,
+                KeyEventKind::Press,
+            )
+        };
+
+        // `"a b"` sits at columns 21..26, plus 1 padding.
+        let mut clicked = setup();
+        handle_mouse_event(
+            &mut clicked,
+            mouse_event(MouseEventKind::Down(MouseButton::Left), 23, 0),
+        );
+
+        let mut pressed = setup();
+        press_c(&mut pressed);
+        assert!(pressed.filter_text.is_empty());
+        handle_mouse_event(&mut pressed, mouse_event(MouseEventKind::Moved, 23, 0));
+        press_c(&mut pressed);
+
+        assert_eq!(clicked.filter_text, "bash.str_min:'a b'");
+        assert_eq!(pressed.filter_text, clicked.filter_text);
+        assert_eq!(pressed.focused_pane, clicked.focused_pane);
+    }
+
+    #[test]
+    fn test_tab_accepts_filter_completion() {
+        let mut app = make_link_test_app();
+        let press = |app: &mut AppState, code: KeyCode| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press)
+        };
+        let candidates = |app: &AppState| app.completion.as_ref().map(|c| c.candidates.clone());
+        app.focus_pane(FocusPane::Filter);
+
+        press(&mut app, KeyCode::Char('i'));
+        assert_eq!(candidates(&app), None);
+        press(&mut app, KeyCode::Char(':'));
+        assert_eq!(
+            candidates(&app),
+            Some(vec!["base".to_string(), "rifle".to_string()])
+        );
+        // The popup stands on the filter input, its entries lined up with the term.
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        let row = |y: u16| -> String {
+            let buffer = terminal.backend().buffer();
+            (1..10).map(|x| buffer[(x, y)].symbol()).collect()
+        };
+        assert_eq!(row(17), "│ base  │");
+        assert_eq!(row(18), "│ rifle │");
+        assert_eq!(row(20), " Filter (");
+        assert_eq!(row(21), "i:       ");
+
+        // Up/Down move through the popup instead of the history; Tab takes the pick.
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.filter_text, "i:rifle");
+        assert_eq!(app.filter_cursor, 7);
+        assert!(app.completion.is_none());
+        assert_eq!(app.focused_pane, FocusPane::Filter);
+        app.flush_pending_filter();
+        assert_eq!(app.filtered_indices.len(), 1);
+
+        // Esc closes the popup but keeps the filter focused.
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('i'));
+        press(&mut app, KeyCode::Char(':'));
+        press(&mut app, KeyCode::Char('b'));
+        assert_eq!(candidates(&app), Some(vec!["base".to_string()]));
+        press(&mut app, KeyCode::Esc);
+        assert!(app.completion.is_none());
+        assert_eq!(app.input_mode, InputMode::Filtering);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.filter_text, "i:rifle i:b");
+    }
+
+    #[test]
+    fn test_click_on_header_type_and_category_adds_filter_terms() {
+        let mut app = make_test_app(vec![data::IndexedItem::from_value(json!({
+            "id": "rifle",
+            "type": "GUN",
+            "category": "guns"
+        }))]);
+        app.focus_pane(FocusPane::List);
+        app.update_filter();
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(160, 40)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+
+        let links: Vec<(&str, &str)> = app
+            .header_links
+            .iter()
+            .map(|link| (link.classifier, link.value.as_str()))
+            .collect();
+        assert_eq!(links, [("t", "GUN"), ("c", "guns")]);
+
+        let click = |app: &mut AppState, classifier: &str| {
+            let area = app
+                .header_links
+                .iter()
+                .find(|link| link.classifier == classifier)
+                .unwrap()
+                .area;
+            handle_mouse_event(
+                app,
+                mouse_event(MouseEventKind::Down(MouseButton::Left), area.x, area.y),
+            )
+        };
+        assert!(click(&mut app, "t"));
+        assert_eq!(app.filter_text, "t:'GUN'");
+        assert_eq!(app.focused_pane, FocusPane::Filter);
+        assert!(click(&mut app, "c"));
+        assert_eq!(app.filter_text, "t:'GUN' c:'guns'");
+
+        // Just past the value is not a link.
+        let area = app.header_links[0].area;
+        let filter = app.filter_text.clone();
+        handle_mouse_event(
+            &mut app,
+            mouse_event(
+                MouseEventKind::Down(MouseButton::Left),
+                area.right() + 1,
+                area.y,
+            ),
+        );
+        assert_eq!(app.filter_text, filter);
+    }
+
+    #[test]
+    fn test_hovering_nested_value_sets_breadcrumb() {
+        let mut app = make_test_app(vec![data::IndexedItem::from_value(json!({
+            "id": "zombie",
+            "type": "MONSTER",
+            "bash": {"str_min": 30}
+        }))]);
+        app.update_filter();
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(160, 40)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+
+        // Nothing hovered: the top-level key of the first visible line.
+        assert_eq!(ui::details_breadcrumb(&app).as_deref(), Some("id"));
+
+        let area = app.details_content_area.unwrap();
+        let (row, column) = app
+            .details_wrapped_annotated
+            .iter()
+            .enumerate()
+            .find_map(|(row, line)| {
+                let mut x = 0;
+                for span in line {
+                    if span.span.content.as_ref() == "30" {
+                        return Some((row as u16, x));
+                    }
+                    x += span.span.width() as u16;
+                }
+                None
+            })
+            .unwrap();
+        assert!(handle_mouse_event(
+            &mut app,
+            mouse_event(MouseEventKind::Moved, area.x + 1 + column, area.y + row),
+        ));
+        assert_eq!(
+            ui::details_breadcrumb(&app).as_deref(),
+            Some("bash.str_min")
+        );
+
+        // Moving off the body falls back again.
+        handle_mouse_event(&mut app, mouse_event(MouseEventKind::Moved, 0, 0));
+        assert_eq!(ui::details_breadcrumb(&app).as_deref(), Some("id"));
+    }
+
+    #[test]
+    fn test_hovering_a_known_id_previews_its_object() {
+        let indexed_items: Vec<data::IndexedItem> = [
+            json!({"id": "rifle", "type": "GUN", "copy-from": "base_rifle"}),
+            json!({"id": "base_rifle", "type": "GUN", "name": {"str": "basic rifle"},
+                "description": "long text", "weight": "3 kg", "range": 12, "flags": ["X"]}),
+        ]
+        .into_iter()
+        .map(data::IndexedItem::from_value)
+        .collect();
+        let mut app = make_test_app(indexed_items);
+        app.update_filter();
+        app.select_item(0);
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(160, 40)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+
+        let area = app.details_content_area.unwrap();
+        let (row, column) = app
+            .details_wrapped_annotated
+            .iter()
+            .enumerate()
+            .find_map(|(row, line)| {
+                let mut x = 0;
+                for span in line {
+                    if span.span.content.contains("base_rifle") {
+                        return Some((row as u16, x));
+                    }
+                    x += span.span.width() as u16;
+                }
+                None
+            })
+            .unwrap();
+        let (column, row) = (area.x + 1 + column, area.y + row);
+        handle_mouse_event(&mut app, mouse_event(MouseEventKind::Moved, column, row));
+        let preview = app.hovered_preview.clone().unwrap();
+        assert_eq!(preview.id, "base_rifle");
+        assert_eq!(preview.anchor, (column, row));
+        assert_eq!(
+            preview.fields,
+            [
+                ("type".to_string(), "GUN".to_string()),
+                ("name".to_string(), "basic rifle".to_string()),
+                ("weight".to_string(), "3 kg".to_string()),
+                ("range".to_string(), "12".to_string()),
+            ]
+        );
+
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("weight: 3 kg"));
+
+        // Leaving the span or pressing a key closes the preview.
+        handle_mouse_event(&mut app, mouse_event(MouseEventKind::Moved, 0, 0));
+        assert!(app.hovered_preview.is_none());
+        handle_mouse_event(&mut app, mouse_event(MouseEventKind::Moved, column, row));
+        assert!(app.hovered_preview.is_some());
+        handle_key_event(
+            &mut app,
+            KeyCode::Down,
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+        );
+        assert!(app.hovered_preview.is_none());
+    }
+
+    #[test]
+    fn test_clicking_the_minimap_scrolls_the_details() {
+        let mut app = make_mouse_test_app(1);
+        let fields: serde_json::Map<String, serde_json::Value> = (0..80)
+            .map(|idx| (format!("field_{:02}", idx), json!(idx)))
+            .collect();
+        app.indexed_items[0].value = serde_json::Value::Object(fields);
+        app.cached_details_item_idx = None;
+        app.update_filter();
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+
+        let minimap = app.details_minimap_area.unwrap();
+        assert_eq!(app.details_scroll_state.offset().y, 0);
+        assert!(handle_mouse_event(
+            &mut app,
+            mouse_event(
+                MouseEventKind::Down(MouseButton::Left),
+                minimap.x,
+                minimap.bottom() - 1
+            ),
+        ));
+        // The last cell scrolls to the end of the body.
+        let total = app.details_wrapped_annotated.len();
+        let visible = app.details_content_area.unwrap().height as usize;
+        assert_eq!(
+            app.details_scroll_state.offset().y as usize,
+            total - visible
+        );
+        assert_eq!(app.focused_pane, FocusPane::Details);
+
+        // A short body has no minimap.
+        app.indexed_items[0].value = json!({"id": "item_0"});
+        app.cached_details_item_idx = None;
+        app.refresh_details();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        assert!(app.details_minimap_area.is_none());
     }
 
     #[test]
@@ -2226,7 +7467,7 @@ mod tests {
         let mut app = make_mouse_test_app(1);
         let style = theme::Theme::Dracula.config().json_style;
         let annotated = ui::highlight_json_annotated(r#""id": 1"#, &style);
-        app.details_wrapped_annotated = ui::wrap_annotated_lines(&annotated, 20);
+        app.details_wrapped_annotated = ui::wrap_annotated_lines(&annotated, 20).0;
         app.details_area = Some(Rect::new(20, 0, 40, 10));
         app.details_content_area = Some(Rect::new(20, 0, 40, 10));
         app.filter_text = "x".to_string();
@@ -2243,4 +7484,24 @@ mod tests {
         assert_eq!(app.focused_pane, FocusPane::Details);
         assert_eq!(app.filter_text, "x");
     }
+
+    #[test]
+    fn test_clear_cache_needs_confirmation() {
+        let press = |app: &mut AppState, code| {
+            handle_key_event(app, code, KeyModifiers::NONE, KeyEventKind::Press);
+        };
+        let mut app = make_mouse_test_app(3);
+        app.show_help = true;
+        app.cache_size = Some(1024);
+
+        // The first x only asks; any other key takes the question back.
+        press(&mut app, KeyCode::Char('x'));
+        assert!(app.confirm_clear_cache);
+        assert_eq!(app.cache_size, Some(1024));
+        assert!(app.cache_clear_result.is_none());
+        press(&mut app, KeyCode::Char('z'));
+        assert!(!app.confirm_clear_cache);
+        assert!(app.show_help);
+        assert_eq!(app.cache_size, Some(1024));
+    }
 }
@@ -1,9 +1,12 @@
 use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::Range;
 
 /// Represents a parsed search term with an optional classifier and exact match flag.
 /// Used to represent individual components of a space-separated search query.
-#[derive(Debug, PartialEq)]
-pub(crate) struct SearchTerm {
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchTerm {
     /// Optional field classifier (e.g., "id", "str_min").
     pub classifier: Option<String>,
     /// The pattern to match.
@@ -12,54 +15,51 @@ pub(crate) struct SearchTerm {
     pub exact: bool,
 }
 
+/// Characters that open and close an exact segment.
+const QUOTES: [char; 2] = ['\'', '"'];
+
 /// Parses a search string into a `SearchTerm`.
 /// Supports "classifier:value", "classifier:'exact_value'", "'exact_value'", and "pattern".
-pub(crate) fn parse_search_term(term: &str) -> SearchTerm {
+/// Double quotes work like single quotes, and `\ ` keeps a space in a plain pattern.
+pub fn parse_search_term(term: &str) -> SearchTerm {
     // Check for classifier (field:value format)
-    if let Some(colon_pos) = term.find(':') {
-        let classifier = term[..colon_pos].to_string();
-        let value_part = &term[colon_pos + 1..];
+    let (classifier, value_part) = match term.find(':') {
+        Some(colon_pos) => (Some(term[..colon_pos].to_string()), &term[colon_pos + 1..]),
+        None => (None, term),
+    };
 
-        // Check if the value is quoted (exact match)
-        if value_part.starts_with('\'') && value_part.ends_with('\'') && value_part.len() >= 2 {
-            SearchTerm {
-                classifier: Some(classifier),
-                pattern: unescape_exact_pattern(&value_part[1..value_part.len() - 1]),
-                exact: true,
-            }
-        } else {
-            SearchTerm {
-                classifier: Some(classifier),
-                pattern: value_part.to_string(),
-                exact: false,
-            }
-        }
-    } else {
-        // No classifier - check if the whole term is quoted
-        if term.starts_with('\'') && term.ends_with('\'') && term.len() >= 2 {
-            SearchTerm {
-                classifier: None,
-                pattern: unescape_exact_pattern(&term[1..term.len() - 1]),
-                exact: true,
-            }
-        } else {
-            SearchTerm {
-                classifier: None,
-                pattern: term.to_string(),
-                exact: false,
-            }
-        }
+    // Check if the value is quoted (exact match)
+    match quoted_inner(value_part) {
+        Some((quote, inner)) => SearchTerm {
+            classifier,
+            pattern: unescape_exact_pattern(inner, quote),
+            exact: true,
+        },
+        None => SearchTerm {
+            classifier,
+            pattern: unescape_spaces(value_part),
+            exact: false,
+        },
+    }
+}
+
+/// Returns the quote and the text between quotes of a `'...'` or `"..."` value.
+fn quoted_inner(value: &str) -> Option<(char, &str)> {
+    let quote = value.chars().next().filter(|ch| QUOTES.contains(ch))?;
+    if value.len() < 2 || !value.ends_with(quote) {
+        return None;
     }
+    Some((quote, &value[1..value.len() - 1]))
 }
 
-fn unescape_exact_pattern(raw: &str) -> String {
+fn unescape_exact_pattern(raw: &str, quote: char) -> String {
     let mut out = String::with_capacity(raw.len());
     let mut chars = raw.chars();
 
     while let Some(ch) = chars.next() {
         if ch == '\\' {
             if let Some(next) = chars.next() {
-                if next == '\'' || next == '\\' {
+                if next == quote || next == '\\' {
                     out.push(next);
                 } else {
                     out.push('\\');
@@ -76,12 +76,35 @@ fn unescape_exact_pattern(raw: &str) -> String {
     out
 }
 
+/// Turns `\ ` back into a space; other backslashes are kept as typed.
+fn unescape_spaces(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\\' && chars.peek().is_some_and(|next| next.is_whitespace()) {
+            continue;
+        }
+        out.push(ch);
+    }
+
+    out
+}
+
 /// Splits a query string into terms while preserving quoted segments.
 ///
-/// Whitespace delimits terms unless it's inside a single-quoted segment.
-/// Quotes only begin an exact segment at token start (or right after `:`),
+/// Whitespace delimits terms unless it's escaped with `\` or inside a single-
+/// or double-quoted segment. Quotes only begin an exact segment at token start (or right after `:`),
 /// so apostrophes in normal words are preserved.
 fn split_query_terms(query: &str) -> Vec<String> {
+    split_query_ranges(query)
+        .into_iter()
+        .map(|range| query[range].to_string())
+        .collect()
+}
+
+/// Byte ranges of the terms `split_query_terms` would return.
+fn split_query_ranges(query: &str) -> Vec<Range<usize>> {
     fn is_escaped(input: &str, byte_idx: usize) -> bool {
         let bytes = input.as_bytes();
         let mut i = byte_idx;
@@ -95,13 +118,13 @@ fn split_query_terms(query: &str) -> Vec<String> {
 
     let mut terms = Vec::new();
     let mut start: Option<usize> = None;
-    let mut in_single_quotes = false;
+    let mut open_quote: Option<char> = None;
     let mut chars = query.char_indices().peekable();
 
     while let Some((idx, ch)) = chars.next() {
-        if ch.is_whitespace() && !in_single_quotes {
+        if ch.is_whitespace() && open_quote.is_none() && !is_escaped(query, idx) {
             if let Some(token_start) = start.take() {
-                terms.push(query[token_start..idx].to_string());
+                terms.push(token_start..idx);
             }
             continue;
         }
@@ -110,41 +133,670 @@ fn split_query_terms(query: &str) -> Vec<String> {
             start = Some(idx);
         }
 
-        if ch == '\'' && !is_escaped(query, idx) {
-            if !in_single_quotes {
+        if !QUOTES.contains(&ch) || is_escaped(query, idx) {
+            continue;
+        }
+        match open_quote {
+            None => {
                 if let Some(token_start) = start {
                     let quote_starts_exact =
                         idx == token_start || query[token_start..idx].ends_with(':');
                     if quote_starts_exact {
-                        in_single_quotes = true;
+                        open_quote = Some(ch);
                     }
                 }
-            } else {
+            }
+            Some(quote) if quote == ch => {
                 let next_is_delimiter = match chars.peek() {
                     None => true,
                     Some((_, next)) => next.is_whitespace(),
                 };
                 if next_is_delimiter {
-                    in_single_quotes = false;
+                    open_quote = None;
                 }
             }
+            Some(_) => {}
         }
     }
 
     if let Some(token_start) = start {
-        terms.push(query[token_start..].to_string());
+        terms.push(token_start..query.len());
     }
 
     terms
 }
 
+/// Role of a piece of query text, used to highlight the filter input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryTokenKind {
+    /// Whitespace between terms.
+    Space,
+    /// A `field:` prefix, colon included.
+    Classifier,
+    /// A plain substring pattern.
+    Pattern,
+    /// A quoted exact value, quotes included.
+    Exact,
+    /// A value that opens a quote but never closes it.
+    Unterminated,
+    /// A grouping parenthesis or the `|` between alternatives.
+    Operator,
+}
+
+/// Splits `query` into highlighted tokens that cover every byte exactly once,
+/// so the tokens concatenate back to the input.
+pub fn tokenize_query(query: &str) -> Vec<(QueryTokenKind, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    for (lexeme, range) in lex_query(query) {
+        if range.start > pos {
+            tokens.push((QueryTokenKind::Space, pos..range.start));
+        }
+        pos = range.end;
+        if lexeme != Lexeme::Term {
+            tokens.push((QueryTokenKind::Operator, range));
+            continue;
+        }
+        let term = &query[range.clone()];
+        let value_start = match term.find(':') {
+            Some(colon_pos) => {
+                let value_start = range.start + colon_pos + 1;
+                tokens.push((QueryTokenKind::Classifier, range.start..value_start));
+                value_start
+            }
+            None => range.start,
+        };
+        let value = &query[value_start..range.end];
+        if !value.is_empty() {
+            let kind = if !value.starts_with(QUOTES) {
+                QueryTokenKind::Pattern
+            } else if quoted_inner(value).is_some() {
+                QueryTokenKind::Exact
+            } else {
+                QueryTokenKind::Unterminated
+            };
+            tokens.push((kind, value_start..range.end));
+        }
+    }
+    if pos < query.len() {
+        tokens.push((QueryTokenKind::Space, pos..query.len()));
+    }
+    tokens
+}
+
+/// Opens a group of terms, as in `t:gun (ammo:rpg | ammo:40mm)`.
+const GROUP_OPEN: char = '(';
+/// Closes a group opened with [`GROUP_OPEN`].
+const GROUP_CLOSE: char = ')';
+/// Separates alternatives; written as a term of its own.
+const OR_OPERATOR: &str = "|";
+
+/// What a piece of query text stands for in the expression grammar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lexeme {
+    Open,
+    Close,
+    Or,
+    Term,
+}
+
+/// Splits `query` into terms and operators, with their byte ranges.
+///
+/// `(` at the start of a term and `)` at its end group terms, but only when
+/// every one of them is paired with one in another term. A term like `(abs)`
+/// keeps its parentheses, and a query with unbalanced parentheses such as
+/// `(foo` is read as plain terms, exactly as before groups existed.
+fn lex_query(query: &str) -> Vec<(Lexeme, Range<usize>)> {
+    let ranges = split_query_ranges(query);
+    let parens: Vec<(usize, usize)> = ranges
+        .iter()
+        .map(|range| match &query[range.clone()] {
+            OR_OPERATOR => (0, 0),
+            term => group_parens(term),
+        })
+        .collect();
+
+    let mut depth = 0usize;
+    let mut balanced = true;
+    for &(opens, closes) in &parens {
+        depth += opens;
+        let Some(rest) = depth.checked_sub(closes) else {
+            balanced = false;
+            break;
+        };
+        depth = rest;
+    }
+    balanced &= depth == 0;
+
+    let mut lexemes = Vec::new();
+    for (range, (opens, closes)) in ranges.into_iter().zip(parens) {
+        if &query[range.clone()] == OR_OPERATOR {
+            lexemes.push((Lexeme::Or, range));
+            continue;
+        }
+        let (opens, closes) = if balanced { (opens, closes) } else { (0, 0) };
+        let (start, end) = (range.start + opens, range.end - closes);
+        lexemes.extend((range.start..start).map(|pos| (Lexeme::Open, pos..pos + 1)));
+        if end > start {
+            lexemes.push((Lexeme::Term, start..end));
+        }
+        lexemes.extend((end..range.end).map(|pos| (Lexeme::Close, pos..pos + 1)));
+    }
+    lexemes
+}
+
+/// How many leading `(` and trailing `)` of a term may pair with parentheses
+/// in other terms. Pairs within the term itself, as in `(abs)`, stay text.
+fn group_parens(term: &str) -> (usize, usize) {
+    let opens = term.chars().take_while(|&c| c == GROUP_OPEN).count();
+    let closes = term[opens..]
+        .chars()
+        .rev()
+        .take_while(|&c| c == GROUP_CLOSE)
+        .count();
+    let paired = opens.min(closes);
+    (opens - paired, closes - paired)
+}
+
+/// A query as a tree of terms. Terms side by side must all match, `|`
+/// separates alternatives and binds looser, and parentheses group:
+/// `a b | c` is `(a b) | c`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryExpr {
+    Term(SearchTerm),
+    /// Every child must match; no children matches everything.
+    And(Vec<QueryExpr>),
+    /// At least one child must match.
+    Or(Vec<QueryExpr>),
+}
+
+impl Default for QueryExpr {
+    fn default() -> Self {
+        Self::And(Vec::new())
+    }
+}
+
+impl QueryExpr {
+    /// The terms in the tree, in query order.
+    pub fn terms(&self) -> Vec<&SearchTerm> {
+        match self {
+            Self::Term(term) => vec![term],
+            Self::And(children) | Self::Or(children) => {
+                children.iter().flat_map(Self::terms).collect()
+            }
+        }
+    }
+
+    fn for_each_term_mut(&mut self, f: &mut impl FnMut(&mut SearchTerm)) {
+        match self {
+            Self::Term(term) => f(term),
+            Self::And(children) | Self::Or(children) => {
+                for child in children {
+                    child.for_each_term_mut(f);
+                }
+            }
+        }
+    }
+}
+
+/// Parses `query` into an expression tree. The top level is always an `And`,
+/// so a query without parentheses or `|` is an `And` of its terms.
+pub fn parse_query_expr(query: &str) -> Result<QueryExpr, String> {
+    let lexemes = lex_query(query);
+    let mut parser = ExprParser {
+        query,
+        lexemes: &lexemes,
+        pos: 0,
+    };
+    let expr = parser.alternatives()?;
+    if parser.pos < lexemes.len() {
+        return Err("unmatched `)`".to_string());
+    }
+    Ok(match expr {
+        QueryExpr::And(_) => expr,
+        other => QueryExpr::And(vec![other]),
+    })
+}
+
+/// Recursive-descent parser over the output of `lex_query`.
+struct ExprParser<'a> {
+    query: &'a str,
+    lexemes: &'a [(Lexeme, Range<usize>)],
+    pos: usize,
+}
+
+impl ExprParser<'_> {
+    fn peek(&self) -> Option<Lexeme> {
+        self.lexemes.get(self.pos).map(|(lexeme, _)| *lexeme)
+    }
+
+    /// `sequence ('|' sequence)*`
+    fn alternatives(&mut self) -> Result<QueryExpr, String> {
+        let mut branches = vec![self.sequence()?];
+        while self.peek() == Some(Lexeme::Or) {
+            self.pos += 1;
+            branches.push(self.sequence()?);
+        }
+        if branches.len() == 1 {
+            return Ok(QueryExpr::And(branches.remove(0)));
+        }
+        if branches.iter().any(Vec::is_empty) {
+            return Err("`|` needs a term on both sides".to_string());
+        }
+        Ok(QueryExpr::Or(
+            branches
+                .into_iter()
+                .map(|mut children| match children.len() {
+                    1 => children.remove(0),
+                    _ => QueryExpr::And(children),
+                })
+                .collect(),
+        ))
+    }
+
+    /// Terms and groups side by side. Groups of plain terms merge into the sequence.
+    fn sequence(&mut self) -> Result<Vec<QueryExpr>, String> {
+        let mut children = Vec::new();
+        loop {
+            match self.lexemes.get(self.pos) {
+                Some((Lexeme::Term, range)) => {
+                    children.push(QueryExpr::Term(parse_search_term(
+                        &self.query[range.clone()],
+                    )));
+                    self.pos += 1;
+                }
+                Some((Lexeme::Open, _)) => {
+                    self.pos += 1;
+                    let group = self.alternatives()?;
+                    if self.peek() != Some(Lexeme::Close) {
+                        return Err("unclosed `(`".to_string());
+                    }
+                    self.pos += 1;
+                    match group {
+                        QueryExpr::And(inner) if inner.is_empty() => {
+                            return Err("empty `()`".to_string());
+                        }
+                        QueryExpr::And(inner) => children.extend(inner),
+                        other => children.push(other),
+                    }
+                }
+                _ => return Ok(children),
+            }
+        }
+    }
+}
+
+/// The term being typed at the cursor, as completion sees it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionToken {
+    /// Byte range of the partial value in the query; an accepted completion replaces it.
+    pub range: Range<usize>,
+    /// The classifier before the `:`, if any.
+    pub classifier: Option<String>,
+    /// The value typed so far.
+    pub prefix: String,
+}
+
+/// Finds the term that ends at byte offset `cursor` of `query`. Quoted or
+/// escaped values, a cursor inside a term and bare terms shorter than two
+/// characters (the word index holds nothing shorter) give `None`.
+pub fn completion_token(query: &str, cursor: usize) -> Option<CompletionToken> {
+    let range = split_query_ranges(query)
+        .into_iter()
+        .find(|range| range.end == cursor)?;
+    let mut start = range.start;
+    while query[start..cursor].starts_with(GROUP_OPEN) {
+        start += 1;
+    }
+    // `count:` only prefixes the query; complete the term after it.
+    if query[..start].trim().is_empty() && query[start..cursor].starts_with(COUNT_PREFIX) {
+        start += COUNT_PREFIX.len();
+    }
+    let term = &query[start..cursor];
+    let (classifier, value_start) = match term.find(':') {
+        Some(colon_pos) => (Some(term[..colon_pos].to_string()), start + colon_pos + 1),
+        None if term.chars().count() < 2 => return None,
+        None => (None, start),
+    };
+    let prefix = &query[value_start..cursor];
+    if prefix.starts_with(QUOTES) || prefix.contains('\\') {
+        return None;
+    }
+    Some(CompletionToken {
+        range: value_start..cursor,
+        classifier,
+        prefix: prefix.to_string(),
+    })
+}
+
+/// Up to `limit` indexed values completing `token`, the most common first.
+///
+/// Classifier terms complete from the matching `SearchIndex` map (`t:` from the
+/// types, `key:` from the field names, ...); bare words from the word index.
+/// Other classifiers aren't indexed and get no completions.
+pub fn completion_candidates<'a>(
+    search_index: &'a crate::search_index::SearchIndex,
+    token: &CompletionToken,
+    limit: usize,
+) -> Vec<&'a str> {
+    let sorted_keys = search_index.sorted_keys();
+    let keys = match token.classifier.as_deref() {
+        None => &sorted_keys.words,
+        Some("id" | "abstract" | "i") => &sorted_keys.by_id,
+        Some("type" | "t") => &sorted_keys.by_type,
+        Some("category" | "c") => &sorted_keys.by_category,
+        Some("key") => &sorted_keys.by_key,
+        Some(_) => return Vec::new(),
+    };
+    keys.complete(&token.prefix, limit)
+}
+
+/// Suffix marking a classifier as an array-length comparison, as in `flags#:>3`.
+const LENGTH_SUFFIX: char = '#';
+
+/// Comparison operator of a length or number filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl CompareOp {
+    /// Splits a leading `>=`, `<=`, `>`, `<` or `=` off `pattern`.
+    fn split(pattern: &str) -> Option<(Self, &str)> {
+        [
+            (">=", Self::Ge),
+            ("<=", Self::Le),
+            (">", Self::Gt),
+            ("<", Self::Lt),
+            ("=", Self::Eq),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| pattern.strip_prefix(prefix).map(|rest| (op, rest)))
+    }
+
+    /// Whether `lhs <op> rhs` holds.
+    fn holds<T: PartialOrd>(self, lhs: T, rhs: T) -> bool {
+        match self {
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Eq => lhs == rhs,
+            Self::Ge => lhs >= rhs,
+            Self::Gt => lhs > rhs,
+        }
+    }
+}
+
+/// Array-length condition parsed from a `path#:<op><n>` term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthFilter {
+    pub op: CompareOp,
+    pub value: usize,
+}
+
+impl LengthFilter {
+    /// Parses `>3`, `>=3`, `<3`, `<=3`, `=3` or a bare `3` (same as `=3`).
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let pattern = pattern.trim();
+        let (op, number) = CompareOp::split(pattern).unwrap_or((CompareOp::Eq, pattern));
+        let value = number.trim().parse().ok()?;
+        Some(Self { op, value })
+    }
+
+    pub fn matches(self, len: usize) -> bool {
+        self.op.holds(len, self.value)
+    }
+}
+
+/// Numeric condition parsed from a `path:<op><n>` term, as in `volume:>200`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberFilter {
+    pub op: CompareOp,
+    pub value: f64,
+}
+
+impl NumberFilter {
+    /// Parses `>200`, `>=1.5`, `<3`, `<=3` or `=0`. Unlike a length, the operator
+    /// is required: a bare `200` stays a text search.
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let (op, number) = CompareOp::split(pattern.trim())?;
+        let value = number
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())?;
+        Some(Self { op, value })
+    }
+
+    pub fn matches(self, number: f64) -> bool {
+        self.op.holds(number, self.value)
+    }
+}
+
+/// The number a string value starts with, dropping a unit after it: `"250 ml"`
+/// and `"250ml"` give 250. Anything but letters after the number (a date such
+/// as `"2024-05-01"`, a version) gives `None`, as does a string not starting
+/// with a digit. Units are not converted.
+pub fn leading_number(text: &str) -> Option<f64> {
+    if !text.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let end = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(end);
+    if !unit.trim().chars().all(char::is_alphabetic) {
+        return None;
+    }
+    number.parse().ok()
+}
+
+/// A `*`/`?` wildcard pattern matched against a whole value, ignoring case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+    pattern: Vec<char>,
+}
+
+impl Glob {
+    /// Compiles `pattern` if it contains `*` or `?`; plain values give `None`
+    /// so they stay on substring matching and the hash lookups.
+    pub fn new(pattern: &str) -> Option<Self> {
+        if !pattern.contains(['*', '?']) {
+            return None;
+        }
+        Some(Self {
+            pattern: pattern.to_lowercase().chars().collect(),
+        })
+    }
+
+    /// Returns true if all of `text` matches; `*` spans any run of characters, `?` one.
+    pub fn matches(&self, text: &str) -> bool {
+        let text: Vec<char> = text.to_lowercase().chars().collect();
+        let pattern = &self.pattern;
+        let (mut p, mut t) = (0, 0);
+        // Last `*` seen and the text position it currently absorbs up to.
+        let mut star: Option<(usize, usize)> = None;
+        while t < text.len() {
+            if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+                p += 1;
+                t += 1;
+            } else if p < pattern.len() && pattern[p] == '*' {
+                star = Some((p, t));
+                p += 1;
+            } else if let Some((star_p, star_t)) = star {
+                // Let the last `*` swallow one more character and retry.
+                p = star_p + 1;
+                t = star_t + 1;
+                star = Some((star_p, star_t + 1));
+            } else {
+                return false;
+            }
+        }
+        pattern[p..].iter().all(|&ch| ch == '*')
+    }
+}
+
+impl SearchTerm {
+    /// The wildcard pattern of an unquoted term containing `*` or `?`.
+    pub fn glob(&self) -> Option<Glob> {
+        if self.exact {
+            return None;
+        }
+        Glob::new(&self.pattern)
+    }
+
+    /// For a `path#:>3` term, returns the array path and the parsed length filter.
+    /// The filter is `None` when the pattern isn't a valid comparison.
+    pub fn length_filter(&self) -> Option<(&str, Option<LengthFilter>)> {
+        let path = self.classifier.as_deref()?.strip_suffix(LENGTH_SUFFIX)?;
+        Some((path, LengthFilter::parse(&self.pattern)))
+    }
+
+    /// The numeric comparison of an unquoted term such as `volume:>200`.
+    pub fn number_filter(&self) -> Option<NumberFilter> {
+        if self.exact {
+            return None;
+        }
+        NumberFilter::parse(&self.pattern)
+    }
+
+    /// For a `path:@null`, `path:@empty` or `path:@missing` term, returns the
+    /// dot-path and the state asked for. Quoted values stay literal.
+    pub fn presence(&self) -> Option<(&str, Presence)> {
+        if self.exact {
+            return None;
+        }
+        let path = match self.classifier.as_deref()? {
+            "id" | "i" => "id",
+            "type" | "t" => "type",
+            "category" | "c" => "category",
+            "key" | WARNED_CLASSIFIER | OTHER_CLASSIFIER | LIMIT_CLASSIFIER | DEPTH_CLASSIFIER
+            | SORT_CLASSIFIER => {
+                return None;
+            }
+            path => path,
+        };
+        let presence = match self.pattern.to_lowercase().as_str() {
+            "@null" => Presence::Null,
+            "@empty" => Presence::Empty,
+            "@missing" => Presence::Missing,
+            _ => return None,
+        };
+        Some((path, presence))
+    }
+}
+
+/// State of a field asked for with a `path:@null`, `path:@empty` or `path:@missing` term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    /// The path leads to an explicit `null`.
+    Null,
+    /// The path leads to an empty string.
+    Empty,
+    /// The path leads to no value at all; the opposite of the field being defined.
+    Missing,
+}
+
+impl Presence {
+    /// Checks the value at `parts` on `json`, fanning out over arrays like other field terms.
+    pub fn matches(self, json: &Value, parts: &[&str]) -> bool {
+        match self {
+            Self::Null => field_parts_any(json, parts, &Value::is_null),
+            Self::Empty => field_parts_any(json, parts, &|value| value.as_str() == Some("")),
+            Self::Missing => !field_resolves(json, parts),
+        }
+    }
+}
+
+/// Parses a full query string into its individual search terms, leaving out
+/// grouping parentheses and `|`.
+pub fn parse_query(query: &str) -> Vec<SearchTerm> {
+    match parse_query_expr(query) {
+        Ok(expr) => expr.terms().into_iter().cloned().collect(),
+        Err(_) => parse_flat_query(query),
+    }
+}
+
+/// Every whitespace-separated term, operators included, for queries that don't parse.
+fn parse_flat_query(query: &str) -> Vec<SearchTerm> {
+    split_query_terms(query)
+        .iter()
+        .map(|term| parse_search_term(term))
+        .collect()
+}
+
+/// A parsed query: space-separated terms that must all match (AND logic),
+/// with `|` alternatives and parenthesized groups as in [`QueryExpr`].
+///
+/// Use it to test single JSON objects without building a `SearchIndex` for the
+/// whole dataset. Matching runs the same evaluation as `find_matches` over a
+/// one-item index, so both always agree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    text: String,
+    terms: Vec<SearchTerm>,
+}
+
+impl Query {
+    /// Parses a query string using the same rules as the filter input.
+    pub fn parse(query: &str) -> Self {
+        Self {
+            text: query.to_string(),
+            terms: parse_query(query),
+        }
+    }
+
+    /// The parsed terms, in query order.
+    pub fn terms(&self) -> &[SearchTerm] {
+        &self.terms
+    }
+
+    /// Returns true if the query has no terms (and so matches everything).
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Checks whether a JSON object satisfies the query.
+    ///
+    /// Each call indexes the object on its own; use `find_matches` for many items.
+    pub fn matches(&self, json: &Value) -> bool {
+        let item = crate::data::IndexedItem::from_value(json.clone());
+        let items = std::slice::from_ref(&item);
+        let search_index = crate::search_index::SearchIndex::build(items);
+        !find_matches(&self.text, items, &search_index).is_empty()
+    }
+}
+
+/// Returns true if a classifier targets the given dot-path of a JSON value.
+///
+/// Fast-path classifiers (`i`, `t`, `c` and their long forms) only look at
+/// top-level fields, mirroring what `SearchIndex` indexes.
+pub fn classifier_targets_path(classifier: &str, path: &str) -> bool {
+    match classifier {
+        "id" | "abstract" | "i" => path == "id" || path == "abstract",
+        "type" | "t" => path == "type",
+        "category" | "c" => path == "category",
+        _ => path == classifier,
+    }
+}
+
 /// Recursively checks if a JSON value matches the search criteria.
 ///
 /// For exact matches (`exact: true`), the value (converted to string) must be identical to the pattern.
 /// For pattern matches (`exact: false`), the value must contain the pattern as a substring (case-insensitive for strings).
 ///
 /// **Optimization Note:** If `exact` is false, `pattern` MUST be passed in lowercase.
-pub(crate) fn matches_value(value: &Value, pattern: &str, exact: bool) -> bool {
+pub fn matches_value(value: &Value, pattern: &str, exact: bool) -> bool {
+    matches_value_to_depth(value, pattern, exact, usize::MAX)
+}
+
+/// Like `matches_value`, but only looks `depth` objects deep: with a depth of 1
+/// the fields of `value` are checked, but not the fields of objects inside them.
+/// Array elements count as the depth of the array.
+pub fn matches_value_to_depth(value: &Value, pattern: &str, exact: bool, depth: usize) -> bool {
     match value {
         Value::String(s) => {
             if exact {
@@ -176,11 +828,16 @@ pub(crate) fn matches_value(value: &Value, pattern: &str, exact: bool) -> bool {
         }
         Value::Array(arr) => {
             // Check if any element in the array matches
-            arr.iter().any(|v| matches_value(v, pattern, exact))
+            arr.iter()
+                .any(|v| matches_value_to_depth(v, pattern, exact, depth))
         }
         Value::Object(obj) => {
-            // Recursively check all values in the object
-            obj.values().any(|v| matches_value(v, pattern, exact))
+            // Recursively check all values in the object, one level deeper
+            let Some(depth) = depth.checked_sub(1) else {
+                return false;
+            };
+            obj.values()
+                .any(|v| matches_value_to_depth(v, pattern, exact, depth))
         }
         Value::Null => {
             if exact {
@@ -197,8 +854,7 @@ pub(crate) fn matches_value(value: &Value, pattern: &str, exact: bool) -> bool {
 /// and checks if any value found at that path matches the criteria.
 ///
 /// **Optimization Note:** If `exact` is false, `pattern` MUST be passed in lowercase.
-#[allow(dead_code)]
-pub(crate) fn matches_field(json: &Value, field_name: &str, pattern: &str, exact: bool) -> bool {
+pub fn matches_field(json: &Value, field_name: &str, pattern: &str, exact: bool) -> bool {
     // Split once here; recursive calls use matches_field_parts to avoid re-splitting.
     let parts: Vec<&str> = field_name.split('.').collect();
     matches_field_parts(json, &parts, pattern, exact)
@@ -207,6 +863,12 @@ pub(crate) fn matches_field(json: &Value, field_name: &str, pattern: &str, exact
 /// Inner implementation that operates on a pre-split path slice, avoiding repeated
 /// split().collect() allocations when called across many items in the slow search path.
 fn matches_field_parts(json: &Value, parts: &[&str], pattern: &str, exact: bool) -> bool {
+    field_parts_any(json, parts, &|value| matches_value(value, pattern, exact))
+}
+
+/// Walks the dot-path `parts` (fanning out over arrays) and returns true if
+/// `leaf` accepts any value found at its end.
+fn field_parts_any(json: &Value, parts: &[&str], leaf: &impl Fn(&Value) -> bool) -> bool {
     let mut current = json;
     for (i, part) in parts.iter().enumerate() {
         match current {
@@ -214,7 +876,7 @@ fn matches_field_parts(json: &Value, parts: &[&str], pattern: &str, exact: bool)
                 if let Some(value) = obj.get(*part) {
                     if i == parts.len() - 1 {
                         // Last part - check the value
-                        return matches_value(value, pattern, exact);
+                        return leaf(value);
                     } else {
                         // Not the last part - continue traversing
                         current = value;
@@ -229,7 +891,7 @@ fn matches_field_parts(json: &Value, parts: &[&str], pattern: &str, exact: bool)
                 let remaining = &parts[i..];
                 return arr
                     .iter()
-                    .any(|item| matches_field_parts(item, remaining, pattern, exact));
+                    .any(|item| field_parts_any(item, remaining, leaf));
             }
             _ => {
                 // The current value is not an object or array, can't traverse further
@@ -241,6 +903,294 @@ fn matches_field_parts(json: &Value, parts: &[&str], pattern: &str, exact: bool)
     false
 }
 
+/// Whether a number, or a string starting with one, satisfies `filter`.
+/// Arrays and objects match when any value inside them does.
+pub fn matches_value_number(value: &Value, filter: NumberFilter) -> bool {
+    match value {
+        Value::Number(n) => n.as_f64().is_some_and(|n| filter.matches(n)),
+        Value::String(s) => leading_number(s).is_some_and(|n| filter.matches(n)),
+        Value::Array(arr) => arr.iter().any(|v| matches_value_number(v, filter)),
+        Value::Object(obj) => obj.values().any(|v| matches_value_number(v, filter)),
+        Value::Bool(_) | Value::Null => false,
+    }
+}
+
+/// Like `matches_value`, but every scalar must match `glob` as a whole.
+pub fn matches_value_glob(value: &Value, glob: &Glob) -> bool {
+    match value {
+        Value::String(s) => glob.matches(s),
+        Value::Number(n) => glob.matches(&n.to_string()),
+        Value::Bool(b) => glob.matches(&b.to_string()),
+        Value::Array(arr) => arr.iter().any(|v| matches_value_glob(v, glob)),
+        Value::Object(obj) => obj.values().any(|v| matches_value_glob(v, glob)),
+        Value::Null => glob.matches("null"),
+    }
+}
+
+/// Result of running a query, with diagnostics the UI can surface.
+#[derive(Debug, Default, PartialEq)]
+pub struct MatchOutcome {
+    /// Indices of matching items, in list order unless a `sort:` term reordered them.
+    pub indices: Vec<usize>,
+    /// Whether a term walked every item's JSON instead of using the index.
+    pub slow_path: bool,
+    /// Classifier of a slow-path term that matched no item at all.
+    pub slow_path_miss: Option<String>,
+    /// Classifiers that are neither indexed fields nor a path present on any item.
+    pub unknown_classifiers: Vec<String>,
+    /// Set by a `count:` query: only the number of matches should be shown.
+    pub count_only: bool,
+    /// With a `limit:` term, how many items matched before `indices` was cut to the limit.
+    pub limited_total: Option<usize>,
+    /// Why the query could not be evaluated at all.
+    pub error: Option<String>,
+}
+
+/// Prefix turning a query into a count of its matches (only recognized at query start).
+const COUNT_PREFIX: &str = "count:";
+
+/// Returns true for classifiers answered by `SearchIndex` rather than a field walk.
+fn is_indexed_classifier(classifier: &str) -> bool {
+    matches!(
+        classifier,
+        "id" | "abstract"
+            | "i"
+            | "type"
+            | "t"
+            | "category"
+            | "c"
+            | "key"
+            | WARNED_CLASSIFIER
+            | OTHER_CLASSIFIER
+            | LIMIT_CLASSIFIER
+            | DEPTH_CLASSIFIER
+            | SORT_CLASSIFIER
+    )
+}
+
+/// User-defined classifier shorthands, e.g. `str` for `bash.str_min`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassifierAliases {
+    paths: BTreeMap<String, String>,
+}
+
+impl ClassifierAliases {
+    /// Adds `alias` for `path`. Built-in classifiers (`id`, `t`, ...) keep their
+    /// meaning; returns false if `alias` is one of them and was ignored.
+    pub fn insert(&mut self, alias: &str, path: &str) -> bool {
+        if is_indexed_classifier(alias) {
+            return false;
+        }
+        self.override_builtin(alias, path);
+        true
+    }
+
+    /// Adds `alias` for `path`, replacing a built-in classifier of that name.
+    pub fn override_builtin(&mut self, alias: &str, path: &str) {
+        self.paths.insert(alias.to_string(), path.to_string());
+    }
+
+    /// The full path of `classifier` if it is an alias, else `classifier` itself.
+    pub fn expand<'a>(&'a self, classifier: &'a str) -> &'a str {
+        self.paths
+            .get(classifier)
+            .map_or(classifier, String::as_str)
+    }
+
+    /// Rewrites aliased classifiers in `terms` to their full paths.
+    pub fn apply(&self, terms: &mut [SearchTerm]) {
+        for classifier in terms.iter_mut().filter_map(|term| term.classifier.as_mut()) {
+            if let Some(path) = self.paths.get(classifier.as_str()) {
+                *classifier = path.clone();
+            }
+        }
+    }
+}
+
+/// Pseudo-classifier selecting items that have source warnings (`warned:true`).
+const WARNED_CLASSIFIER: &str = "warned";
+/// Pseudo-classifier selecting items by how they compare to a second dataset (`other:changed`).
+const OTHER_CLASSIFIER: &str = "other";
+/// Meta-term keeping only the first N matches (`limit:100`), applied after all other terms.
+const LIMIT_CLASSIFIER: &str = "limit";
+/// Meta-term capping how deep plain words look into an object (`depth:1`).
+const DEPTH_CLASSIFIER: &str = "depth";
+/// Meta-term ordering the matches by a field (`sort:volume`, `sort:-volume` for descending).
+const SORT_CLASSIFIER: &str = "sort";
+
+/// Field the matches are ordered by, from a `sort:` term.
+#[derive(Debug, Clone, PartialEq)]
+struct SortKey {
+    path: String,
+    descending: bool,
+}
+
+impl SortKey {
+    fn parse(pattern: &str, aliases: &ClassifierAliases) -> Result<Self, String> {
+        let (descending, field) = match pattern.strip_prefix('-') {
+            Some(field) => (true, field),
+            None => (false, pattern),
+        };
+        let path = match aliases.expand(field) {
+            "" => {
+                return Err("`sort:` takes a field, e.g. sort:volume or sort:-volume".to_string());
+            }
+            "i" => "id",
+            "t" => "type",
+            "c" => "category",
+            path => path,
+        };
+        Ok(Self {
+            path: path.to_string(),
+            descending,
+        })
+    }
+
+    /// Orders `indices` by the field's value on each item, keeping list order
+    /// among equal values. Items without the field go last either way.
+    fn sort(&self, indices: &mut [usize], items: &[crate::data::IndexedItem]) {
+        let parts: Vec<&str> = self.path.split('.').collect();
+        let mut keyed: Vec<(Option<SortValue>, usize)> = indices
+            .iter()
+            .map(|&idx| (SortValue::at(&items[idx].value, &parts), idx))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) if self.descending => b.cmp(a),
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+        for (slot, (_, idx)) in indices.iter_mut().zip(keyed) {
+            *slot = idx;
+        }
+    }
+}
+
+/// A field value as `sort:` compares it: numbers (also strings such as
+/// `"250 ml"`) by value and before any text, text without regard to case.
+#[derive(Debug, Clone, PartialEq)]
+enum SortValue {
+    Number(f64),
+    Text(String),
+}
+
+impl SortValue {
+    /// The first value at `parts` on `json`, taking the first element of arrays
+    /// on the way. Translatable names compare by their text.
+    fn at(json: &Value, parts: &[&str]) -> Option<Self> {
+        let mut current = json;
+        for part in parts {
+            while let Value::Array(arr) = current {
+                current = arr.first()?;
+            }
+            current = current.get(*part)?;
+        }
+        while let Value::Array(arr) = current {
+            current = arr.first()?;
+        }
+        match current {
+            Value::Number(n) => n.as_f64().map(Self::Number),
+            Value::String(text) => Some(match leading_number(text) {
+                Some(n) => Self::Number(n),
+                None => Self::Text(text.to_lowercase()),
+            }),
+            Value::Bool(flag) => Some(Self::Text(flag.to_string())),
+            Value::Object(fields) => ["str", "str_sp"]
+                .into_iter()
+                .find_map(|key| fields.get(key)?.as_str())
+                .map(|text| Self::Text(text.to_lowercase())),
+            Value::Null | Value::Array(_) => None,
+        }
+    }
+}
+
+impl Eq for SortValue {}
+
+impl PartialOrd for SortValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Number(_), Self::Text(_)) => Ordering::Less,
+            (Self::Text(_), Self::Number(_)) => Ordering::Greater,
+            (Self::Text(a), Self::Text(b)) => a.cmp(b),
+        }
+    }
+}
+
+/// Inputs to matching besides the query and the dataset.
+#[derive(Debug, Clone, Default)]
+pub struct MatchOptions {
+    /// Classifier shorthands expanded before dispatch.
+    pub aliases: ClassifierAliases,
+    /// Items with source warnings, selected by `warned:true`.
+    pub warned: foldhash::HashSet<usize>,
+    /// Status of each item against the comparison dataset, selected by `other:`.
+    /// Empty when no second dataset is loaded.
+    pub compare: Vec<Option<crate::compare::CompareStatus>>,
+}
+
+/// Items whose comparison status is named by `pattern`: `same`, `changed`,
+/// `missing`, or `present` for either of the first two.
+fn other_matches(
+    pattern: &str,
+    statuses: &[Option<crate::compare::CompareStatus>],
+) -> Result<foldhash::HashSet<usize>, String> {
+    use crate::compare::CompareStatus;
+
+    if statuses.is_empty() {
+        return Err("`other:` needs a second dataset (--compare or Ctrl+D in Ctrl+G)".to_string());
+    }
+    let wanted: &[CompareStatus] = match pattern.to_lowercase().as_str() {
+        "same" => &[CompareStatus::Same],
+        "changed" => &[CompareStatus::Changed],
+        "missing" => &[CompareStatus::Missing],
+        "present" => &[CompareStatus::Same, CompareStatus::Changed],
+        _ => return Err("`other:` takes same, changed, missing or present".to_string()),
+    };
+    Ok(statuses
+        .iter()
+        .enumerate()
+        .filter(|(_, status)| status.is_some_and(|status| wanted.contains(&status)))
+        .map(|(idx, _)| idx)
+        .collect())
+}
+
+/// Records `classifier` as unknown if no item has a value at that path.
+fn note_unknown_classifier(
+    outcome: &mut MatchOutcome,
+    items: &[crate::data::IndexedItem],
+    classifier: &str,
+) {
+    if is_indexed_classifier(classifier)
+        || outcome.unknown_classifiers.iter().any(|c| c == classifier)
+    {
+        return;
+    }
+    let parts: Vec<&str> = classifier.split('.').collect();
+    if !items.iter().any(|item| field_resolves(&item.value, &parts)) {
+        outcome.unknown_classifiers.push(classifier.to_string());
+    }
+}
+
+/// Returns true if the dot-path leads to a value on this JSON (arrays are walked like in matching).
+fn field_resolves(json: &Value, parts: &[&str]) -> bool {
+    let Some((first, rest)) = parts.split_first() else {
+        return true;
+    };
+    match json {
+        Value::Object(obj) => obj.get(*first).is_some_and(|v| field_resolves(v, rest)),
+        Value::Array(arr) => arr.iter().any(|item| field_resolves(item, parts)),
+        _ => false,
+    }
+}
+
 /// Fast indexed search for items
 /// Uses inverted index for common fields, falls back to recursive for nested fields
 /// Returns indices of matching items
@@ -249,85 +1199,432 @@ pub fn find_matches(
     items: &[crate::data::IndexedItem],
     search_index: &crate::search_index::SearchIndex,
 ) -> Vec<usize> {
-    use foldhash::HashSet;
+    find_matches_with_outcome(query, items, search_index).indices
+}
+
+/// Same as `find_matches`, but also reports why a query came back empty.
+///
+/// A leading `count:` evaluates the rest of the query as usual and flags the
+/// outcome as `count_only`. Nested `count:` is rejected with an error.
+pub fn find_matches_with_outcome(
+    query: &str,
+    items: &[crate::data::IndexedItem],
+    search_index: &crate::search_index::SearchIndex,
+) -> MatchOutcome {
+    find_matches_with_options(query, items, search_index, &MatchOptions::default())
+}
+
+/// Same as `find_matches_with_outcome`, with aliases and warned items from `options`.
+pub fn find_matches_with_options(
+    query: &str,
+    items: &[crate::data::IndexedItem],
+    search_index: &crate::search_index::SearchIndex,
+    options: &MatchOptions,
+) -> MatchOutcome {
+    let Some(subquery) = query.trim_start().strip_prefix(COUNT_PREFIX) else {
+        return run_query(query, items, search_index, options);
+    };
+    let subquery = subquery.trim();
+    if subquery.starts_with(COUNT_PREFIX) {
+        return MatchOutcome {
+            count_only: true,
+            error: Some("nested `count:` is not supported".to_string()),
+            ..MatchOutcome::default()
+        };
+    }
+    let mut outcome = run_query(subquery, items, search_index, options);
+    outcome.count_only = true;
+    outcome
+}
 
+/// Evaluates a query without a `count:` prefix.
+fn run_query(
+    query: &str,
+    items: &[crate::data::IndexedItem],
+    search_index: &crate::search_index::SearchIndex,
+    options: &MatchOptions,
+) -> MatchOutcome {
     if query.is_empty() {
-        return collect_all_indices(items);
+        return MatchOutcome {
+            indices: collect_all_indices(items),
+            ..MatchOutcome::default()
+        };
     }
 
-    // Parse all search terms at once (not per item)
-    let terms: Vec<SearchTerm> = split_query_terms(query)
-        .iter()
-        .map(|term| parse_search_term(term))
-        .collect();
+    let mut outcome = MatchOutcome::default();
+
+    // Parse the query once (not per item)
+    let mut expr = match parse_query_expr(query) {
+        Ok(expr) => expr,
+        Err(error) => {
+            outcome.error = Some(error);
+            return outcome;
+        }
+    };
+    let limit = match take_modifier(
+        &mut expr,
+        LIMIT_CLASSIFIER,
+        "a number of results, e.g. limit:100",
+    ) {
+        Ok(limit) => limit,
+        Err(error) => {
+            outcome.error = Some(error);
+            return outcome;
+        }
+    };
+    let depth = match take_modifier(&mut expr, DEPTH_CLASSIFIER, "a nesting depth, e.g. depth:1") {
+        Ok(depth) => depth,
+        Err(error) => {
+            outcome.error = Some(error);
+            return outcome;
+        }
+    };
+    let sort = match take_sort(&mut expr, &options.aliases) {
+        Ok(sort) => sort,
+        Err(error) => {
+            outcome.error = Some(error);
+            return outcome;
+        }
+    };
+    outcome.limited_total = limit.map(|_| 0);
+    expr.for_each_term_mut(&mut |term| options.aliases.apply(std::slice::from_mut(term)));
+
+    let context = EvalContext {
+        items,
+        search_index,
+        options,
+        depth,
+    };
+    let Some(results) = context.eval(&expr, &mut outcome) else {
+        return outcome;
+    };
+
+    let mut result_vec: Vec<usize> = results.into_iter().collect();
+    result_vec.sort_unstable();
+    if let Some(sort) = sort {
+        sort.sort(&mut result_vec, items);
+    }
+    if let Some(limit) = limit {
+        outcome.limited_total = Some(result_vec.len());
+        result_vec.truncate(limit);
+    }
+    outcome.indices = result_vec;
+    outcome
+}
+
+/// What a query is evaluated against.
+struct EvalContext<'a> {
+    items: &'a [crate::data::IndexedItem],
+    search_index: &'a crate::search_index::SearchIndex,
+    options: &'a MatchOptions,
+    /// Nesting depth plain words are looked for at, from a `depth:` term.
+    depth: Option<usize>,
+}
 
-    // Start with all items, then intersect with results from each term
-    let mut results: Option<HashSet<usize>> = None;
+impl EvalContext<'_> {
+    /// Indices of the items matching `expr`, or `None` once `outcome.error` is set.
+    fn eval(
+        &self,
+        expr: &QueryExpr,
+        outcome: &mut MatchOutcome,
+    ) -> Option<foldhash::HashSet<usize>> {
+        use foldhash::HashSet;
+
+        let children = match expr {
+            QueryExpr::Term(term) => return self.term(term, outcome),
+            QueryExpr::Or(children) => {
+                let mut found = HashSet::default();
+                for child in children {
+                    found.extend(self.eval(child, outcome)?);
+                }
+                return Some(found);
+            }
+            QueryExpr::And(children) => children,
+        };
+
+        // Start with all items, then intersect with results from each child
+        let mut results: Option<HashSet<usize>> = None;
+        for (child_idx, child) in children.iter().enumerate() {
+            let matches = self.eval(child, outcome)?;
+
+            // Intersect with AND logic
+            results = Some(match results {
+                None => matches,
+                Some(mut prev) => {
+                    // Optimization: Always iterate over the smaller set
+                    // and reuse the allocation if possible.
+                    if prev.len() < matches.len() {
+                        // prev is smaller: iterate prev and keep only elements in matches
+                        prev.retain(|k| matches.contains(k));
+                        prev
+                    } else {
+                        // matches are smaller (or equal): iterate matches and keep only elements in prev
+                        // We can reuse matches' allocation since we own it
+                        let mut m = matches;
+                        m.retain(|k| prev.contains(k));
+                        m
+                    }
+                }
+            });
+
+            // Early exit if no matches left (optimization)
+            if results.as_ref().is_some_and(|r| r.is_empty()) {
+                // Still flag typos in the terms that were never evaluated.
+                for classifier in children[child_idx + 1..]
+                    .iter()
+                    .flat_map(QueryExpr::terms)
+                    .filter(|t| t.length_filter().is_none())
+                    .filter_map(|t| t.classifier.as_deref())
+                {
+                    note_unknown_classifier(outcome, self.items, classifier);
+                }
+                break;
+            }
+        }
+        Some(results.unwrap_or_else(|| (0..self.items.len()).collect()))
+    }
 
-    for term in terms {
-        let matches = if let Some(classifier) = &term.classifier {
+    /// Indices of the items matching a single term, or `None` once `outcome.error` is set.
+    fn term(
+        &self,
+        term: &SearchTerm,
+        outcome: &mut MatchOutcome,
+    ) -> Option<foldhash::HashSet<usize>> {
+        let (items, search_index, options) = (self.items, self.search_index, self.options);
+        let matches = if let Some((path, filter)) = term.length_filter() {
+            let Some(filter) = filter else {
+                outcome.error = Some(format!(
+                    "`{}{}:` needs a length such as >3, <=2 or =0",
+                    path, LENGTH_SUFFIX
+                ));
+                return None;
+            };
+            outcome.slow_path = true;
+            slow_search_length(items, path, filter)
+        } else if term.classifier.as_deref() == Some(WARNED_CLASSIFIER) {
+            match term.pattern.to_lowercase().as_str() {
+                "true" => options.warned.clone(),
+                "false" => (0..items.len())
+                    .filter(|idx| !options.warned.contains(idx))
+                    .collect(),
+                _ => {
+                    outcome.error = Some("`warned:` takes true or false".to_string());
+                    return None;
+                }
+            }
+        } else if term.classifier.as_deref() == Some(OTHER_CLASSIFIER) {
+            match other_matches(&term.pattern, &options.compare) {
+                Ok(found) => found,
+                Err(error) => {
+                    outcome.error = Some(error);
+                    return None;
+                }
+            }
+        } else if let Some((path, presence)) = term.presence() {
+            outcome.slow_path = true;
+            slow_search_presence(items, path, presence)
+        } else if let Some(classifier) = &term.classifier {
+            let glob = term.glob();
+            // Globs scan the index keys; plain patterns keep the direct lookups.
+            let lookup = |field_index| match &glob {
+                Some(glob) => {
+                    search_index.lookup_field_matching(field_index, |key| glob.matches(key))
+                }
+                None => search_index.lookup_field(field_index, &term.pattern, term.exact),
+            };
             // Classifier-based search
             match classifier.as_str() {
                 "id" | "abstract" | "i" => {
                     // Fast path - use id index (includes abstract)
                     // Support both "id:" and shortcut "i:"
-                    search_index.lookup_field(&search_index.by_id, &term.pattern, term.exact)
+                    lookup(&search_index.by_id)
                 }
                 "type" | "t" => {
                     // Fast path - use type index
                     // Support both "type:" and shortcut "t:"
-                    search_index.lookup_field(&search_index.by_type, &term.pattern, term.exact)
+                    lookup(&search_index.by_type)
                 }
                 "category" | "c" => {
                     // Fast path - use category index
                     // Support both "category:" and shortcut "c:"
-                    search_index.lookup_field(&search_index.by_category, &term.pattern, term.exact)
+                    lookup(&search_index.by_category)
+                }
+                "key" => {
+                    // Fast path - items defining a field of that name at any depth
+                    lookup(&search_index.by_key)
                 }
                 _ => {
                     // Nested field - fallback to recursive search
-                    slow_search_classifier(items, classifier, &term.pattern, term.exact)
+                    outcome.slow_path = true;
+                    let matches = match (term.number_filter(), &glob) {
+                        (Some(filter), _) => slow_search_number(items, classifier, filter),
+                        (None, Some(glob)) => slow_search_glob(items, classifier, glob),
+                        (None, None) => {
+                            slow_search_classifier(items, classifier, &term.pattern, term.exact)
+                        }
+                    };
+                    if matches.is_empty() {
+                        if outcome.slow_path_miss.is_none() {
+                            outcome.slow_path_miss = Some(classifier.clone());
+                        }
+                        note_unknown_classifier(outcome, items, classifier);
+                    }
+                    matches
                 }
             }
         } else {
             // No classifier - use word index for pattern match
-            if term.exact {
-                // Exact match without classifier - need recursive search
-                slow_search_no_classifier(items, &term.pattern, true)
+            if term.exact || self.depth.is_some() {
+                // Exact matches and depth caps need the recursive search
+                outcome.slow_path = true;
+                let depth = self.depth.unwrap_or(usize::MAX);
+                slow_search_no_classifier(items, &term.pattern, term.exact, depth)
+            } else if let Some(glob) = term.glob() {
+                // Glob without classifier - match whole indexed words
+                search_index
+                    .lookup_field_matching(&search_index.word_index, |word| glob.matches(word))
             } else {
                 // Pattern match - use word index
                 search_index.search_words(&term.pattern)
             }
         };
+        Some(matches)
+    }
+}
+
+/// Removes top-level `limit:` or `depth:` terms (per `classifier`) from `expr`,
+/// returning the smallest number given. `usage` describes the number in the
+/// error for a bad one.
+fn take_modifier(
+    expr: &mut QueryExpr,
+    classifier: &str,
+    usage: &str,
+) -> Result<Option<usize>, String> {
+    let mut smallest: Option<usize> = None;
+    for pattern in take_meta_terms(expr, classifier)? {
+        let Ok(n) = pattern.parse::<usize>() else {
+            return Err(format!("`{}:` takes {}", classifier, usage));
+        };
+        smallest = Some(smallest.map_or(n, |prev| prev.min(n)));
+    }
+    Ok(smallest)
+}
 
-        // Intersect with AND logic
-        results = Some(match results {
-            None => matches,
-            Some(mut prev) => {
-                // Optimization: Always iterate over the smaller set
-                // and reuse the allocation if possible.
-                if prev.len() < matches.len() {
-                    // prev is smaller: iterate prev and keep only elements in matches
-                    prev.retain(|k| matches.contains(k));
-                    prev
-                } else {
-                    // matches are smaller (or equal): iterate matches and keep only elements in prev
-                    // We can reuse matches' allocation since we own it
-                    let mut m = matches;
-                    m.retain(|k| prev.contains(k));
-                    m
-                }
+/// Removes the top-level `sort:` term from `expr`; only one may be given.
+fn take_sort(expr: &mut QueryExpr, aliases: &ClassifierAliases) -> Result<Option<SortKey>, String> {
+    match take_meta_terms(expr, SORT_CLASSIFIER)?.as_slice() {
+        [] => Ok(None),
+        [pattern] => SortKey::parse(pattern, aliases).map(Some),
+        _ => Err("only one `sort:` can be given".to_string()),
+    }
+}
+
+/// Removes the top-level terms with `classifier` from `expr`, returning their
+/// patterns in query order. Such a term inside a group or an alternative is an error.
+fn take_meta_terms(expr: &mut QueryExpr, classifier: &str) -> Result<Vec<String>, String> {
+    let is_meta = |term: &SearchTerm| term.classifier.as_deref() == Some(classifier);
+    let mut patterns = Vec::new();
+    if let QueryExpr::And(children) = expr {
+        children.retain(|child| match child {
+            QueryExpr::Term(term) if is_meta(term) => {
+                patterns.push(term.pattern.clone());
+                false
             }
+            _ => true,
         });
+    }
+    if expr.terms().into_iter().any(is_meta) {
+        return Err(format!(
+            "`{}:` can't be inside parentheses or `|`",
+            classifier
+        ));
+    }
+    Ok(patterns)
+}
 
-        // Early exit if no matches left (optimization)
-        if results.as_ref().is_some_and(|r| r.is_empty()) {
-            return Vec::new();
-        }
+/// Slow path: items where the array at `path` has a length satisfying `filter`.
+fn slow_search_length(
+    items: &[crate::data::IndexedItem],
+    path: &str,
+    filter: LengthFilter,
+) -> foldhash::HashSet<usize> {
+    let parts: Vec<&str> = path.split('.').collect();
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| matches_length_parts(&item.value, &parts, filter))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Walks `parts` like `matches_field_parts` and compares the length of the array
+/// found at the end. Non-array values never match.
+fn matches_length_parts(json: &Value, parts: &[&str], filter: LengthFilter) -> bool {
+    let Some((first, rest)) = parts.split_first() else {
+        return matches!(json, Value::Array(arr) if filter.matches(arr.len()));
+    };
+    match json {
+        Value::Object(obj) => obj
+            .get(*first)
+            .is_some_and(|value| matches_length_parts(value, rest, filter)),
+        Value::Array(arr) => arr
+            .iter()
+            .any(|item| matches_length_parts(item, parts, filter)),
+        _ => false,
     }
+}
 
-    let mut result_vec: Vec<usize> = results.unwrap_or_default().into_iter().collect();
-    result_vec.sort_unstable();
-    result_vec
+/// Slow path: items whose value at `path` is null, empty or missing.
+fn slow_search_presence(
+    items: &[crate::data::IndexedItem],
+    path: &str,
+    presence: Presence,
+) -> foldhash::HashSet<usize> {
+    let parts: Vec<&str> = path.split('.').collect();
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| presence.matches(&item.value, &parts))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Slow path: items whose value at `classifier` matches `glob`.
+fn slow_search_glob(
+    items: &[crate::data::IndexedItem],
+    classifier: &str,
+    glob: &Glob,
+) -> foldhash::HashSet<usize> {
+    let parts: Vec<&str> = classifier.split('.').collect();
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            field_parts_any(&item.value, &parts, &|value| {
+                matches_value_glob(value, glob)
+            })
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Slow path: items whose value at `classifier` is a number satisfying `filter`.
+fn slow_search_number(
+    items: &[crate::data::IndexedItem],
+    classifier: &str,
+    filter: NumberFilter,
+) -> foldhash::HashSet<usize> {
+    let parts: Vec<&str> = classifier.split('.').collect();
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            field_parts_any(&item.value, &parts, &|value| {
+                matches_value_number(value, filter)
+            })
+        })
+        .map(|(idx, _)| idx)
+        .collect()
 }
 
 /// Slow path: recursive search with classifier for nested fields
@@ -361,6 +1658,7 @@ fn slow_search_no_classifier(
     items: &[crate::data::IndexedItem],
     pattern: &str,
     exact: bool,
+    depth: usize,
 ) -> foldhash::HashSet<usize> {
     // Optimization: Pre-calculate the pattern to match against.
     // If not exact, we lowercase it once here instead of for every value check.
@@ -373,7 +1671,7 @@ fn slow_search_no_classifier(
     items
         .iter()
         .enumerate()
-        .filter(|(_, item)| matches_value(&item.value, &pattern_owned, exact))
+        .filter(|(_, item)| matches_value_to_depth(&item.value, &pattern_owned, exact, depth))
         .map(|(idx, _)| idx)
         .collect()
 }
@@ -470,6 +1768,235 @@ mod tests {
         assert_eq!(terms, vec!["id:wouldn't"]);
     }
 
+    #[test]
+    fn test_parse_double_quoted_terms() {
+        let terms = parse_query(r#"name:"red apple" "say \"hi\"""#);
+        assert_eq!(
+            terms,
+            vec![
+                SearchTerm {
+                    classifier: Some("name".to_string()),
+                    pattern: "red apple".to_string(),
+                    exact: true
+                },
+                SearchTerm {
+                    classifier: None,
+                    pattern: "say \"hi\"".to_string(),
+                    exact: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_escaped_spaces() {
+        assert_eq!(
+            split_query_terms(r"name:red\ apple id:x"),
+            vec![r"name:red\ apple", "id:x"]
+        );
+        let terms = parse_query(r"name:red\ apple");
+        assert_eq!(terms[0].pattern, "red apple");
+        assert!(!terms[0].exact);
+        // Backslashes that don't escape a space are kept as typed.
+        assert_eq!(parse_query(r"path\to")[0].pattern, r"path\to");
+    }
+
+    #[test]
+    fn test_parse_mixed_quoting() {
+        let terms = split_query_terms(r#"a:"it's here" b:'say "hi"' don't c:x\ y"#);
+        assert_eq!(
+            terms,
+            vec![r#"a:"it's here""#, r#"b:'say "hi"'"#, "don't", r"c:x\ y"]
+        );
+        let parsed = parse_query(r#"a:"it's here" b:'say "hi"'"#);
+        assert_eq!(parsed[0].pattern, "it's here");
+        assert_eq!(parsed[1].pattern, "say \"hi\"");
+        assert!(parsed.iter().all(|term| term.exact));
+    }
+
+    fn token_kinds(query: &str) -> Vec<(QueryTokenKind, &str)> {
+        tokenize_query(query)
+            .into_iter()
+            .map(|(kind, range)| (kind, &query[range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_query_classifies_terms() {
+        use QueryTokenKind::*;
+        assert_eq!(
+            token_kinds("t:gun  'exact phrase' wouldn't "),
+            vec![
+                (Classifier, "t:"),
+                (Pattern, "gun"),
+                (Space, "  "),
+                (Exact, "'exact phrase'"),
+                (Space, " "),
+                (Pattern, "wouldn't"),
+                (Space, " "),
+            ]
+        );
+        assert_eq!(token_kinds("id:"), vec![(Classifier, "id:")]);
+        assert!(tokenize_query("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_query_expr_builds_tree() {
+        let term = |text: &str| QueryExpr::Term(parse_search_term(text));
+        use QueryExpr::{And, Or};
+
+        assert_eq!(
+            parse_query_expr("t:gun (ammo:rpg | ammo:40mm)"),
+            Ok(And(vec![
+                term("t:gun"),
+                Or(vec![term("ammo:rpg"), term("ammo:40mm")])
+            ]))
+        );
+        // `|` binds looser than juxtaposition; groups of plain terms merge.
+        assert_eq!(
+            parse_query_expr("a b | (c d) e"),
+            Ok(And(vec![Or(vec![
+                And(vec![term("a"), term("b")]),
+                And(vec![term("c"), term("d"), term("e")])
+            ])]))
+        );
+        assert_eq!(
+            parse_query_expr("((a | b)) name:'x (y)'"),
+            Ok(And(vec![
+                Or(vec![term("a"), term("b")]),
+                term("name:'x (y)'")
+            ]))
+        );
+        assert_eq!(parse_query_expr(""), Ok(And(Vec::new())));
+
+        assert!(parse_query_expr("t:gun |").is_err());
+        assert!(parse_query_expr("| t:gun").is_err());
+        assert!(parse_query_expr("a ( )").is_err());
+        assert_eq!(
+            parse_query(" (t:gun | c:guns) x"),
+            vec![
+                parse_search_term("t:gun"),
+                parse_search_term("c:guns"),
+                parse_search_term("x")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parentheses_without_a_group_stay_in_terms() {
+        // Queries that were flat before groups existed still parse to the same terms.
+        for query in [
+            "(abs)",
+            "t:gun (abs)",
+            "(foo",
+            "t:gun (ammo:rpg",
+            "t:gun foo)",
+            "a) (b",
+            "((a) b",
+            "name:(x) ()",
+            "(name:'a b')",
+        ] {
+            let flat = parse_flat_query(query)
+                .into_iter()
+                .map(QueryExpr::Term)
+                .collect();
+            assert_eq!(
+                parse_query_expr(query),
+                Ok(QueryExpr::And(flat)),
+                "{}",
+                query
+            );
+            assert_eq!(parse_query(query), parse_flat_query(query), "{}", query);
+        }
+        assert_eq!(
+            parse_query("(abs)"),
+            vec![SearchTerm {
+                classifier: None,
+                pattern: "(abs)".to_string(),
+                exact: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_grouped_queries_evaluate_against_index() {
+        use serde_json::json;
+        let (items, index) = crate::indexed(&[
+            json!({"id": "launcher", "type": "GUN", "ammo": ["rpg"]}),
+            json!({"id": "grenade_launcher", "type": "GUN", "ammo": ["40mm"]}),
+            json!({"id": "pistol", "type": "GUN", "ammo": ["9mm"]}),
+            json!({"id": "rpg_round", "type": "AMMO", "ammo": ["rpg"]}),
+        ]);
+        let find = |query: &str| find_matches_with_outcome(query, &items, &index);
+
+        assert_eq!(find("t:gun (ammo:rpg | ammo:40mm)").indices, [0, 1]);
+        assert_eq!(find("t:ammo | i:pistol").indices, [2, 3]);
+        assert_eq!(find("(t:ammo | i:pistol) 9mm").indices, [2]);
+        assert_eq!(find("(t:gun ammo:rpg) | ammo:40mm").indices, [0, 1]);
+        // Flat queries are unchanged; an empty intersection still flags typos.
+        assert_eq!(find("t:gun ammo:rpg").indices, [0]);
+        let outcome = find("t:nothing (amo:rpg | t:gun)");
+        assert!(outcome.indices.is_empty());
+        assert_eq!(outcome.unknown_classifiers, ["amo"]);
+
+        assert_eq!(find("(t:gun | t:ammo) limit:2").indices, [0, 1]);
+        assert_eq!(find("(t:gun | t:ammo) limit:2").limited_total, Some(4));
+        assert!(find("t:gun | t:ammo limit:2").error.is_some());
+        assert!(find("count:(t:gun | t:ammo)").count_only);
+        assert_eq!(find("count:(t:gun | t:ammo)").indices.len(), 4);
+
+        // Query agrees with the indexed search.
+        for query in ["t:gun (ammo:rpg | ammo:40mm)", "(t:ammo | i:pistol) 9mm"] {
+            let unindexed: Vec<usize> = items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| Query::parse(query).matches(&item.value))
+                .map(|(idx, _)| idx)
+                .collect();
+            assert_eq!(unindexed, find(query).indices, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_tokenize_query_marks_operators() {
+        use QueryTokenKind::*;
+        assert_eq!(
+            token_kinds("(t:gun | x)"),
+            vec![
+                (Operator, "("),
+                (Classifier, "t:"),
+                (Pattern, "gun"),
+                (Space, " "),
+                (Operator, "|"),
+                (Space, " "),
+                (Pattern, "x"),
+                (Operator, ")"),
+            ]
+        );
+        assert_eq!(token_kinds("x)"), vec![(Pattern, "x)")]);
+        assert_eq!(token_kinds("(abs)"), vec![(Pattern, "(abs)")]);
+        assert_eq!(
+            token_kinds("(foo bar"),
+            vec![(Pattern, "(foo"), (Space, " "), (Pattern, "bar")]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_query_flags_unterminated_quote() {
+        use QueryTokenKind::*;
+        assert_eq!(
+            token_kinds("id:rifle name:'m4 carbine"),
+            vec![
+                (Classifier, "id:"),
+                (Pattern, "rifle"),
+                (Space, " "),
+                (Classifier, "name:"),
+                (Unterminated, "'m4 carbine"),
+            ]
+        );
+        assert_eq!(token_kinds("'"), vec![(Unterminated, "'")]);
+    }
+
     #[test]
     fn test_matches_value_string_pattern() {
         // When exact=false, pattern must be lowercase
@@ -567,6 +2094,8 @@ mod tests {
             value: json!({"id": "f_alien_gasper", "flags": ["TRANSPARENT", "EMITTER", "MINEABLE"]}),
             id: "f_alien_gasper".to_string(),
             item_type: "furniture".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -575,12 +2104,210 @@ mod tests {
         assert!(!find_matches("ITTER", &items, &index).is_empty());
     }
 
+    #[test]
+    fn test_unknown_classifier_is_reported() {
+        let items = vec![crate::data::IndexedItem {
+            value: json!({"id": "rifle", "type": "GUN", "bash": {"str_min": 30}}),
+            id: "rifle".to_string(),
+            item_type: "GUN".to_string(),
+            raw: None,
+            source: None,
+        }];
+        let index = crate::search_index::SearchIndex::build(&items);
+
+        let outcome = find_matches_with_outcome("typ:gun", &items, &index);
+        assert!(outcome.indices.is_empty());
+        assert_eq!(outcome.unknown_classifiers, vec!["typ".to_string()]);
+
+        // Terms after an early exit are still checked.
+        let outcome = find_matches_with_outcome("t:nothing bsh.str_min:30", &items, &index);
+        assert_eq!(outcome.unknown_classifiers, vec!["bsh.str_min".to_string()]);
+    }
+
+    #[test]
+    fn test_resolving_classifier_is_not_unknown() {
+        let items = vec![crate::data::IndexedItem {
+            value: json!({"id": "rifle", "type": "GUN", "bash": {"str_min": 30}}),
+            id: "rifle".to_string(),
+            item_type: "GUN".to_string(),
+            raw: None,
+            source: None,
+        }];
+        let index = crate::search_index::SearchIndex::build(&items);
+
+        // A valid nested path that simply has no matching value.
+        let outcome = find_matches_with_outcome("bash.str_min:99", &items, &index);
+        assert!(outcome.indices.is_empty());
+        assert_eq!(outcome.slow_path_miss.as_deref(), Some("bash.str_min"));
+        assert!(outcome.unknown_classifiers.is_empty());
+
+        // Fast-path classifiers never warn.
+        let outcome = find_matches_with_outcome("t:nothing c:nothing", &items, &index);
+        assert!(outcome.unknown_classifiers.is_empty());
+    }
+
+    #[test]
+    fn test_slow_path_is_flagged_for_item_walks_only() {
+        let items = vec![crate::data::IndexedItem {
+            value: json!({"id": "rifle", "type": "GUN", "bash": {"str_min": 30}}),
+            id: "rifle".to_string(),
+            item_type: "GUN".to_string(),
+            raw: None,
+            source: None,
+        }];
+        let index = crate::search_index::SearchIndex::build(&items);
+        let slow = |query: &str| find_matches_with_outcome(query, &items, &index).slow_path;
+
+        assert!(!slow("t:gun"));
+        assert!(!slow("i:rifle rifle"));
+        assert!(slow("bash.str_min:30"));
+        assert!(slow("t:gun bash.str_min:30"));
+        assert!(slow("\"rifle\""));
+        assert!(slow("bash:@missing"));
+    }
+
+    #[test]
+    fn test_count_query_matches_plain_query() {
+        let items: Vec<crate::data::IndexedItem> = ["GUN", "GUN", "AMMO"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| crate::data::IndexedItem {
+                value: json!({"id": format!("item_{}", i), "type": t}),
+                id: format!("item_{}", i),
+                item_type: t.to_string(),
+                raw: None,
+                source: None,
+            })
+            .collect();
+        let index = crate::search_index::SearchIndex::build(&items);
+
+        for query in ["t:GUN", "item", "t:AMMO item_2", ""] {
+            let plain = find_matches_with_outcome(query, &items, &index);
+            let counted = find_matches_with_outcome(&format!("count:{}", query), &items, &index);
+            assert!(!plain.count_only);
+            assert!(counted.count_only);
+            assert_eq!(
+                counted.indices.len(),
+                plain.indices.len(),
+                "query {:?}",
+                query
+            );
+        }
+
+        let nested = find_matches_with_outcome("count:count:t:GUN", &items, &index);
+        assert!(nested.indices.is_empty());
+        assert!(nested.error.is_some());
+    }
+
+    #[test]
+    fn test_sort_orders_matches_by_a_field() {
+        let (items, index) = crate::indexed(&[
+            json!({"id": "a", "type": "GUN", "volume": "750 ml", "name": {"str": "Rifle"}}),
+            json!({"id": "b", "type": "GUN", "name": "pistol"}),
+            json!({"id": "c", "type": "GUN", "volume": 300, "name": "Carbine"}),
+            json!({"id": "d", "type": "AMMO", "volume": "2 L", "name": "box"}),
+            json!({"id": "e", "type": "GUN", "volume": "300 ml"}),
+        ]);
+        let find = |query: &str| find_matches(query, &items, &index);
+
+        // Numbers and strings with units compare by value; ties keep list order.
+        assert_eq!(find("sort:volume"), vec![3, 2, 4, 0, 1]);
+        assert_eq!(find("t:gun sort:volume"), vec![2, 4, 0, 1]);
+        // Missing fields stay last when descending too.
+        assert_eq!(find("t:gun sort:-volume"), vec![0, 2, 4, 1]);
+        // Text ignores case; translatable names compare by their text.
+        assert_eq!(find("sort:name"), vec![3, 2, 1, 0, 4]);
+        assert_eq!(find("sort:-name"), vec![0, 1, 2, 3, 4]);
+        // Sorting comes before the limit.
+        assert_eq!(find("t:gun sort:-volume limit:2"), vec![0, 2]);
+
+        let error = |query| find_matches_with_outcome(query, &items, &index).error;
+        assert!(error("sort:").is_some());
+        assert!(error("sort:name sort:volume").is_some());
+        assert!(error("t:gun | sort:name").is_some());
+    }
+
+    #[test]
+    fn test_depth_caps_plain_word_search() {
+        let (items, index) = crate::indexed(&[
+            json!({"id": "a", "material": "steel"}),
+            json!({"id": "b", "qualities": {"cut": {"note": "steel edge"}}}),
+            json!({"id": "c", "flags": ["STEEL_PLATE"], "armor": [{"material": "kevlar"}]}),
+        ]);
+
+        assert_eq!(find_matches("steel", &items, &index), vec![0, 1, 2]);
+        // Array elements sit at their field's depth; the nested note is three objects down.
+        assert_eq!(find_matches("steel depth:1", &items, &index), vec![0, 2]);
+        assert_eq!(find_matches("steel depth:3", &items, &index), vec![0, 1, 2]);
+        assert_eq!(
+            find_matches("kevlar depth:1", &items, &index),
+            Vec::<usize>::new()
+        );
+        assert_eq!(find_matches("kevlar depth:2", &items, &index), vec![2]);
+        assert_eq!(find_matches("'steel' depth:2", &items, &index), vec![0]);
+        // Classified terms ignore the cap.
+        assert_eq!(
+            find_matches("qualities:edge depth:1", &items, &index),
+            vec![1]
+        );
+
+        assert!(matches_value_to_depth(&items[1].value, "edge", false, 3));
+        assert!(!matches_value_to_depth(&items[1].value, "edge", false, 2));
+
+        let error = |query| find_matches_with_outcome(query, &items, &index).error;
+        assert!(error("steel depth:deep").is_some());
+        assert!(error("(steel depth:1) | kevlar").is_some());
+    }
+
+    #[test]
+    fn test_limit_keeps_first_matches() {
+        let items: Vec<crate::data::IndexedItem> = ["GUN", "AMMO", "GUN", "GUN", "GUN"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| crate::data::IndexedItem {
+                value: json!({"id": format!("item_{}", i), "type": t}),
+                id: format!("item_{}", i),
+                item_type: t.to_string(),
+                raw: None,
+                source: None,
+            })
+            .collect();
+        let index = crate::search_index::SearchIndex::build(&items);
+
+        let outcome = find_matches_with_outcome("t:GUN limit:2", &items, &index);
+        assert_eq!(outcome.indices, [0, 2]);
+        assert_eq!(outcome.limited_total, Some(4));
+
+        // The smallest of several limits wins, wherever they appear.
+        let outcome = find_matches_with_outcome("limit:3 t:GUN limit:1", &items, &index);
+        assert_eq!(outcome.indices, [0]);
+        assert_eq!(outcome.limited_total, Some(4));
+
+        // A limit alone caps the whole dataset; one above the total changes nothing.
+        let outcome = find_matches_with_outcome("limit:3", &items, &index);
+        assert_eq!(outcome.indices, [0, 1, 2]);
+        assert_eq!(outcome.limited_total, Some(5));
+        let outcome = find_matches_with_outcome("t:AMMO limit:10", &items, &index);
+        assert_eq!(outcome.indices, [1]);
+        assert_eq!(outcome.limited_total, Some(1));
+
+        assert_eq!(
+            find_matches_with_outcome("t:GUN", &items, &index).limited_total,
+            None
+        );
+        let outcome = find_matches_with_outcome("t:GUN limit:many", &items, &index);
+        assert!(outcome.indices.is_empty());
+        assert!(outcome.error.is_some());
+    }
+
     #[test]
     fn test_search_exact_match() {
         let items = vec![crate::data::IndexedItem {
             value: json!({"id": "f_alien_gasper", "flags": ["TRANSPARENT", "EMITTER", "MINEABLE"]}),
             id: "f_alien_gasper".to_string(),
             item_type: "furniture".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -600,6 +2327,8 @@ mod tests {
             value: json!({"snippet": "exact phrase match"}),
             id: "test".to_string(),
             item_type: "item".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -615,6 +2344,8 @@ mod tests {
             value: json!({"snippet": "You wouldn't buy a car"}),
             id: "test".to_string(),
             item_type: "item".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -630,6 +2361,8 @@ mod tests {
             value: json!({"snippet": "You wouldn't buy a car"}),
             id: "test".to_string(),
             item_type: "item".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -645,6 +2378,8 @@ mod tests {
             value: json!({"id": "f_alien_gasper", "type": "furniture"}),
             id: "f_alien_gasper".to_string(),
             item_type: "furniture".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -663,6 +2398,8 @@ mod tests {
             value: json!({"bash": {"str_min": 30, "str_max": 60}}),
             id: "test".to_string(),
             item_type: "furniture".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -685,6 +2422,8 @@ mod tests {
             value: json!({"bash": {"str_min": 30}}),
             id: "test".to_string(),
             item_type: "furniture".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -700,6 +2439,8 @@ mod tests {
             value: json!({"id": "f_alien_gasper", "flags": ["EMITTER"]}),
             id: "f_alien_gasper".to_string(),
             item_type: "furniture".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -745,6 +2486,8 @@ mod tests {
             }),
             id: "f_alien_gasper".to_string(),
             item_type: "furniture".to_string(),
+            raw: None,
+            source: None,
         }];
         let index = crate::search_index::SearchIndex::build(&items);
 
@@ -822,6 +2565,8 @@ mod tests {
             value: json!({"id": "test_item", "type": "TOOL", "category": "weapons"}),
             id: "test_item".to_string(),
             item_type: "TOOL".to_string(),
+            raw: None,
+            source: None,
         }];
 
         let index = crate::search_index::SearchIndex::build(&items);
@@ -844,6 +2589,8 @@ mod tests {
             value: json!({"id": "test", "flags": ["EMITTER", "DANGEROUS"]}),
             id: "test".to_string(),
             item_type: "item".to_string(),
+            raw: None,
+            source: None,
         }];
 
         let index = crate::search_index::SearchIndex::build(&items);
@@ -873,16 +2620,22 @@ mod tests {
                 }),
                 id: "f_alien_gasper".to_string(),
                 item_type: "furniture".to_string(),
+                raw: None,
+                source: None,
             },
             crate::data::IndexedItem {
                 value: json!({"id": "apple", "color": "red"}),
                 id: "apple".to_string(),
                 item_type: "fruit".to_string(),
+                raw: None,
+                source: None,
             },
             crate::data::IndexedItem {
                 value: json!({"id": "banana", "color": "yellow"}),
                 id: "banana".to_string(),
                 item_type: "fruit".to_string(),
+                raw: None,
+                source: None,
             },
         ];
         let index = crate::search_index::SearchIndex::build(&items);
@@ -903,6 +2656,336 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classifier_aliases_expand_to_paths() {
+        use serde_json::json;
+        let (items, index) = crate::indexed(&[
+            json!({"id": "door", "type": "furniture", "bash": {"str_min": 30}, "color": "red"}),
+            json!({"id": "wall", "type": "terrain", "bash": {"str_min": 60}, "color": "grey"}),
+            json!({"id": "red_door", "type": "furniture", "bash": {"str_min": 10}}),
+        ]);
+
+        let mut options = MatchOptions::default();
+        assert!(options.aliases.insert("str", "bash.str_min"));
+        let aliased = find_matches_with_options("str:30 t:furniture", &items, &index, &options);
+        assert_eq!(aliased.indices, vec![0]);
+        assert_eq!(
+            aliased.indices,
+            find_matches("bash.str_min:30 t:furniture", &items, &index)
+        );
+        assert!(aliased.unknown_classifiers.is_empty());
+
+        // Built-in classifiers are not shadowed by a plain alias...
+        assert!(!options.aliases.insert("t", "color"));
+        let builtin = find_matches_with_options("t:furniture", &items, &index, &options);
+        assert_eq!(builtin.indices, vec![0, 2]);
+
+        // ...only by an explicit override.
+        options.aliases.override_builtin("t", "color");
+        let overridden = find_matches_with_options("t:red", &items, &index, &options);
+        assert_eq!(
+            overridden.indices,
+            find_matches("color:red", &items, &index)
+        );
+    }
+
+    #[test]
+    fn test_warned_filter() {
+        let items: Vec<crate::data::IndexedItem> = ["a", "b", "c"]
+            .into_iter()
+            .map(|id| crate::data::IndexedItem {
+                id: id.to_string(),
+                item_type: "GUN".to_string(),
+                value: serde_json::json!({"id": id, "type": "GUN"}),
+                raw: None,
+                source: None,
+            })
+            .collect();
+        let index = crate::search_index::SearchIndex::build(&items);
+        let options = MatchOptions {
+            warned: [1].into_iter().collect(),
+            ..MatchOptions::default()
+        };
+
+        let warned = find_matches_with_options("warned:true", &items, &index, &options);
+        assert_eq!(warned.indices, vec![1]);
+        assert!(warned.unknown_classifiers.is_empty());
+        let clean = find_matches_with_options("warned:false", &items, &index, &options);
+        assert_eq!(clean.indices, vec![0, 2]);
+        let combined = find_matches_with_options("warned:true id:a", &items, &index, &options);
+        assert!(combined.indices.is_empty());
+        assert!(
+            find_matches_with_options("warned:maybe", &items, &index, &options)
+                .error
+                .is_some()
+        );
+        // Without warnings nothing is flagged.
+        assert!(find_matches("warned:true", &items, &index).is_empty());
+    }
+
+    #[test]
+    fn test_other_filter() {
+        use crate::compare::CompareStatus;
+
+        let (items, index) = crate::indexed(&[
+            json!({"id": "a", "type": "GUN"}),
+            json!({"id": "b", "type": "GUN"}),
+            json!({"id": "c", "type": "GUN"}),
+            json!({"id": "d", "type": "GUN"}),
+        ]);
+        let mut options = MatchOptions::default();
+        assert!(
+            find_matches_with_options("other:same", &items, &index, &options)
+                .error
+                .is_some()
+        );
+
+        options.compare = vec![
+            Some(CompareStatus::Same),
+            Some(CompareStatus::Changed),
+            Some(CompareStatus::Missing),
+            None,
+        ];
+        let run = |query| find_matches_with_options(query, &items, &index, &options);
+        assert_eq!(run("other:changed").indices, vec![1]);
+        assert_eq!(run("other:missing").indices, vec![2]);
+        assert_eq!(run("other:present").indices, vec![0, 1]);
+        assert_eq!(run("other:present id:b").indices, vec![1]);
+        assert!(run("other:gone").error.is_some());
+    }
+
+    #[test]
+    fn test_array_length_filter() {
+        use serde_json::json;
+        let (items, index) = crate::indexed(&[
+            json!({"id": "a", "flags": ["X", "Y", "Z", "W"], "bash": {"items": [1, 2]}}),
+            json!({"id": "b", "flags": ["X"], "bash": {"items": []}}),
+            json!({"id": "c", "flags": "NOT_AN_ARRAY"}),
+            json!({"id": "d"}),
+        ]);
+
+        assert_eq!(find_matches("flags#:>3", &items, &index), vec![0]);
+        assert_eq!(find_matches("flags#:>=1", &items, &index), vec![0, 1]);
+        assert_eq!(find_matches("flags#:=1", &items, &index), vec![1]);
+        assert_eq!(find_matches("bash.items#:0", &items, &index), vec![1]);
+        assert_eq!(find_matches("bash.items#:<5 id:a", &items, &index), vec![0]);
+
+        // A string at the path is not an array, whatever its length.
+        assert!(find_matches("flags#:<100 id:c", &items, &index).is_empty());
+        assert!(find_matches("id#:=1", &items, &index).is_empty());
+
+        let outcome = find_matches_with_outcome("flags#:lots", &items, &index);
+        assert!(outcome.error.is_some());
+    }
+
+    #[test]
+    fn test_length_filter_parse() {
+        assert_eq!(
+            LengthFilter::parse(">=2"),
+            Some(LengthFilter {
+                op: CompareOp::Ge,
+                value: 2
+            })
+        );
+        assert_eq!(
+            LengthFilter::parse("3"),
+            Some(LengthFilter {
+                op: CompareOp::Eq,
+                value: 3
+            })
+        );
+        assert_eq!(LengthFilter::parse(">x"), None);
+        assert_eq!(LengthFilter::parse(""), None);
+    }
+
+    #[test]
+    fn test_number_filter_reads_units_and_plain_numbers() {
+        use serde_json::json;
+        let (items, index) = crate::indexed(&[
+            json!({"id": "a", "volume": "250 ml", "weight": "1.5 kg"}),
+            json!({"id": "b", "volume": 150, "weight": 700}),
+            json!({"id": "c", "volume": "500ml", "pocket_data": [{"max_contains_volume": "2 L"}]}),
+            json!({"id": "d", "volume": "about 900 ml", "built": "2024-05-01"}),
+        ]);
+
+        assert_eq!(find_matches("volume:>200", &items, &index), vec![0, 2]);
+        assert_eq!(find_matches("volume:<=250", &items, &index), vec![0, 1]);
+        assert_eq!(find_matches("volume:=150", &items, &index), vec![1]);
+        assert_eq!(find_matches("weight:<2", &items, &index), vec![0]);
+        assert_eq!(
+            find_matches("pocket_data.max_contains_volume:>=2", &items, &index),
+            vec![2]
+        );
+        // Only strings starting with a number followed by a unit are read as numbers.
+        assert!(find_matches("built:>2000", &items, &index).is_empty());
+        // Without an operator, or quoted, the term is still a text search.
+        assert_eq!(find_matches("volume:250", &items, &index), vec![0]);
+        assert!(find_matches("volume:'>200'", &items, &index).is_empty());
+
+        assert_eq!(leading_number("250 ml"), Some(250.0));
+        assert_eq!(leading_number("1.5kg"), Some(1.5));
+        assert_eq!(leading_number("42"), Some(42.0));
+        assert_eq!(leading_number("about 900 ml"), None);
+        assert_eq!(leading_number("-5 ml"), None);
+        assert_eq!(leading_number("0.9.1"), None);
+        assert_eq!(NumberFilter::parse("200"), None);
+        assert_eq!(NumberFilter::parse(">abc"), None);
+    }
+
+    #[test]
+    fn test_glob_matches_whole_value() {
+        let glob = Glob::new("pistol_*").unwrap();
+        assert!(glob.matches("pistol_m1911"));
+        assert!(glob.matches("PISTOL_"));
+        assert!(!glob.matches("bb_pistol_m1911"));
+
+        let glob = Glob::new("*_mag").unwrap();
+        assert!(glob.matches("glock_mag"));
+        assert!(!glob.matches("glock_magazine"));
+
+        let glob = Glob::new("9mm_*_box").unwrap();
+        assert!(glob.matches("9mm_fmj_box"));
+        assert!(glob.matches("9mm__box"));
+        assert!(!glob.matches("9mm_fmj"));
+
+        let glob = Glob::new("a?c").unwrap();
+        assert!(glob.matches("abc"));
+        assert!(!glob.matches("ac"));
+
+        assert!(Glob::new("pistol").is_none());
+    }
+
+    #[test]
+    fn test_glob_terms_use_index_and_slow_path() {
+        use serde_json::json;
+        let (items, index) = crate::indexed(&[
+            json!({"id": "pistol_m1911", "type": "GUN", "ammo": ["45_acp"]}),
+            json!({"id": "bb_pistol", "type": "GUN", "ammo": ["bb"]}),
+            json!({"id": "pistol_mag", "type": "MAGAZINE", "ammo": ["45_acp"]}),
+        ]);
+
+        // Prefix, suffix and mid-string globs on the indexed id.
+        assert_eq!(find_matches("id:pistol_*", &items, &index), vec![0, 2]);
+        assert_eq!(find_matches("i:*_pistol", &items, &index), vec![1]);
+        assert_eq!(find_matches("id:pistol_m*1", &items, &index), vec![0]);
+        assert_eq!(find_matches("t:MAG*", &items, &index), vec![2]);
+        // Slow path over a nested field.
+        assert_eq!(find_matches("ammo:*_acp t:gun", &items, &index), vec![0]);
+        assert_eq!(find_matches("ammo:?b", &items, &index), vec![1]);
+        // Without a classifier, globs match whole words.
+        assert_eq!(find_matches("*_m1911", &items, &index), vec![0]);
+        // Quoted values stay literal.
+        assert!(find_matches("id:'pistol_*'", &items, &index).is_empty());
+    }
+
+    #[test]
+    fn test_presence_terms_tell_null_empty_and_missing_apart() {
+        let (items, index) = crate::indexed(&[
+            json!({"id": "null_desc", "type": "GUN", "description": null, "use": {"target": null}}),
+            json!({"id": "empty_desc", "type": "GUN", "description": "", "use": {"target": ""}}),
+            json!({"id": "no_desc", "type": "GUN", "use": {}}),
+            json!({"id": "has_desc", "type": "GUN", "description": "A gun.", "use": {"target": "self"}}),
+        ]);
+
+        for (path, expected) in [("description", [0, 1, 2]), ("use.target", [0, 1, 2])] {
+            let null = format!("{}:@null", path);
+            let empty = format!("{}:@EMPTY", path);
+            let missing = format!("{}:@missing", path);
+            assert_eq!(find_matches(&null, &items, &index), vec![expected[0]]);
+            assert_eq!(find_matches(&empty, &items, &index), vec![expected[1]]);
+            assert_eq!(find_matches(&missing, &items, &index), vec![expected[2]]);
+
+            // `Query` agrees with the indexed search on every item.
+            for query in [&null, &empty, &missing] {
+                let query_matches: Vec<usize> = items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| Query::parse(query).matches(&item.value))
+                    .map(|(idx, _)| idx)
+                    .collect();
+                assert_eq!(
+                    query_matches,
+                    find_matches(query, &items, &index),
+                    "{}",
+                    query
+                );
+            }
+        }
+
+        // Shorthand classifiers resolve to their fields; `c:` is absent everywhere.
+        assert_eq!(find_matches("c:@missing", &items, &index), vec![0, 1, 2, 3]);
+        assert!(find_matches("i:@missing", &items, &index).is_empty());
+        // Quoted pseudo-values are searched for literally.
+        assert!(find_matches("description:'@null'", &items, &index).is_empty());
+    }
+
+    #[test]
+    fn test_completion_candidates_for_partial_terms() {
+        let (_, index) = crate::indexed(&[
+            json!({"id": "glock_19", "type": "GUN", "category": "guns"}),
+            json!({"id": "m1911", "type": "GUN", "category": "guns"}),
+            json!({"id": "gunpowder", "type": "AMMO", "category": "chems"}),
+            json!({"id": "gum", "type": "GENERIC", "category": "food", "name": "bubble gum"}),
+        ]);
+        let complete = |query: &str| -> Vec<&str> {
+            completion_token(query, query.len())
+                .map(|token| completion_candidates(&index, &token, 10))
+                .unwrap_or_default()
+        };
+
+        // The partial value after the classifier, with its place in the query.
+        let token = completion_token("name:bubble t:G", 15).unwrap();
+        assert_eq!(token.classifier.as_deref(), Some("t"));
+        assert_eq!(token.prefix, "G");
+        assert_eq!(token.range, 14..15);
+
+        // Types: the most used first, ties alphabetically.
+        assert_eq!(complete("t:g"), ["gun", "generic"]);
+        assert_eq!(complete("type:a"), ["ammo"]);
+        assert_eq!(complete("t:"), ["gun", "ammo", "generic"]);
+        assert_eq!(complete("count:c:g"), ["guns"]);
+        assert_eq!(complete("i:gl"), ["glock_19"]);
+        assert_eq!(complete("key:cat"), ["category"]);
+        // Bare words complete from the word index; a complete word offers nothing.
+        assert_eq!(complete("zombie gu"), ["gun", "guns", "gum", "gunpowder"]);
+        assert!(complete("gunpowder").is_empty());
+        // Unindexed classifiers and quoted values aren't completed.
+        assert!(complete("name:bu").is_empty());
+        assert!(complete("t:'g").is_empty());
+        assert!(complete("t:g ").is_empty());
+        assert!(complete("g").is_empty());
+        // Only the term ending at the cursor is completed.
+        assert!(completion_token("t:g zombie", 3).is_some());
+        assert!(completion_token("t:g zombie", 2).is_none());
+    }
+
+    #[test]
+    fn test_key_classifier_finds_defined_fields() {
+        use serde_json::json;
+        let (items, index) = crate::indexed(&[
+            json!({"id": "backpack", "type": "ARMOR", "pocket_data": [{"rigid": true}]}),
+            json!({"id": "jacket", "type": "ARMOR", "pocket_data": []}),
+            json!({"id": "rock", "type": "GENERIC", "description": "no pocket_data here"}),
+        ]);
+
+        assert_eq!(
+            find_matches("key:'pocket_data'", &items, &index),
+            vec![0, 1]
+        );
+        assert_eq!(find_matches("key:rigid", &items, &index), vec![0]);
+        assert_eq!(
+            find_matches("key:pocket_* key:rig*", &items, &index),
+            vec![0]
+        );
+        // The generic search still only sees values.
+        assert_eq!(find_matches("pocket_data", &items, &index), vec![2]);
+        assert!(
+            find_matches_with_outcome("key:nothing", &items, &index)
+                .unknown_classifiers
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_search_intersection_optimization() {
         use serde_json::json;
@@ -918,6 +3001,8 @@ mod tests {
                 value: json!({"id": id, "type": type_, "category": cat}),
                 id,
                 item_type: type_.to_string(),
+                raw: None,
+                source: None,
             });
         }
 
@@ -976,7 +3061,9 @@ mod tests {
                     "array": ["one", "two", "three", "four", "five"]
                 }),
                 id: format!("item_{}", i),
-                item_type: "item".to_string()
+                item_type: "item".to_string(),
+                raw: None,
+                source: None,
             });
         }
 
@@ -0,0 +1,76 @@
+/// How many recently viewed ids are remembered.
+pub const RECENT_LIMIT: usize = 50;
+
+/// Ids of recently viewed objects, most recent first and without duplicates.
+/// Carried between launches in the session.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RecentItems {
+    ids: Vec<String>,
+}
+
+impl RecentItems {
+    /// Restores a saved list, dropping blanks, repeats and anything past the limit.
+    pub fn from_ids(ids: impl IntoIterator<Item = String>) -> Self {
+        let mut recent = Self::default();
+        for id in ids {
+            if recent.ids.len() == RECENT_LIMIT {
+                break;
+            }
+            if !id.is_empty() && !recent.ids.contains(&id) {
+                recent.ids.push(id);
+            }
+        }
+        recent
+    }
+
+    /// Recently viewed ids, most recent first.
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    /// Moves `id` to the front, forgetting the oldest entry once the list is full.
+    pub fn push(&mut self, id: &str) {
+        if id.is_empty() || self.ids.first().is_some_and(|first| first == id) {
+            return;
+        }
+        self.ids.retain(|recent| recent != id);
+        self.ids.insert(0, id.to_string());
+        self.ids.truncate(RECENT_LIMIT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_items_dedup_and_cap() {
+        let mut recent = RecentItems::default();
+        recent.push("rifle");
+        recent.push("pistol");
+        recent.push("");
+        recent.push("rifle");
+        assert_eq!(recent.ids(), ["rifle", "pistol"]);
+
+        for idx in 0..RECENT_LIMIT {
+            recent.push(&format!("item_{}", idx));
+        }
+        assert_eq!(recent.ids().len(), RECENT_LIMIT);
+        assert_eq!(recent.ids()[0], format!("item_{}", RECENT_LIMIT - 1));
+        assert!(
+            !recent
+                .ids()
+                .iter()
+                .any(|id| id == "rifle" || id == "pistol")
+        );
+
+        let restored = RecentItems::from_ids(
+            ["a", "b", "a", "", "c"]
+                .into_iter()
+                .map(str::to_string)
+                .chain((0..RECENT_LIMIT).map(|idx| idx.to_string())),
+        );
+        assert_eq!(restored.ids()[..3], ["a", "b", "c"]);
+        assert_eq!(restored.ids().len(), RECENT_LIMIT);
+    }
+}
@@ -1,10 +1,24 @@
-use crate::data::IndexedItem;
+use crate::data::{Cancelled, IndexedItem, string_members, write_atomically};
+use anyhow::Result;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use foldhash::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::fs;
+use std::io::BufReader;
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Layout of a saved index. Bump it when the maps or the tokenization change,
+/// so indexes saved by older builds are rebuilt instead of used.
+pub const INDEX_FORMAT: u32 = 1;
 
 /// Inverted index for fast search across 30k+ items
 /// Indexes common fields (id/abstract, type, category) and tokenized words
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SearchIndex {
     /// Index for id OR abstract (mutually exclusive in data)
     pub by_id: HashMap<String, HashSet<usize>>,
@@ -14,6 +28,119 @@ pub struct SearchIndex {
     pub by_category: HashMap<String, HashSet<usize>>,
     /// Word index for fast text search (tokenized from id, name, type, category)
     pub word_index: HashMap<String, HashSet<usize>>,
+    /// Object keys at any depth, for `key:` searches (kept out of `word_index`)
+    pub by_key: HashMap<String, HashSet<usize>>,
+    /// Keys of every map in sorted order for completion, built on first use
+    /// (and so left out of saved indexes)
+    #[serde(skip)]
+    sorted_keys: OnceLock<SortedKeyLists>,
+}
+
+/// Keys of one inverted map in sorted order, each with the number of items it holds,
+/// so keys sharing a prefix are found with a binary search.
+#[derive(Debug, Default)]
+pub struct SortedKeys {
+    keys: Vec<(String, usize)>,
+}
+
+impl SortedKeys {
+    fn of(map: &HashMap<String, HashSet<usize>>) -> Self {
+        let mut keys: Vec<(String, usize)> = map
+            .iter()
+            .map(|(key, indices)| (key.clone(), indices.len()))
+            .collect();
+        keys.sort_unstable();
+        Self { keys }
+    }
+
+    /// Up to `limit` keys that start with `prefix` (ignoring case) and extend it,
+    /// the ones found on most items first.
+    pub fn complete(&self, prefix: &str, limit: usize) -> Vec<&str> {
+        let prefix = prefix.to_lowercase();
+        let start = self
+            .keys
+            .partition_point(|(key, _)| key.as_str() < prefix.as_str());
+        let mut found: Vec<(&str, usize)> = self.keys[start..]
+            .iter()
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .filter(|(key, _)| key.len() > prefix.len())
+            .map(|(key, count)| (key.as_str(), *count))
+            .collect();
+        found.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        found.truncate(limit);
+        found.into_iter().map(|(key, _)| key).collect()
+    }
+}
+
+/// A `SortedKeys` for every map in a `SearchIndex`.
+#[derive(Debug, Default)]
+pub struct SortedKeyLists {
+    pub by_id: SortedKeys,
+    pub by_type: SortedKeys,
+    pub by_category: SortedKeys,
+    pub words: SortedKeys,
+    pub by_key: SortedKeys,
+}
+
+/// Size of one inverted map: distinct keys and the item references they hold.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MapStats {
+    pub keys: usize,
+    pub postings: usize,
+}
+
+impl MapStats {
+    fn of(map: &HashMap<String, HashSet<usize>>) -> Self {
+        Self {
+            keys: map.len(),
+            postings: map.values().map(HashSet::len).sum(),
+        }
+    }
+}
+
+/// Sizes of every map in a `SearchIndex`, as reported by `--stats`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IndexStats {
+    pub by_id: MapStats,
+    pub by_type: MapStats,
+    pub by_category: MapStats,
+    pub words: MapStats,
+    pub by_key: MapStats,
+}
+
+impl IndexStats {
+    /// Sum over all maps.
+    pub fn total(&self) -> MapStats {
+        [
+            self.by_id,
+            self.by_type,
+            self.by_category,
+            self.words,
+            self.by_key,
+        ]
+        .into_iter()
+        .fold(MapStats::default(), |acc, map| MapStats {
+            keys: acc.keys + map.keys,
+            postings: acc.postings + map.postings,
+        })
+    }
+}
+
+/// A saved index, tagged with what it is valid for.
+#[derive(Serialize, Deserialize)]
+struct SavedIndex<I> {
+    format: u32,
+    /// Version of the program that built it; item order and tokens may differ between versions.
+    app_version: String,
+    /// Checksum of the data the index was built from, as in `Root::checksum`.
+    dataset: u64,
+    index: I,
+}
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SearchIndex {
@@ -24,6 +151,8 @@ impl SearchIndex {
             by_type: Default::default(),
             by_category: Default::default(),
             word_index: Default::default(),
+            by_key: Default::default(),
+            sorted_keys: OnceLock::new(),
         }
     }
 
@@ -37,99 +166,99 @@ impl SearchIndex {
         let mut index = Self::new();
 
         for (idx, item) in items.iter().enumerate() {
-            let json = &item.value;
-            let id = &item.id;
-            let type_ = &item.item_type;
-
-            // Index primary search fields
-            if !id.is_empty() {
-                index
-                    .by_id
-                    .entry(id.to_lowercase())
-                    .or_default()
-                    .insert(idx);
-            } else if let Some(abstr) = json.get("abstract").and_then(|v| v.as_str()) {
-                index
-                    .by_id
-                    .entry(abstr.to_lowercase())
-                    .or_default()
-                    .insert(idx);
-            }
-
-            if !type_.is_empty() {
-                index
-                    .by_type
-                    .entry(type_.to_lowercase())
-                    .or_default()
-                    .insert(idx);
-            }
-
-            if let Some(category) = json.get("category").and_then(|v| v.as_str()) {
-                index
-                    .by_category
-                    .entry(category.to_lowercase())
-                    .or_default()
-                    .insert(idx);
-            }
-
-            // Recursively index EVERYTHING in the JSON. Note: This covers the fields above,
-            // so we don't need to explicitly call index_words for them here.
-            Self::index_value_recursive(&mut index.word_index, json, idx);
+            index.index_item(idx, item);
         }
 
         index
     }
 
-    pub fn build_with_progress<F>(items: &[IndexedItem], mut on_progress: F) -> Self
+    /// Writes the index to `path` as gzipped JSON, tagged with `dataset`, the
+    /// checksum of the data it was built from (see `Root::checksum`).
+    pub fn save(&self, path: &Path, dataset: u64) -> Result<()> {
+        let saved = SavedIndex {
+            format: INDEX_FORMAT,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            dataset,
+            index: self,
+        };
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        serde_json::to_writer(&mut encoder, &saved)?;
+        write_atomically(path, &encoder.finish()?)?;
+        Ok(())
+    }
+
+    /// Reads an index written by [`SearchIndex::save`]. Returns `None` if the
+    /// file is missing or unreadable, was saved by another format or version,
+    /// or indexes other data than `dataset`; the index is then rebuilt.
+    pub fn load(path: &Path, dataset: u64) -> Option<Self> {
+        let reader = BufReader::new(GzDecoder::new(BufReader::new(fs::File::open(path).ok()?)));
+        let saved: SavedIndex<Self> = serde_json::from_reader(reader).ok()?;
+        let current = saved.format == INDEX_FORMAT
+            && saved.app_version == env!("CARGO_PKG_VERSION")
+            && saved.dataset == dataset;
+        current.then_some(saved.index)
+    }
+
+    /// Builds the index, reporting `(processed, total)` periodically.
+    /// Returning `ControlFlow::Break` from `on_progress` stops early with [`Cancelled`].
+    pub fn build_with_progress<F>(
+        items: &[IndexedItem],
+        mut on_progress: F,
+    ) -> Result<Self, Cancelled>
     where
-        F: FnMut(usize, usize),
+        F: FnMut(usize, usize) -> ControlFlow<()>,
     {
         let mut index = Self::new();
         let total = items.len();
 
         for (idx, item) in items.iter().enumerate() {
-            let json = &item.value;
-            let id = &item.id;
-            let type_ = &item.item_type;
-
-            if !id.is_empty() {
-                index
-                    .by_id
-                    .entry(id.to_lowercase())
-                    .or_default()
-                    .insert(idx);
-            } else if let Some(abstr) = json.get("abstract").and_then(|v| v.as_str()) {
-                index
-                    .by_id
-                    .entry(abstr.to_lowercase())
-                    .or_default()
-                    .insert(idx);
-            }
+            index.index_item(idx, item);
 
-            if !type_.is_empty() {
-                index
-                    .by_type
-                    .entry(type_.to_lowercase())
-                    .or_default()
-                    .insert(idx);
+            if (idx % 250 == 0 || idx + 1 == total) && on_progress(idx + 1, total).is_break() {
+                return Err(Cancelled);
             }
+        }
 
-            if let Some(category) = json.get("category").and_then(|v| v.as_str()) {
-                index
-                    .by_category
-                    .entry(category.to_lowercase())
-                    .or_default()
-                    .insert(idx);
-            }
+        Ok(index)
+    }
 
-            Self::index_value_recursive(&mut index.word_index, json, idx);
+    /// Adds one item to every map. Array `id`s and `type`s are indexed under
+    /// each member, besides the item's primary id and type.
+    fn index_item(&mut self, idx: usize, item: &IndexedItem) {
+        let json = &item.value;
+
+        let mut ids: Vec<&str> = std::iter::once(item.id.as_str())
+            .chain(string_members(json.get("id")))
+            .filter(|id| !id.is_empty())
+            .collect();
+        if ids.is_empty() {
+            // Templates are referred to by their abstract name instead.
+            ids.extend(json.get("abstract").and_then(Value::as_str));
+        }
+        for id in ids {
+            self.by_id.entry(id.to_lowercase()).or_default().insert(idx);
+        }
 
-            if idx % 250 == 0 || idx + 1 == total {
-                on_progress(idx + 1, total);
-            }
+        let types =
+            std::iter::once(item.item_type.as_str()).chain(string_members(json.get("type")));
+        for type_ in types.filter(|type_| !type_.is_empty()) {
+            self.by_type
+                .entry(type_.to_lowercase())
+                .or_default()
+                .insert(idx);
         }
 
-        index
+        if let Some(category) = json.get("category").and_then(|v| v.as_str()) {
+            self.by_category
+                .entry(category.to_lowercase())
+                .or_default()
+                .insert(idx);
+        }
+
+        // Recursively index EVERYTHING in the JSON. Note: This covers the fields above,
+        // so we don't need to explicitly call index_words for them here.
+        Self::index_value_recursive(&mut self.word_index, json, idx);
+        Self::index_keys_recursive(&mut self.by_key, json, idx);
     }
 
     /// Recursively index all string values in JSON for word search
@@ -156,6 +285,36 @@ impl SearchIndex {
         }
     }
 
+    /// Recursively index the keys of all objects in JSON, nested ones included
+    fn index_keys_recursive(
+        by_key: &mut HashMap<String, HashSet<usize>>,
+        value: &Value,
+        idx: usize,
+    ) {
+        match value {
+            Value::Array(arr) => {
+                for item in arr {
+                    Self::index_keys_recursive(by_key, item, idx);
+                }
+            }
+            Value::Object(obj) => {
+                for (key, val) in obj {
+                    // Keys are nearly always lowercase already; skip the allocation then.
+                    match by_key.get_mut(key.as_str()) {
+                        Some(set) => {
+                            set.insert(idx);
+                        }
+                        None => {
+                            by_key.entry(key.to_lowercase()).or_default().insert(idx);
+                        }
+                    }
+                    Self::index_keys_recursive(by_key, val, idx);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Tokenize and index words from a string.
     ///
     /// This is a hot path. We optimize for the common case where words are already lowercase
@@ -183,6 +342,17 @@ impl SearchIndex {
         }
     }
 
+    /// Counts keys and postings in each map, for memory reports.
+    pub fn stats(&self) -> IndexStats {
+        IndexStats {
+            by_id: MapStats::of(&self.by_id),
+            by_type: MapStats::of(&self.by_type),
+            by_category: MapStats::of(&self.by_category),
+            words: MapStats::of(&self.word_index),
+            by_key: MapStats::of(&self.by_key),
+        }
+    }
+
     /// Fast lookup in a specific field index 
     /// Returns indices of items matching the pattern
     pub fn lookup_field(
@@ -206,6 +376,31 @@ impl SearchIndex {
         }
     }
 
+    /// Returns indices of items whose key in `field_index` satisfies `matches`.
+    /// Used for patterns, such as globs, that a hash lookup can't answer.
+    pub fn lookup_field_matching(
+        &self,
+        field_index: &HashMap<String, HashSet<usize>>,
+        matches: impl Fn(&str) -> bool,
+    ) -> HashSet<usize> {
+        field_index
+            .iter()
+            .filter(|(key, _)| matches(key))
+            .flat_map(|(_, indices)| indices.iter().copied())
+            .collect()
+    }
+
+    /// Sorted keys of every map, for completion. The first call sorts them.
+    pub fn sorted_keys(&self) -> &SortedKeyLists {
+        self.sorted_keys.get_or_init(|| SortedKeyLists {
+            by_id: SortedKeys::of(&self.by_id),
+            by_type: SortedKeys::of(&self.by_type),
+            by_category: SortedKeys::of(&self.by_category),
+            words: SortedKeys::of(&self.word_index),
+            by_key: SortedKeys::of(&self.by_key),
+        })
+    }
+
     /// Fast word-based text search.
     /// Returns indices of items containing words that match the pattern.
     pub fn search_words(&self, pattern: &str) -> HashSet<usize> {
@@ -225,6 +420,71 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_build_with_progress_stops_when_cancelled() {
+        let items: Vec<IndexedItem> = (0..1000)
+            .map(|i| IndexedItem {
+                value: json!({"id": format!("item_{}", i), "type": "TOOL"}),
+                id: format!("item_{}", i),
+                item_type: "TOOL".to_string(),
+                raw: None,
+                source: None,
+            })
+            .collect();
+
+        let mut cancel = false;
+        let mut reports = Vec::new();
+        let result = SearchIndex::build_with_progress(&items, |processed, _| {
+            reports.push(processed);
+            // Flag set (e.g. by Esc) after the first report.
+            let stop = cancel;
+            cancel = true;
+            if stop {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+        assert_eq!(result.err(), Some(Cancelled));
+        assert_eq!(reports, vec![1, 251]);
+    }
+
+    #[test]
+    fn test_saved_index_answers_queries_like_a_fresh_one() {
+        let (items, built) = crate::indexed(&[
+            json!({"id": "rifle", "type": "GUN", "category": "weapons", "name": "hunting rifle"}),
+            json!({"abstract": "gun_base", "type": "GUN", "pocket_data": [{"ammo": "308"}]}),
+            json!({"id": "308", "type": "AMMO", "description": "Rifle cartridge"}),
+        ]);
+        let path = crate::test_dir("saved_index").join("search_index.json.gz");
+        let dataset = 0x5eed;
+        built.save(&path, dataset).unwrap();
+
+        let loaded = SearchIndex::load(&path, dataset).unwrap();
+        assert_eq!(loaded.stats(), built.stats());
+        for query in [
+            "t:gun",
+            "i:gun_base",
+            "c:weapons",
+            "rifle",
+            "key:ammo",
+            "cart*",
+        ] {
+            assert_eq!(
+                crate::matcher::find_matches(query, &items, &loaded),
+                crate::matcher::find_matches(query, &items, &built),
+                "{}",
+                query
+            );
+        }
+
+        // An index of other data, or no saved index at all, is not used.
+        assert!(SearchIndex::load(&path, dataset + 1).is_none());
+        fs::remove_file(&path).unwrap();
+        assert!(SearchIndex::load(&path, dataset).is_none());
+    }
+
     #[test]
     fn test_index_building() {
         let items = vec![
@@ -232,11 +492,15 @@ mod tests {
                 value: json!({"id": "test_item", "type": "TOOL", "category": "weapons"}),
                 id: "test_item".to_string(),
                 item_type: "TOOL".to_string(),
+                raw: None,
+                source: None,
             },
             IndexedItem {
                 value: json!({"abstract": "abstract_base", "type": "MONSTER"}),
                 id: "".to_string(),
                 item_type: "MONSTER".to_string(),
+                raw: None,
+                source: None,
             },
         ];
 
@@ -262,6 +526,27 @@ mod tests {
         assert!(index.word_index.contains_key("weapons"));
     }
 
+    #[test]
+    fn test_array_ids_and_types_index_every_member() {
+        let raw = |json: &str| serde_json::value::RawValue::from_string(json.to_string()).unwrap();
+        let items: Vec<IndexedItem> = [
+            r#"{"id": ["rifle", "rifle_old"], "type": "GUN"}"#,
+            r#"{"id": "mutant", "type": ["MONSTER", "mutation"]}"#,
+        ]
+        .into_iter()
+        .map(|json| crate::data::index_raw_item(raw(json)).unwrap())
+        .collect();
+        // The first member is the primary, used for display and sorting.
+        assert_eq!(items[0].id, "rifle");
+        assert_eq!(items[1].item_type, "MONSTER");
+
+        let index = SearchIndex::build(&items);
+        assert!(index.by_id["rifle"].contains(&0));
+        assert!(index.by_id["rifle_old"].contains(&0));
+        assert!(index.by_type["monster"].contains(&1));
+        assert!(index.by_type["mutation"].contains(&1));
+    }
+
     #[test]
     fn test_lookup_exact() {
         let items = vec![
@@ -269,11 +554,15 @@ mod tests {
                 value: json!({"id": "test_item", "type": "TOOL"}),
                 id: "test_item".to_string(),
                 item_type: "TOOL".to_string(),
+                raw: None,
+                source: None,
             },
             IndexedItem {
                 value: json!({"id": "test_weapon", "type": "TOOL"}),
                 id: "test_weapon".to_string(),
                 item_type: "TOOL".to_string(),
+                raw: None,
+                source: None,
             },
         ];
 
@@ -292,11 +581,15 @@ mod tests {
                 value: json!({"id": "test_item", "type": "TOOL"}),
                 id: "test_item".to_string(),
                 item_type: "TOOL".to_string(),
+                raw: None,
+                source: None,
             },
             IndexedItem {
                 value: json!({"id": "test_weapon", "type": "TOOL"}),
                 id: "test_weapon".to_string(),
                 item_type: "TOOL".to_string(),
+                raw: None,
+                source: None,
             },
         ];
 
@@ -313,6 +606,8 @@ mod tests {
             value: json!({"id": "zombie_soldier", "type": "MONSTER", "name": "Zombie Soldier"}),
             id: "zombie_soldier".to_string(),
             item_type: "MONSTER".to_string(),
+            raw: None,
+            source: None,
         }];
 
         let index = SearchIndex::build(&items);
@@ -324,4 +619,90 @@ mod tests {
         let results = index.search_words("soldier");
         assert_eq!(results.len(), 1);
     }
+
+    #[test]
+    fn test_by_key_indexes_nested_keys_only() {
+        let items = vec![
+            IndexedItem {
+                value: json!({
+                    "id": "backpack",
+                    "pocket_data": [{"max_contains_volume": "15 L"}]
+                }),
+                id: "backpack".to_string(),
+                item_type: "ARMOR".to_string(),
+                raw: None,
+                source: None,
+            },
+            IndexedItem {
+                value: json!({"id": "rock", "Weight": 600, "description": "pocket_data"}),
+                id: "rock".to_string(),
+                item_type: "GENERIC".to_string(),
+                raw: None,
+                source: None,
+            },
+        ];
+        let index = SearchIndex::build(&items);
+
+        assert_eq!(index.by_key["pocket_data"], HashSet::from_iter([0]));
+        assert_eq!(index.by_key["max_contains_volume"], HashSet::from_iter([0]));
+        assert_eq!(index.by_key["weight"], HashSet::from_iter([1]));
+        assert_eq!(index.by_key["id"], HashSet::from_iter([0, 1]));
+        // Keys stay out of the word index: only the description mentions pocket_data.
+        assert_eq!(index.search_words("max_contains"), HashSet::default());
+        assert_eq!(index.search_words("pocket_data"), HashSet::from_iter([1]));
+    }
+
+    #[test]
+    fn test_stats_counts_keys_and_postings() {
+        let (_, index) = crate::indexed(&[
+            json!({"id": "rifle", "type": "GUN", "name": "hunting rifle"}),
+            json!({"id": "pistol", "type": "GUN", "category": "guns"}),
+        ]);
+        let stats = index.stats();
+
+        assert_eq!(
+            stats.by_id,
+            MapStats {
+                keys: 2,
+                postings: 2
+            }
+        );
+        assert_eq!(
+            stats.by_type,
+            MapStats {
+                keys: 1,
+                postings: 2
+            }
+        );
+        assert_eq!(
+            stats.by_category,
+            MapStats {
+                keys: 1,
+                postings: 1
+            }
+        );
+        // rifle, gun, hunting, pistol, guns; "gun" is shared.
+        assert_eq!(
+            stats.words,
+            MapStats {
+                keys: 5,
+                postings: 6
+            }
+        );
+        // id, type, name, category.
+        assert_eq!(
+            stats.by_key,
+            MapStats {
+                keys: 4,
+                postings: 6
+            }
+        );
+        assert_eq!(
+            stats.total(),
+            MapStats {
+                keys: 13,
+                postings: 17
+            }
+        );
+    }
 }
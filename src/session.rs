@@ -0,0 +1,112 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// State carried over between launches, stored as `session.toml` in the data dir.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Filter text at the time of quitting.
+    pub filter: String,
+    /// Name of the theme in use (as accepted by `--theme`).
+    pub theme: Option<String>,
+    /// Recently viewed object ids, most recent first.
+    pub recent: Vec<String>,
+    /// Game version last loaded from each download source, keyed by source name.
+    pub versions: BTreeMap<String, String>,
+    /// Name of the download source in use at the time of quitting.
+    pub source: Option<String>,
+}
+
+impl Session {
+    /// Loads the session, falling back to an empty one if the file is missing or invalid.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the session, creating the parent directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The version last loaded from `source`, if the previous launch used
+    /// another source. Relaunching with the same source keeps the default.
+    pub fn version_after_switch_to(&self, source: &str) -> Option<&str> {
+        if self.source.as_deref().is_none_or(|last| last == source) {
+            return None;
+        }
+        self.versions.get(source).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip() {
+        let path = crate::test_dir("session").join("nested/session.toml");
+
+        let session = Session {
+            filter: "t:GUN ammo:'9mm'".to_string(),
+            theme: Some("gruvbox".to_string()),
+            recent: vec!["rifle".to_string(), "mon_zombie".to_string()],
+            versions: BTreeMap::from([
+                ("cbn".to_string(), "2024-06-12".to_string()),
+                ("dda".to_string(), "stable".to_string()),
+            ]),
+            source: Some("dda".to_string()),
+        };
+        session.save(&path).unwrap();
+        assert_eq!(Session::load(&path), session);
+    }
+
+    #[test]
+    fn test_session_load_missing_or_invalid() {
+        let path = crate::test_dir("session_invalid").join("session.toml");
+        assert_eq!(Session::load(&path), Session::default());
+
+        fs::write(&path, "filter = [not toml").unwrap();
+        assert_eq!(Session::load(&path), Session::default());
+
+        // Missing keys fall back to defaults.
+        fs::write(&path, "filter = \"zombie\"\n").unwrap();
+        assert_eq!(
+            Session::load(&path),
+            Session {
+                filter: "zombie".to_string(),
+                theme: None,
+                recent: Vec::new(),
+                versions: BTreeMap::new(),
+                source: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_version_restored_only_after_source_switch() {
+        let mut session = Session {
+            versions: BTreeMap::from([
+                ("cbn".to_string(), "2024-06-12".to_string()),
+                ("dda".to_string(), "stable".to_string()),
+            ]),
+            source: Some("cbn".to_string()),
+            ..Session::default()
+        };
+        assert_eq!(session.version_after_switch_to("cbn"), None);
+        assert_eq!(session.version_after_switch_to("dda"), Some("stable"));
+        assert_eq!(session.version_after_switch_to("mirror"), None);
+
+        session.source = None;
+        assert_eq!(session.version_after_switch_to("dda"), None);
+    }
+}
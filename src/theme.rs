@@ -8,6 +8,7 @@ pub enum Theme {
     Solarized,
     Gruvbox,
     EverforestLight,
+    Monochrome,
 }
 
 impl Theme {
@@ -18,12 +19,19 @@ impl Theme {
             Self::Solarized => solarized_dark(),
             Self::Gruvbox => gruvbox_theme(),
             Self::EverforestLight => everforest_light_theme(),
+            Self::Monochrome => monochrome_theme(),
         }
     }
 
     /// Returns a list of all available theme names as strings.
     pub fn variants() -> &'static [&'static str] {
-        &["dracula", "solarized", "gruvbox", "everforest_light"]
+        &[
+            "dracula",
+            "solarized",
+            "gruvbox",
+            "everforest_light",
+            "monochrome",
+        ]
     }
 }
 
@@ -36,6 +44,7 @@ impl FromStr for Theme {
             "solarized" => Ok(Self::Solarized),
             "gruvbox" => Ok(Self::Gruvbox),
             "everforest_light" => Ok(Self::EverforestLight),
+            "monochrome" => Ok(Self::Monochrome),
             _ => Err(format!(
                 "Unknown theme: {}. Available: {}",
                 s,
@@ -48,10 +57,10 @@ impl FromStr for Theme {
 /// Style for JSON highlighting
 #[derive(Clone, Copy)]
 pub struct JsonStyle {
-    pub key: Color,
-    pub string: Color,
-    pub number: Color,
-    pub boolean: Color,
+    pub key: Style,
+    pub string: Style,
+    pub number: Style,
+    pub boolean: Style,
 }
 
 /// Complete theme configuration for ratatui.
@@ -88,9 +97,39 @@ pub struct ThemeConfig {
     pub border_selected: Style,
     pub title: Style,
     pub text: Style,
+    /// Style patched onto details spans that matched the current query.
+    pub search_match: Style,
+    /// Gutter markers for lines added, removed and changed in the diff view.
+    pub diff_added: Style,
+    pub diff_removed: Style,
+    pub diff_changed: Style,
+    /// Unterminated quotes in the filter input.
+    pub query_error: Style,
+    /// Markers for objects with source warnings.
+    pub warning: Style,
     pub json_style: JsonStyle,
 }
 
+impl ThemeConfig {
+    /// Style of every other list row: `list_normal` with its background nudged
+    /// away from black or white. Themes without an RGB background don't stripe.
+    pub fn list_stripe(&self) -> Style {
+        let Some(Color::Rgb(r, g, b)) = self.list_normal.bg else {
+            return self.list_normal;
+        };
+        let lighten = u16::from(r) + u16::from(g) + u16::from(b) < 384;
+        let shift = |c: u8| {
+            if lighten {
+                c.saturating_add(12)
+            } else {
+                c.saturating_sub(12)
+            }
+        };
+        self.list_normal
+            .bg(Color::Rgb(shift(r), shift(g), shift(b)))
+    }
+}
+
 /// Returns a ThemeConfig based on the Solarized Dark color palette.
 #[allow(unused_variables)]
 pub fn solarized_dark() -> ThemeConfig {
@@ -109,10 +148,10 @@ pub fn solarized_dark() -> ThemeConfig {
     let green = Color::Rgb(133, 153, 0);
 
     let json_style = JsonStyle {
-        key: cyan,
-        string: green,
-        number: magenta,
-        boolean: red,
+        key: Style::default().fg(cyan),
+        string: Style::default().fg(green),
+        number: Style::default().fg(magenta),
+        boolean: Style::default().fg(red),
     };
 
     ThemeConfig {
@@ -126,6 +165,12 @@ pub fn solarized_dark() -> ThemeConfig {
         border_selected: Style::default().fg(blue),
         title: Style::default().fg(blue).add_modifier(Modifier::BOLD),
         text: Style::default().fg(base0).bg(base02),
+        search_match: Style::default().fg(base03).bg(yellow),
+        diff_added: Style::default().fg(green).add_modifier(Modifier::BOLD),
+        diff_removed: Style::default().fg(red).add_modifier(Modifier::BOLD),
+        diff_changed: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
+        query_error: Style::default().fg(red).add_modifier(Modifier::UNDERLINED),
+        warning: Style::default().fg(red).add_modifier(Modifier::BOLD),
         json_style,
     }
 }
@@ -143,12 +188,14 @@ pub fn dracula_theme() -> ThemeConfig {
     let orange = Color::Rgb(255, 184, 108);
     let pink = Color::Rgb(255, 121, 198);
     let cyan = Color::Rgb(139, 233, 253);
+    let green = Color::Rgb(80, 250, 123);
+    let red = Color::Rgb(255, 85, 85);
 
     let json_style = JsonStyle {
-        key: cyan,
-        string: yellow,
-        number: orange,
-        boolean: pink,
+        key: Style::default().fg(cyan),
+        string: Style::default().fg(yellow),
+        number: Style::default().fg(orange),
+        boolean: Style::default().fg(pink),
     };
 
     ThemeConfig {
@@ -162,6 +209,12 @@ pub fn dracula_theme() -> ThemeConfig {
         border_selected: Style::default().fg(purple),
         title: Style::default().fg(purple).add_modifier(Modifier::BOLD),
         text: Style::default().fg(fg).bg(bg),
+        search_match: Style::default().fg(bg).bg(green),
+        diff_added: Style::default().fg(green).add_modifier(Modifier::BOLD),
+        diff_removed: Style::default().fg(red).add_modifier(Modifier::BOLD),
+        diff_changed: Style::default().fg(orange).add_modifier(Modifier::BOLD),
+        query_error: Style::default().fg(red).add_modifier(Modifier::UNDERLINED),
+        warning: Style::default().fg(red).add_modifier(Modifier::BOLD),
         json_style,
     }
 }
@@ -179,12 +232,14 @@ pub fn gruvbox_theme() -> ThemeConfig {
     let green = Color::Rgb(152, 151, 26);
     let orange = Color::Rgb(214, 93, 14);
     let purple = Color::Rgb(177, 98, 134);
+    let yellow = Color::Rgb(215, 153, 33);
+    let red = Color::Rgb(204, 36, 29);
 
     let json_style = JsonStyle {
-        key: blue,
-        string: green,
-        number: purple,
-        boolean: orange,
+        key: Style::default().fg(blue),
+        string: Style::default().fg(green),
+        number: Style::default().fg(purple),
+        boolean: Style::default().fg(orange),
     };
 
     ThemeConfig {
@@ -198,6 +253,12 @@ pub fn gruvbox_theme() -> ThemeConfig {
         border_selected: Style::default().fg(orange),
         title: Style::default().fg(orange).add_modifier(Modifier::BOLD),
         text: Style::default().fg(fg1).bg(bg0),
+        search_match: Style::default().fg(bg0).bg(yellow),
+        diff_added: Style::default().fg(green).add_modifier(Modifier::BOLD),
+        diff_removed: Style::default().fg(red).add_modifier(Modifier::BOLD),
+        diff_changed: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
+        query_error: Style::default().fg(red).add_modifier(Modifier::UNDERLINED),
+        warning: Style::default().fg(red).add_modifier(Modifier::BOLD),
         json_style,
     }
 }
@@ -217,10 +278,10 @@ pub fn everforest_light_theme() -> ThemeConfig {
     let magenta = Color::Rgb(223, 105, 186);
 
     let json_style = JsonStyle {
-        key: blue,
-        string: green,
-        number: red,
-        boolean: magenta,
+        key: Style::default().fg(blue),
+        string: Style::default().fg(green),
+        number: Style::default().fg(red),
+        boolean: Style::default().fg(magenta),
     };
 
     ThemeConfig {
@@ -234,6 +295,93 @@ pub fn everforest_light_theme() -> ThemeConfig {
         border_selected: Style::default().fg(yellow),
         title: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
         text: Style::default().fg(fg).bg(bg_view),
+        search_match: Style::default().fg(bg).bg(yellow),
+        diff_added: Style::default().fg(green).add_modifier(Modifier::BOLD),
+        diff_removed: Style::default().fg(red).add_modifier(Modifier::BOLD),
+        diff_changed: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
+        query_error: Style::default().fg(red).add_modifier(Modifier::UNDERLINED),
+        warning: Style::default().fg(red).add_modifier(Modifier::BOLD),
+        json_style,
+    }
+}
+
+/// Returns a ThemeConfig without colors, for `NO_COLOR` and `--no-color`.
+/// Roles are told apart by modifiers only; everything else uses the terminal's
+/// own foreground and background.
+pub fn monochrome_theme() -> ThemeConfig {
+    let json_style = JsonStyle {
+        key: Style::default(),
+        string: Style::default(),
+        number: Style::default().add_modifier(Modifier::ITALIC),
+        boolean: Style::default().add_modifier(Modifier::UNDERLINED),
+    };
+
+    ThemeConfig {
+        background: Color::Reset,
+        list_normal: Style::default(),
+        list_selected: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+        border: Style::default().add_modifier(Modifier::DIM),
+        border_selected: Style::default().add_modifier(Modifier::BOLD),
+        title: Style::default().add_modifier(Modifier::BOLD),
+        text: Style::default(),
+        search_match: Style::default().add_modifier(Modifier::REVERSED),
+        diff_added: Style::default().add_modifier(Modifier::BOLD),
+        diff_removed: Style::default().add_modifier(Modifier::DIM),
+        diff_changed: Style::default().add_modifier(Modifier::UNDERLINED),
+        query_error: Style::default().add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
+        warning: Style::default().add_modifier(Modifier::BOLD),
         json_style,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_stripe_shifts_the_background() {
+        let dark = solarized_dark();
+        assert_eq!(dark.list_stripe().bg, Some(Color::Rgb(19, 66, 78)));
+        assert_eq!(dark.list_stripe().fg, dark.list_normal.fg);
+
+        let light = everforest_light_theme();
+        let Some(Color::Rgb(r, ..)) = light.list_normal.bg else {
+            panic!("everforest light has an RGB background");
+        };
+        assert!(matches!(light.list_stripe().bg, Some(Color::Rgb(stripe, ..)) if stripe == r - 12));
+
+        // Nothing to shift without a color.
+        assert_eq!(monochrome_theme().list_stripe(), Style::default());
+    }
+
+    #[test]
+    fn test_monochrome_theme_has_no_colors() {
+        let theme = Theme::from_str("monochrome").unwrap().config();
+        let json = theme.json_style;
+        let styles = [
+            theme.list_normal,
+            theme.list_selected,
+            theme.border,
+            theme.border_selected,
+            theme.title,
+            theme.text,
+            theme.search_match,
+            theme.diff_added,
+            theme.diff_removed,
+            theme.diff_changed,
+            theme.query_error,
+            theme.warning,
+            json.key,
+            json.string,
+            json.number,
+            json.boolean,
+        ];
+        for style in styles {
+            assert!(!matches!(style.fg, Some(Color::Rgb(..))), "{:?}", style);
+            assert!(!matches!(style.bg, Some(Color::Rgb(..))), "{:?}", style);
+        }
+        // Value kinds stay distinguishable without color.
+        assert_ne!(json.string, json.number);
+        assert_ne!(json.number, json.boolean);
+    }
+}
@@ -5,15 +5,20 @@ use ratatui::{
     text::{Line, Span, Text},
     widgets::{
         Block, Borders, Clear, LineGauge, List, ListItem, Paragraph, Scrollbar,
-        ScrollbarOrientation, ScrollbarState,
+        ScrollbarOrientation, ScrollbarState, Wrap,
     },
 };
 use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ops::Range;
 use std::rc::Rc;
 use tui_scrollview::{ScrollView, ScrollbarVisibility};
 
+use crate::diff::{DiffKind, DiffRow};
+use crate::matcher::{self, SearchTerm};
 use crate::theme;
-use crate::{AppState, FocusPane, InputMode};
+use crate::{AppState, BuildKind, FocusPane, InputMode, LayoutMode};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Semantic role of a span in the rendered JSON.
@@ -37,8 +42,101 @@ pub struct AnnotatedSpan {
     pub span_id: Option<usize>,
 }
 
-/// Main UI entry point that renders the entire application layout.
-pub fn ui(f: &mut Frame, app: &mut AppState) {
+/// Side-by-side comparison of the pinned item (left) with the selected one (right).
+pub struct DetailsDiff {
+    pub left: Vec<Vec<AnnotatedSpan>>,
+    pub right: Vec<Vec<AnnotatedSpan>>,
+    pub rows: Vec<DiffRow>,
+}
+
+/// A cell in the wrapped details lines: line index and display column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextPos {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Text selected in the details pane, from where it was started to the moving end.
+/// Both ends are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetailsSelection {
+    pub anchor: TextPos,
+    pub cursor: TextPos,
+}
+
+impl DetailsSelection {
+    /// The selection's ends in reading order.
+    pub fn range(&self) -> (TextPos, TextPos) {
+        (self.anchor.min(self.cursor), self.anchor.max(self.cursor))
+    }
+
+    /// Selected columns of `line` (end exclusive), or `None` outside the selection.
+    fn columns_on(&self, line: usize) -> Option<(usize, usize)> {
+        let (start, end) = self.range();
+        if line < start.line || line > end.line {
+            return None;
+        }
+        let from = if line == start.line { start.column } else { 0 };
+        let to = if line == end.line {
+            end.column + 1
+        } else {
+            usize::MAX
+        };
+        Some((from, to))
+    }
+}
+
+/// The selected text of `lines`. Lines are joined with newlines except where
+/// `continued` marks a wrap, so a value spanning wrapped lines comes out whole.
+pub fn selection_text(
+    lines: &[Vec<AnnotatedSpan>],
+    continued: &[bool],
+    selection: &DetailsSelection,
+) -> String {
+    let (start, end) = selection.range();
+    let mut text = String::new();
+    for (idx, line) in lines.iter().enumerate().take(end.line + 1).skip(start.line) {
+        let Some((from, to)) = selection.columns_on(idx) else {
+            continue;
+        };
+        let mut column = 0;
+        for c in line.iter().flat_map(|span| span.span.content.chars()) {
+            if column >= from && column < to {
+                text.push(c);
+            }
+            column += UnicodeWidthChar::width(c).unwrap_or(0);
+        }
+        if idx < end.line && !continued.get(idx).copied().unwrap_or(false) {
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// A clickable value in the details header that adds `classifier:'value'` to the filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderLink {
+    /// Screen region of the rendered value.
+    pub area: Rect,
+    pub classifier: &'static str,
+    pub value: String,
+}
+
+/// Terminals narrower than this stack the panes in `LayoutMode::Auto`.
+const STACKED_LAYOUT_MAX_WIDTH: u16 = 100;
+
+/// Screen regions of the main panes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaneAreas {
+    pub list: Rect,
+    pub details: Rect,
+    pub filter: Rect,
+    pub status: Rect,
+}
+
+/// Splits the screen into panes. The filter and status bar always sit at the bottom;
+/// the list and details go side by side or, when `stacked`, list on top of details.
+pub fn pane_areas(area: Rect, stacked: bool) -> PaneAreas {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -46,36 +144,88 @@ pub fn ui(f: &mut Frame, app: &mut AppState) {
             Constraint::Length(3), // Filter input - fixed 3 lines
             Constraint::Length(1), // Status bar
         ])
-        .split(f.area());
+        .split(area);
 
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
-        .split(chunks[0]);
+    let main_chunks = if stacked {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(chunks[0])
+    } else {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+            .split(chunks[0])
+    };
+
+    PaneAreas {
+        list: main_chunks[0],
+        details: main_chunks[1],
+        filter: chunks[1],
+        status: chunks[2],
+    }
+}
+
+/// Resolves the layout mode against the terminal width.
+fn is_stacked(mode: LayoutMode, width: u16) -> bool {
+    match mode {
+        LayoutMode::Auto => width < STACKED_LAYOUT_MAX_WIDTH,
+        LayoutMode::SideBySide => false,
+        LayoutMode::Stacked => true,
+    }
+}
+
+/// Main UI entry point that renders the entire application layout.
+pub fn ui(f: &mut Frame, app: &mut AppState) {
+    let area = f.area();
+    let panes = pane_areas(area, is_stacked(app.layout_mode, area.width));
 
-    app.list_area = Some(main_chunks[0]);
-    app.details_area = Some(main_chunks[1]);
-    app.details_content_area = compute_details_content_area(app, main_chunks[1]);
-    app.filter_area = Some(chunks[1]);
+    app.list_area = Some(panes.list);
+    app.details_area = Some(panes.details);
+    app.details_content_area = compute_details_content_area(app, panes.details);
+    app.filter_area = Some(panes.filter);
 
     // Render item list
-    render_item_list(f, app, main_chunks[0]);
+    render_item_list(f, app, panes.list);
 
     // Render details pane
-    render_details(f, app, main_chunks[1]);
+    render_details(f, app, panes.details);
+    if app.indexed_items.is_empty() && !app.show_progress && app.background_load.is_none() {
+        render_empty_dataset(f, app, panes.details);
+    }
 
     // Render filter input
-    render_filter(f, app, chunks[1]);
+    render_filter(f, app, panes.filter);
 
     // Render status bar
-    render_status_bar(f, app, chunks[2]);
+    render_status_bar(f, app, panes.status);
 
     if app.show_progress {
         render_progress_modal(f, app);
+    } else if app.load_failure.is_some() {
+        render_load_failure_modal(f, app);
     } else if app.show_version_picker {
         render_version_picker(f, app);
+    } else if app.show_type_picker {
+        render_type_picker(f, app);
+    } else if app.show_bookmarks {
+        render_bookmarks(f, app);
+    } else if app.show_recent {
+        render_recent(f, app);
+    } else if app.show_lint {
+        render_lint_report(f, app);
+    } else if app.show_warnings {
+        render_warnings(f, app);
+    } else if app.show_history_search {
+        render_history_search(f, app);
     } else if app.show_help {
         render_help_overlay(f, app);
+    } else if app.show_about {
+        render_about_overlay(f, app);
+    } else if app.input_mode == InputMode::Filtering && app.completion.is_some() {
+        render_completions(f, app);
+    } else if app.hovered_preview.is_some() {
+        render_id_preview(f, app);
     }
 }
 
@@ -85,11 +235,12 @@ fn compute_details_content_area(app: &AppState, area: Rect) -> Option<Rect> {
         return None;
     }
 
-    let constraints = if app.get_selected_item().is_some() {
+    let constraints = if let Some(item) = app.get_selected_item() {
         vec![
-            Constraint::Length(2), // Metadata header
-            Constraint::Length(1), // Separator
-            Constraint::Min(0),    // Content
+            Constraint::Length(metadata_header_height(item, &app.header_fields)), // Metadata header
+            Constraint::Length(1),                                                // Separator
+            Constraint::Length(1),                                                // Breadcrumb
+            Constraint::Min(0),                                                   // Content
         ]
     } else {
         vec![Constraint::Min(0)]
@@ -101,7 +252,7 @@ fn compute_details_content_area(app: &AppState, area: Rect) -> Option<Rect> {
         .split(inner_area);
 
     let content_area = if app.get_selected_item().is_some() {
-        chunks[2]
+        chunks[3]
     } else {
         chunks[0]
     };
@@ -115,16 +266,43 @@ fn compute_details_content_area(app: &AppState, area: Rect) -> Option<Rect> {
 
 /// Renders the scrollable list of game items.
 fn render_item_list(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let widths = app
+        .show_columns
+        .then_some(app.cached_column_widths.as_slice());
+    let has_bookmarks = !app.bookmarks.ids().is_empty();
+    let stripe = app.zebra_stripes.then(|| app.theme.list_stripe());
+    let has_marks = !app.marked.is_empty();
     // Borrow pre-computed display strings — no JSON traversal or String allocation per frame.
     let items: Vec<ListItem> = app
         .cached_display
         .iter()
-        .map(|(display, type_prefix)| {
-            let type_label = Line::from(vec![
-                Span::styled(type_prefix.as_str(), app.theme.title),
-                Span::raw(display.as_str()),
-            ]);
-            ListItem::new(type_label)
+        .zip(&app.filtered_indices)
+        .enumerate()
+        .map(|(pos, (row, &idx))| {
+            let bookmarked = has_bookmarks
+                && app
+                    .bookmarks
+                    .contains(app.indexed_items[idx].id_or_abstract());
+            let mut line = match widths {
+                Some(widths) => {
+                    list_columns_line(row, widths, has_bookmarks.then_some(bookmarked), &app.theme)
+                }
+                None => list_item_line(row, bookmarked, &app.theme),
+            };
+            if has_marks {
+                let mark = if app.marked.contains(&idx) {
+                    MARKED_GLYPH
+                } else {
+                    UNMARKED_GLYPH
+                };
+                line.spans.insert(0, Span::styled(mark, app.theme.title));
+            }
+            let item = ListItem::new(line);
+            // Striped by position in the whole list, so rows keep their shade while scrolling.
+            match stripe {
+                Some(stripe) if pos % 2 == 1 => item.style(stripe),
+                _ => item,
+            }
         })
         .collect();
 
@@ -137,24 +315,78 @@ fn render_item_list(f: &mut Frame, app: &mut AppState, area: Rect) {
             app.theme.border
         })
         .title_style(app.theme.title)
-        .title(format!(" Objects ({}) ", app.filtered_indices.len()))
-        .title_bottom(if is_focused {
-            Line::from(" ↑/↓ move • Tab cycle ").right_aligned()
+        .title(if app.hide_abstracts {
+            format!(" Objects ({}, no abstracts) ", app.filtered_indices.len())
         } else {
-            Line::from("").right_aligned()
+            format!(" Objects ({}) ", app.filtered_indices.len())
+        })
+        .title_bottom(match &app.type_ahead {
+            Some(type_ahead) => Line::styled(
+                format!(
+                    " find: {}{} ",
+                    type_ahead.query,
+                    if type_ahead.matched {
+                        ""
+                    } else {
+                        " (no match)"
+                    }
+                ),
+                if type_ahead.matched {
+                    app.theme.title
+                } else {
+                    app.theme.query_error
+                },
+            )
+            .left_aligned(),
+            None if is_focused => Line::from(" ↑/↓ move • Tab cycle ").right_aligned(),
+            None => Line::from("").right_aligned(),
         })
         .title_alignment(Alignment::Left)
         .style(app.theme.list_normal);
 
-    app.list_content_area = Some(block.inner(area));
+    let mut content = block.inner(area);
+    f.render_widget(block, area);
+    if let Some(widths) = widths
+        && content.height > 0
+    {
+        let header = list_columns_header(&app.list_columns, widths, has_bookmarks);
+        f.render_widget(
+            Paragraph::new(header).style(app.theme.title),
+            Rect {
+                height: 1,
+                ..content
+            },
+        );
+        content.y += 1;
+        content.height -= 1;
+    }
+    app.list_content_area = Some(content);
 
     let list = List::new(items)
-        .block(block)
         .style(app.theme.list_normal)
         .scroll_padding(2)
         .highlight_style(app.theme.list_selected);
 
-    f.render_stateful_widget(list, area, &mut app.list_state);
+    f.render_stateful_widget(list, content, &mut app.list_state);
+
+    if app.filtered_indices.is_empty()
+        && let Some(content) = app.list_content_area
+    {
+        let hint = empty_list_hint(app);
+        let height = (hint.len() as u16).min(content.height);
+        let hint_area = Rect {
+            y: content.y + content.height.saturating_sub(height) / 2,
+            height,
+            ..content
+        };
+        f.render_widget(
+            Paragraph::new(hint)
+                .alignment(Alignment::Center)
+                .style(app.theme.list_normal),
+            hint_area,
+        );
+        return;
+    }
 
     // Render scrollbar
     let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
@@ -171,6 +403,353 @@ fn render_item_list(f: &mut Frame, app: &mut AppState, area: Rect) {
     );
 }
 
+/// Builds one list row; abstract templates are dimmed so real objects stand out,
+/// and the parts of the name the filter matched are emphasized.
+fn list_item_line<'a>(row: &'a ListRow, bookmarked: bool, theme: &theme::ThemeConfig) -> Line<'a> {
+    let name_style = if row.is_abstract {
+        Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC)
+    } else {
+        Style::default()
+    };
+    let mut spans = Vec::with_capacity(4 + 2 * row.name_matches.len());
+    if bookmarked {
+        spans.push(Span::styled(BOOKMARK_GLYPH, theme.title));
+    }
+    if row.warned {
+        spans.push(Span::styled(WARNING_GLYPH, theme.warning));
+    }
+    spans.push(Span::styled(row.type_prefix.as_str(), theme.title));
+    let match_style = name_style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut pos = 0;
+    for range in &row.name_matches {
+        if range.start > pos {
+            spans.push(Span::styled(&row.display[pos..range.start], name_style));
+        }
+        spans.push(Span::styled(&row.display[range.clone()], match_style));
+        pos = range.end;
+    }
+    if pos < row.display.len() || row.name_matches.is_empty() {
+        spans.push(Span::styled(&row.display[pos..], name_style));
+    }
+    Line::from(spans)
+}
+
+/// Byte ranges of `name` that the plain words of the query match, merged and in
+/// order. Like `matches_value`, matching is a case-insensitive substring search;
+/// classifier, exact and wildcard terms don't mark the name.
+pub fn name_match_ranges(name: &str, terms: &[SearchTerm]) -> Vec<std::ops::Range<usize>> {
+    let lower = name.to_lowercase();
+    // Lowercasing changed some byte lengths, so offsets wouldn't line up with `name`.
+    if lower.len() != name.len() {
+        return Vec::new();
+    }
+    let mut ranges: Vec<std::ops::Range<usize>> = terms
+        .iter()
+        .filter(|term| term.classifier.is_none() && !term.exact && term.glob().is_none())
+        .map(|term| term.pattern.to_lowercase())
+        .filter(|pattern| !pattern.is_empty())
+        .flat_map(|pattern| {
+            lower
+                .match_indices(pattern.as_str())
+                .map(|(start, found)| start..start + found.len())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<std::ops::Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Gap between cells in the columns view.
+const COLUMN_GAP: &str = "  ";
+/// Widest the name and field columns get; longer values are cut with an ellipsis.
+const MAX_NAME_COLUMN: usize = 40;
+const MAX_FIELD_COLUMN: usize = 24;
+
+/// One entry of the object list, computed when the filtered set changes.
+pub struct ListRow {
+    pub display: String,
+    pub type_prefix: String,
+    pub is_abstract: bool,
+    /// Whether a source warning concerns this object.
+    pub warned: bool,
+    /// Byte ranges of `display` matched by the filter's plain words.
+    pub name_matches: Vec<std::ops::Range<usize>>,
+    /// Values of the configured field columns; empty unless the columns view is on.
+    pub columns: Vec<String>,
+}
+
+/// Widths of the name, type and field columns: the widest value or header, capped.
+pub fn column_widths(rows: &[ListRow], headers: &[String]) -> Vec<usize> {
+    let mut widths = vec!["id".width(), "type".width()];
+    widths.extend(headers.iter().map(|header| header.width()));
+    for row in rows {
+        let cells = [row.display.as_str(), row.type_prefix.trim_end()]
+            .into_iter()
+            .chain(row.columns.iter().map(String::as_str));
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(cell.width());
+        }
+    }
+    for (i, width) in widths.iter_mut().enumerate() {
+        *width = (*width).min(if i == 0 {
+            MAX_NAME_COLUMN
+        } else {
+            MAX_FIELD_COLUMN
+        });
+    }
+    widths
+}
+
+/// Pads or cuts `text` to exactly `width` terminal cells.
+fn fit_cell(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return format!("{}{}", text, " ".repeat(width - text.width()));
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if used + ch_width + 1 > width {
+            break;
+        }
+        out.push(ch);
+        used += ch_width;
+    }
+    out.push('…');
+    out.push_str(&" ".repeat(width.saturating_sub(used + 1)));
+    out
+}
+
+/// A row of the columns view. `bookmarked` is `None` when nothing is bookmarked;
+/// otherwise every row keeps a glyph slot so the columns stay aligned.
+fn list_columns_line(
+    row: &ListRow,
+    widths: &[usize],
+    bookmarked: Option<bool>,
+    theme: &theme::ThemeConfig,
+) -> Line<'static> {
+    let mut name_style = if row.is_abstract {
+        Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC)
+    } else {
+        Style::default()
+    };
+    // No glyph here: it would push the name out of its column.
+    if row.warned {
+        name_style = name_style.patch(theme.warning);
+    }
+    let mut spans = Vec::with_capacity(2 * widths.len() + 1);
+    match bookmarked {
+        Some(true) => spans.push(Span::styled(BOOKMARK_GLYPH, theme.title)),
+        Some(false) => spans.push(Span::raw(" ".repeat(BOOKMARK_GLYPH.width()))),
+        None => {}
+    }
+    let cells = [row.display.as_str(), row.type_prefix.trim_end()]
+        .into_iter()
+        .chain(row.columns.iter().map(String::as_str));
+    for (i, (cell, &width)) in cells.zip(widths).enumerate() {
+        let style = match i {
+            0 => name_style,
+            1 => theme.title,
+            _ => Style::default(),
+        };
+        if i > 0 {
+            spans.push(Span::raw(COLUMN_GAP));
+        }
+        spans.push(Span::styled(fit_cell(cell, width), style));
+    }
+    Line::from(spans)
+}
+
+fn list_columns_header(columns: &[String], widths: &[usize], has_bookmarks: bool) -> Line<'static> {
+    let mut text = String::new();
+    if has_bookmarks {
+        text.push_str(&" ".repeat(BOOKMARK_GLYPH.width()));
+    }
+    let headers = ["id", "type"]
+        .into_iter()
+        .chain(columns.iter().map(String::as_str));
+    for (i, (header, &width)) in headers.zip(widths).enumerate() {
+        if i > 0 {
+            text.push_str(COLUMN_GAP);
+        }
+        text.push_str(&fit_cell(header, width));
+    }
+    Line::styled(text, Style::default().add_modifier(Modifier::UNDERLINED))
+}
+
+/// Text of the field at dot-path `path` for the columns view. Arrays along the
+/// way are walked, so several values can be found; they are joined with commas.
+pub(crate) fn column_value(json: &Value, path: &str) -> String {
+    let parts: Vec<&str> = path.split('.').collect();
+    let mut found = Vec::new();
+    collect_path_values(json, &parts, &mut found);
+    found
+        .into_iter()
+        .map(column_text)
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn collect_path_values<'a>(json: &'a Value, parts: &[&str], found: &mut Vec<&'a Value>) {
+    let Some((first, rest)) = parts.split_first() else {
+        found.push(json);
+        return;
+    };
+    match json {
+        Value::Object(obj) => {
+            if let Some(value) = obj.get(*first) {
+                collect_path_values(value, rest, found);
+            }
+        }
+        Value::Array(arr) => {
+            for item in arr {
+                collect_path_values(item, parts, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn column_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(arr) => arr
+            .iter()
+            .map(column_text)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::Object(_) => name_value(value).unwrap_or_else(|| "{…}".to_string()),
+        other => other.to_string(),
+    }
+}
+
+/// Marker shown before bookmarked objects in the list.
+const BOOKMARK_GLYPH: &str = "★ ";
+/// Checkbox shown before every object while any are marked for export.
+const MARKED_GLYPH: &str = "[x] ";
+const UNMARKED_GLYPH: &str = "[ ] ";
+/// Marker shown before objects that have source warnings.
+const WARNING_GLYPH: &str = "! ";
+
+/// Builds the centered hint shown when the filter matches nothing.
+fn empty_list_hint(app: &AppState) -> Vec<Line<'static>> {
+    if app.indexed_items.is_empty() {
+        return vec![Line::styled("No data loaded", app.theme.title)];
+    }
+    if let Some(count) = app.match_count {
+        return vec![
+            Line::styled(
+                count.to_string(),
+                app.theme.title.add_modifier(Modifier::BOLD),
+            ),
+            Line::from(if count == 1 {
+                "object matches"
+            } else {
+                "objects match"
+            }),
+            Line::from(""),
+            Line::from("Remove `count:` to list them"),
+        ];
+    }
+    if let Some(error) = &app.filter_error {
+        return vec![
+            Line::styled("Invalid query", app.theme.title),
+            Line::from(error.clone()),
+        ];
+    }
+    let mut lines = vec![Line::styled("No matching objects", app.theme.title)];
+    if !app.filter_unknown_classifiers.is_empty() {
+        lines.push(Line::from(format!(
+            "Unknown field: {}",
+            app.filter_unknown_classifiers.join(", ")
+        )));
+    } else if let Some(classifier) = &app.filter_slow_path_miss {
+        lines.push(Line::from(format!(
+            "No object matched on `{}:`",
+            classifier
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("Try fewer terms"));
+    lines.push(Line::from("or `t:` for type, `i:` for id"));
+    lines
+}
+
+/// Explains how to get data into an empty app, in place of the details, followed
+/// by whatever the source directory load complained about.
+fn render_empty_dataset(f: &mut Frame, app: &AppState, area: Rect) {
+    let content = area.inner(Margin::new(2, 1));
+    if content.width == 0 || content.height == 0 {
+        return;
+    }
+    let key_style = app.theme.title;
+    let mut lines = vec![
+        Line::styled(
+            "No game data loaded",
+            app.theme.title.add_modifier(Modifier::BOLD),
+        ),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Ctrl+G", key_style),
+            Span::raw(if app.offline {
+                " pick a cached game version"
+            } else {
+                " pick a game version to download"
+            }),
+        ]),
+    ];
+    if app.source_dir.is_some() {
+        lines.push(Line::from(vec![
+            Span::styled("Ctrl+R", key_style),
+            Span::raw(" reload the source directory"),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        "Or start with --game <version>, --file <all.json> or --source <dir>",
+        app.theme.text.add_modifier(Modifier::DIM),
+    ));
+    if !app.source_warnings.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::styled("Source warnings", app.theme.warning));
+        lines.extend(app.source_warnings.iter().map(|warning| {
+            Line::styled(
+                warning.message.clone(),
+                app.theme.text.add_modifier(Modifier::DIM),
+            )
+        }));
+    }
+
+    let width = content.width as usize;
+    let rows = lines
+        .iter()
+        .map(|line| line.width().div_ceil(width).max(1))
+        .sum::<usize>();
+    let height = (rows as u16).min(content.height);
+    let message_area = Rect {
+        y: content.y + (content.height - height) / 2,
+        height,
+        ..content
+    };
+    f.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .style(app.theme.text)
+            .wrap(Wrap { trim: true }),
+        message_area,
+    );
+}
+
 /// Renders the details pane showing syntax-highlighted JSON data.
 fn render_details(f: &mut Frame, app: &mut AppState, area: Rect) {
     let is_focused = app.focused_pane == FocusPane::Details;
@@ -182,17 +761,35 @@ fn render_details(f: &mut Frame, app: &mut AppState, area: Rect) {
             app.theme.border
         })
         .style(app.theme.text)
-        .title(" JSON ")
+        .title(details_title(app))
         .title_alignment(Alignment::Left)
-        .title_style(app.theme.title)
-        .title_bottom(if is_focused {
-            Line::from(" ↑/↓ scroll • Tab cycle").right_aligned()
-        } else {
-            Line::from("").right_aligned()
-        });
+        .title_style(app.theme.title);
+    // Wrapping happens further down, so a resize shows last frame's count for one frame.
+    let position = app
+        .details_diff
+        .is_none()
+        .then(|| {
+            details_position(
+                app.details_scroll_state.offset().y,
+                app.details_wrapped_annotated.len(),
+            )
+        })
+        .flatten();
+    let hint = is_focused.then_some("↑/↓ scroll • Tab links • Enter follow");
+    let bottom = [position.as_deref(), hint]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" • ");
+    let block = if bottom.is_empty() {
+        block
+    } else {
+        block.title_bottom(Line::from(format!(" {} ", bottom)).right_aligned())
+    };
 
     let inner_area = block.inner(area);
     f.render_widget(block, area);
+    app.details_minimap_area = None;
 
     if inner_area.width > 0 && inner_area.height > 0 {
         let horizontal_padding = 1;
@@ -217,31 +814,90 @@ fn render_details(f: &mut Frame, app: &mut AppState, area: Rect) {
                     inner_area.height.saturating_sub(header_height + 1),
                 );
             }
+
+            if content_area.height > 0 {
+                if let Some(breadcrumb) = details_breadcrumb(app) {
+                    f.render_widget(
+                        Paragraph::new(breadcrumb)
+                            .style(app.theme.text.add_modifier(Modifier::DIM)),
+                        Rect::new(
+                            content_area.x + horizontal_padding,
+                            content_area.y,
+                            content_area.width.saturating_sub(horizontal_padding * 2),
+                            1,
+                        ),
+                    );
+                }
+                content_area.y += 1;
+                content_area.height -= 1;
+            }
         }
 
         // Apply 1-symbol horizontal padding within the content area
         let content_width = content_area.width.saturating_sub(horizontal_padding * 2);
 
-        if content_width > 0 && content_area.height > 0 {
-            // Re-wrap if width changed
-            if app.details_wrapped_width != content_width {
-                app.details_wrapped_annotated =
-                    wrap_annotated_lines(&app.details_annotated, content_width);
-                app.details_wrapped_width = content_width;
+        if app.details_diff.is_some() && content_width > 0 && content_area.height > 0 {
+            let scroll_view_area = Rect::new(
+                content_area.x + horizontal_padding,
+                content_area.y,
+                content_width,
+                content_area.height,
+            );
+            render_details_diff(f, app, scroll_view_area);
+        } else if content_width > 0 && content_area.height > 0 {
+            // Re-wrap if width changed; in no-wrap mode lines are kept whole.
+            let wrap_width = if app.details_wrap {
+                content_width
+            } else {
+                u16::MAX
+            };
+            if app.details_wrapped_width != wrap_width {
+                let mut display = Cow::Borrowed(&app.details_annotated[..]);
+                if app.group_digits {
+                    display = Cow::Owned(group_number_spans(&display));
+                }
+                (app.details_wrapped_annotated, app.details_wrap_continued) = if app.prose_strings {
+                    wrap_annotated_lines_at_words(&unquote_long_strings(&display), wrap_width)
+                } else {
+                    wrap_annotated_lines(&display, wrap_width)
+                };
+                app.details_wrapped_width = wrap_width;
+                // Positions in the old wrapping no longer point at the same text.
+                app.details_selection = None;
             }
 
             let content_height = app.details_wrapped_annotated.len() as u16;
+            let (canvas_width, horizontal_scrollbar) = if app.details_wrap {
+                (content_width, ScrollbarVisibility::Never)
+            } else {
+                let widest = app
+                    .details_wrapped_annotated
+                    .iter()
+                    .map(|line| line.iter().map(|s| s.span.width()).sum::<usize>())
+                    .max()
+                    .unwrap_or(0);
+                (
+                    content_width.max(widest.min(u16::MAX as usize) as u16),
+                    ScrollbarVisibility::Automatic,
+                )
+            };
 
-            let mut scroll_view = ScrollView::new(Size::new(content_width, content_height))
+            let mut scroll_view = ScrollView::new(Size::new(canvas_width, content_height))
                 .vertical_scrollbar_visibility(ScrollbarVisibility::Automatic)
-                .horizontal_scrollbar_visibility(ScrollbarVisibility::Never);
+                .horizontal_scrollbar_visibility(horizontal_scrollbar);
 
             // Match the background of the scroll view buffer to the theme
             let scroll_area = scroll_view.area();
             scroll_view.buf_mut().set_style(scroll_area, app.theme.text);
 
-            let content_rect = Rect::new(0, 0, content_width, content_height);
-            let text = annotated_to_text(&app.details_wrapped_annotated, app.hovered_span_id);
+            let content_rect = Rect::new(0, 0, canvas_width, content_height);
+            let focused_link_id = app.focused_link_id.filter(|_| is_focused);
+            let text = annotated_to_text(
+                &app.details_wrapped_annotated,
+                app.hovered_span_id,
+                focused_link_id,
+                app.details_selection.as_ref(),
+            );
             scroll_view.render_widget(Paragraph::new(text).style(app.theme.text), content_rect);
 
             // Render ScrollView centered horizontally within content_area using the padding
@@ -253,14 +909,238 @@ fn render_details(f: &mut Frame, app: &mut AppState, area: Rect) {
             );
 
             f.render_stateful_widget(scroll_view, scroll_view_area, &mut app.details_scroll_state);
+
+            // The right padding column holds the minimap of a body too long to fit.
+            if content_height > content_area.height {
+                let minimap_area = Rect::new(
+                    content_area.right() - 1,
+                    content_area.y,
+                    1,
+                    content_area.height,
+                );
+                render_details_minimap(f, app, minimap_area, content_width);
+                app.details_minimap_area = Some(minimap_area);
+            }
         }
     }
 }
 
-/// Renders the metadata header (ID, Name, Type, Category) for the selected item.
+/// The wrapped lines, of `total`, that minimap cell `cell` of `height` stands for.
+/// Only meaningful when there are more lines than cells, so no cell is empty.
+pub fn minimap_lines(cell: u16, height: u16, total: usize) -> Range<usize> {
+    if height == 0 {
+        return 0..0;
+    }
+    let (cell, height) = (cell as usize, height as usize);
+    (cell * total / height).min(total)..((cell + 1) * total / height).min(total)
+}
+
+/// The minimap cell, of `height`, showing wrapped line `line` of `total`.
+pub fn minimap_cell(line: usize, height: u16, total: usize) -> u16 {
+    if total == 0 {
+        return 0;
+    }
+    // The last cell whose range starts at or before `line`.
+    (((line.min(total - 1) + 1) * height as usize).saturating_sub(1) / total) as u16
+}
+
+/// Draws a one-column overview of the details body: each cell is shaded by how
+/// much text its lines hold and colored by their most common kind of value,
+/// and the cells of the visible lines are shown reversed.
+fn render_details_minimap(f: &mut Frame, app: &AppState, area: Rect, line_width: u16) {
+    let lines = &app.details_wrapped_annotated;
+    let total = lines.len();
+    let top = app.details_scroll_state.offset().y as usize;
+    let viewport = minimap_cell(top, area.height, total)
+        ..=minimap_cell(top + area.height as usize - 1, area.height, total);
+    let json_style = &app.theme.json_style;
+
+    let cells: Vec<Line> = (0..area.height)
+        .map(|cell| {
+            let range = minimap_lines(cell, area.height, total);
+            let mut counts = [0usize; 4];
+            for span in range.clone().filter_map(|idx| lines.get(idx)).flatten() {
+                let slot = match span.kind {
+                    JsonSpanKind::Key => 0,
+                    JsonSpanKind::StringValue => 1,
+                    JsonSpanKind::NumberValue => 2,
+                    JsonSpanKind::BooleanValue => 3,
+                    JsonSpanKind::Punctuation | JsonSpanKind::Whitespace => continue,
+                };
+                counts[slot] += span.span.width();
+            }
+            let filled = counts.iter().sum::<usize>() as f64
+                / (range.len().max(1) * line_width.max(1) as usize) as f64;
+            let glyph = match filled {
+                f if f <= 0.0 => " ",
+                f if f < 0.15 => "░",
+                f if f < 0.35 => "▒",
+                _ => "▓",
+            };
+            let dominant = (0..counts.len()).max_by_key(|&slot| counts[slot]);
+            let mut style = match dominant.filter(|&slot| counts[slot] > 0) {
+                Some(0) => json_style.key,
+                Some(1) => json_style.string,
+                Some(2) => json_style.number,
+                Some(_) => json_style.boolean,
+                None => app.theme.text,
+            };
+            if viewport.contains(&cell) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            Line::styled(glyph, style)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(cells).style(app.theme.text), area);
+}
+
+/// `line X of Y` for the details body scrolled `offset` lines into `total` wrapped lines.
+pub fn details_position(offset: u16, total: usize) -> Option<String> {
+    if total == 0 {
+        return None;
+    }
+    let top = (offset as usize + 1).min(total);
+    Some(format!("line {} of {}", top, total))
+}
+
+fn details_title(app: &AppState) -> String {
+    if app.details_diff.is_some() {
+        return format!(" Diff: {} ↔ selected ", app.diff_label().unwrap_or("?"));
+    }
+    let mut title = if app.show_table { " Table" } else { " JSON" }.to_string();
+    if app.show_raw_json {
+        title.push_str(" (raw)");
+    } else if app.show_resolved {
+        title.push_str(" (resolved)");
+    }
+    if let Some(label) = app.pinned_label() {
+        title.push_str(&format!(" · pinned: {}", label));
+    }
+    if let (Some(other), Some(status)) = (&app.compare, app.selected_compare_status()) {
+        title.push_str(&format!(" · {}: {}", other.version, status.label()));
+    }
+    title.push(' ');
+    title
+}
+
+/// Builds one side of the diff view: a gutter marker plus the highlighted line per row.
+/// Rows where this side has no line are left blank.
+fn diff_side_text(
+    lines: &[Vec<AnnotatedSpan>],
+    rows: &[DiffRow],
+    left_side: bool,
+    theme: &theme::ThemeConfig,
+) -> Text<'static> {
+    let rendered: Vec<Line<'static>> = rows
+        .iter()
+        .map(|row| {
+            let Some(line) =
+                (if left_side { row.left } else { row.right }).and_then(|idx| lines.get(idx))
+            else {
+                return Line::from("");
+            };
+            let marker = match row.kind {
+                DiffKind::Same => Span::raw("  "),
+                DiffKind::Changed => Span::styled("~ ", theme.diff_changed),
+                DiffKind::Removed => Span::styled("- ", theme.diff_removed),
+                DiffKind::Added => Span::styled("+ ", theme.diff_added),
+            };
+            let mut spans = vec![marker];
+            spans.extend(line.iter().map(|s| s.span.clone()));
+            Line::from(spans)
+        })
+        .collect();
+    Text::from(rendered)
+}
+
+/// Renders the pinned and selected items side by side, one row per diff row,
+/// so both sides scroll together.
+/// Dot-path shown above the JSON body: the hovered span's, or else the
+/// top-level key at the top of the visible part.
+pub fn details_breadcrumb(app: &AppState) -> Option<String> {
+    if app.details_diff.is_some() {
+        return None;
+    }
+    if let Some(path) = &app.hovered_key_path {
+        return Some(path.clone());
+    }
+    let first_visible = app.details_scroll_state.offset().y as usize;
+    let path = app
+        .details_wrapped_annotated
+        .get(first_visible..)?
+        .iter()
+        .flatten()
+        .find_map(|span| span.key_context.as_deref())?;
+    Some(path.split('.').next().unwrap_or(path).to_string())
+}
+
+fn render_details_diff(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let Some(diff) = &app.details_diff else {
+        return;
+    };
+    let height = diff.rows.len().min(u16::MAX as usize) as u16;
+    let left_width = area.width.saturating_sub(1) / 2;
+    // One column for the divider, one for a gap after it.
+    let right_width = area.width.saturating_sub(left_width + 2);
+    let left = diff_side_text(&diff.left, &diff.rows, true, &app.theme);
+    let right = diff_side_text(&diff.right, &diff.rows, false, &app.theme);
+
+    let mut scroll_view = ScrollView::new(Size::new(area.width, height))
+        .vertical_scrollbar_visibility(ScrollbarVisibility::Automatic)
+        .horizontal_scrollbar_visibility(ScrollbarVisibility::Never);
+    let scroll_area = scroll_view.area();
+    scroll_view.buf_mut().set_style(scroll_area, app.theme.text);
+
+    scroll_view.render_widget(
+        Paragraph::new(left).style(app.theme.text),
+        Rect::new(0, 0, left_width, height),
+    );
+    let divider: Vec<Line> = (0..height).map(|_| Line::from("│")).collect();
+    scroll_view.render_widget(
+        Paragraph::new(divider).style(app.theme.border),
+        Rect::new(left_width, 0, 1, height),
+    );
+    scroll_view.render_widget(
+        Paragraph::new(right).style(app.theme.text),
+        Rect::new(left_width + 2, 0, right_width, height),
+    );
+
+    f.render_stateful_widget(scroll_view, area, &mut app.details_scroll_state);
+}
+
+/// Values of the header fields configured for `item_type`, as `(path, text)`.
+/// Paths missing from the object are left out; unconfigured types get none.
+pub(crate) fn header_field_values<'a>(
+    json: &Value,
+    item_type: &str,
+    header_fields: &'a BTreeMap<String, Vec<String>>,
+) -> Vec<(&'a str, String)> {
+    let Some(paths) = header_fields.get(item_type) else {
+        return Vec::new();
+    };
+    paths
+        .iter()
+        .map(|path| (path.as_str(), column_value(json, path)))
+        .filter(|(_, text)| !text.is_empty())
+        .collect()
+}
+
+/// Rows taken by the metadata header: the four fixed cells plus the configured
+/// fields of `item`'s type, two per row.
+fn metadata_header_height(
+    item: &crate::data::IndexedItem,
+    header_fields: &BTreeMap<String, Vec<String>>,
+) -> u16 {
+    let fields = header_field_values(&item.value, &item.item_type, header_fields).len();
+    (4 + fields).div_ceil(2) as u16
+}
+
+/// Renders the metadata header (ID, Name, Type, Category) for the selected item,
+/// followed by the fields configured for its type, two per row.
 /// Uses a two-column layout with 50% width each.
-/// Returns the height occupied by the header (always 2).
+/// Returns the height occupied by the header.
 fn render_metadata_header(f: &mut Frame, app: &mut AppState, area: Rect) -> u16 {
+    app.header_links.clear();
     let Some(item) = app.get_selected_item() else {
         return 0;
     };
@@ -290,35 +1170,62 @@ fn render_metadata_header(f: &mut Frame, app: &mut AppState, area: Rect) -> u16
     let type_val = if type_val.is_empty() { " " } else { type_val };
     let cat_val = if cat_val.is_empty() { " " } else { cat_val };
 
+    // Type and category cells (indices 2 and 3) filter by their value when clicked.
+    let cell_links = [(2, "t", type_val), (3, "c", cat_val)]
+        .map(|(cell, classifier, value)| (cell, classifier, value.trim().to_string()));
+    let mut cells = vec![
+        Line::from(id_val),
+        Line::from(name_val),
+        Line::from(type_val),
+        Line::from(cat_val),
+    ];
+    for (path, text) in header_field_values(json, type_, &app.header_fields) {
+        cells.push(Line::from(vec![
+            Span::styled(format!("{}: ", path), app.theme.title),
+            Span::raw(text),
+        ]));
+    }
+
     let horizontal_padding = 1;
+    let height = (cells.len().div_ceil(2) as u16).min(area.height);
     let header_area = Rect::new(
         area.x + horizontal_padding,
         area.y,
         area.width.saturating_sub(horizontal_padding * 2),
-        2,
+        height,
     );
 
     let rows = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .constraints(vec![Constraint::Length(1); height as usize])
         .split(header_area);
 
-    for (i, row_area) in rows.iter().enumerate() {
+    let mut links = Vec::new();
+    for (row, (row_area, pair)) in rows.iter().zip(cells.chunks(2)).enumerate() {
         let cols = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
             .split(*row_area);
 
-        if i == 0 {
-            f.render_widget(Paragraph::new(id_val).style(app.theme.text), cols[0]);
-            f.render_widget(Paragraph::new(name_val).style(app.theme.text), cols[1]);
-        } else {
-            f.render_widget(Paragraph::new(type_val).style(app.theme.text), cols[0]);
-            f.render_widget(Paragraph::new(cat_val).style(app.theme.text), cols[1]);
+        for (col_idx, (cell, col)) in pair.iter().zip(cols.iter()).enumerate() {
+            f.render_widget(Paragraph::new(cell.clone()).style(app.theme.text), *col);
+            let cell_idx = row * 2 + col_idx;
+            if let Some((_, classifier, value)) = cell_links
+                .iter()
+                .find(|(idx, _, value)| *idx == cell_idx && !value.is_empty())
+            {
+                let width = (cell.width() as u16).min(col.width);
+                links.push(HeaderLink {
+                    area: Rect::new(col.x, col.y, width, 1),
+                    classifier,
+                    value: value.clone(),
+                });
+            }
         }
     }
+    app.header_links = links;
 
-    2 // height
+    height
 }
 
 /// Renders the interactive filter input box.
@@ -338,6 +1245,25 @@ fn render_filter(f: &mut Frame, app: &mut AppState, area: Rect) {
         } else {
             Line::from("")
         });
+    let block = match history_indicator(app.history_index, app.filter_history.len()) {
+        Some(indicator) => block.title_bottom(Line::from(indicator).left_aligned()),
+        None => block,
+    };
+    // Which dataset(s) the filter runs against, once there is a choice (Alt+O).
+    let block = match &app.compare {
+        Some(_) => block.title(
+            Line::from(format!(" in {} (Alt+O) ", app.search_target.label())).right_aligned(),
+        ),
+        None => block,
+    };
+    // Nested classifiers, exact words and the like check every object, unlike indexed terms.
+    let block = if app.filter_slow_path {
+        block.title(
+            Line::styled(" full scan ", app.theme.text.add_modifier(Modifier::DIM)).right_aligned(),
+        )
+    } else {
+        block
+    };
 
     let inner = block.inner(area);
     app.filter_input_area = Some(inner);
@@ -350,7 +1276,7 @@ fn render_filter(f: &mut Frame, app: &mut AppState, area: Rect) {
             app.theme.text.add_modifier(Modifier::DIM).italic(),
         )))
     } else {
-        Text::from(app.filter_text.as_str())
+        Text::from(Line::from(filter_spans(&app.filter_text, &app.theme)))
     };
 
     let paragraph = Paragraph::new(content)
@@ -370,18 +1296,122 @@ fn render_filter(f: &mut Frame, app: &mut AppState, area: Rect) {
     }
 }
 
-/// Renders the multisection status bar at the bottom.
-fn render_status_bar(f: &mut Frame, app: &mut AppState, area: Rect) {
-    let area = Rect::new(
-        area.x + 1,
-        area.y,
-        area.width.saturating_sub(2),
-        area.height,
-    );
-
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
+/// Lists completions for the filter term being typed, above the term in the filter input.
+fn render_completions(f: &mut Frame, app: &mut AppState) {
+    let (Some(completion), Some(input)) = (&app.completion, app.filter_input_area) else {
+        return;
+    };
+    let Some(before_token) = app.filter_text.get(..completion.token.range.start) else {
+        return;
+    };
+    let area = f.area();
+    // Borders plus a space on each side of the candidates.
+    let widest = completion
+        .candidates
+        .iter()
+        .map(|c| c.width())
+        .max()
+        .unwrap_or(0);
+    let popup_width = (widest as u16 + 4).min(area.width);
+    // The popup stands on the filter's top border.
+    let popup_height = (completion.candidates.len() as u16 + 2).min(input.y.saturating_sub(1));
+    if popup_height < 3 {
+        return;
+    }
+    let scroll = filter_horizontal_scroll(&app.filter_text, app.filter_cursor, input.width);
+    let token_x = filter_cursor_offset(&app.filter_text, before_token.chars().count())
+        .saturating_sub(scroll)
+        .min(input.width);
+    // Line the candidates up with the term, moving left if the popup would stick out.
+    let popup_x = (input.x + token_x)
+        .saturating_sub(2)
+        .min(area.right().saturating_sub(popup_width));
+    let popup_rect = Rect::new(
+        popup_x,
+        input.y - 1 - popup_height,
+        popup_width,
+        popup_height,
+    );
+
+    let items: Vec<ListItem> = completion
+        .candidates
+        .iter()
+        .map(|candidate| ListItem::new(Line::from(format!(" {}", candidate))))
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.border_selected)
+                .style(app.theme.text),
+        )
+        .style(app.theme.list_normal)
+        .highlight_style(app.theme.list_selected);
+
+    f.render_widget(Clear, popup_rect);
+    f.render_stateful_widget(list, popup_rect, &mut app.completion_list_state);
+}
+
+/// Floats a summary of the hovered id's object just below the mouse, or above
+/// it when there is no room below.
+fn render_id_preview(f: &mut Frame, app: &mut AppState) {
+    let Some(preview) = &app.hovered_preview else {
+        return;
+    };
+    let area = f.area();
+    let rows: Vec<Line> = preview
+        .fields
+        .iter()
+        .map(|(field, value)| {
+            Line::from(vec![
+                Span::styled(format!(" {}: ", field), app.theme.json_style.key),
+                Span::styled(value.as_str(), app.theme.text),
+            ])
+        })
+        .collect();
+    let widest = rows
+        .iter()
+        .map(Line::width)
+        .chain([preview.title.width() + 2])
+        .max()
+        .unwrap_or(0);
+    let popup_width = (widest as u16 + 3).min(48).min(area.width);
+    let popup_height = (rows.len() as u16 + 2).min(area.height);
+    if popup_width < 3 || popup_height < 3 {
+        return;
+    }
+    let (column, row) = preview.anchor;
+    let x = column.min(area.right().saturating_sub(popup_width));
+    let y = if row + 1 + popup_height <= area.bottom() {
+        row + 1
+    } else {
+        row.saturating_sub(popup_height)
+    };
+    let popup_rect = Rect::new(x, y, popup_width, popup_height);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(app.theme.border_selected)
+        .style(app.theme.text)
+        .title(format!(" {} ", preview.title))
+        .title_style(app.theme.title);
+
+    f.render_widget(Clear, popup_rect);
+    f.render_widget(Paragraph::new(rows).block(block), popup_rect);
+}
+
+/// Renders the multisection status bar at the bottom.
+fn render_status_bar(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let area = Rect::new(
+        area.x + 1,
+        area.y,
+        area.width.saturating_sub(2),
+        area.height,
+    );
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
             Constraint::Percentage(40),
             Constraint::Percentage(30),
             Constraint::Percentage(30),
@@ -416,18 +1446,43 @@ fn render_status_bar_shortcuts(f: &mut Frame, app: &mut AppState, area: Rect) {
 
 fn render_status_bar_operational(f: &mut Frame, app: &mut AppState, area: Rect) {
     let bar_style = app.theme.text.add_modifier(Modifier::DIM);
-    let mut spans = vec![Span::raw(format!(
-        "Objects: {}",
-        app.total_items
-    ))];
+    if let Some(message) = &app.status_message {
+        f.render_widget(
+            Paragraph::new(Span::styled(message.as_str(), app.theme.warning))
+                .alignment(Alignment::Center),
+            area,
+        );
+        return;
+    }
+    if let Some(status) = background_load_status(app) {
+        f.render_widget(
+            Paragraph::new(Span::styled(status, app.theme.title)).alignment(Alignment::Center),
+            area,
+        );
+        return;
+    }
+    let mut spans = vec![Span::raw(match app.limited_total {
+        Some(total) => format!("Showing {} of {}", app.filtered_indices.len(), total),
+        None => format!("Objects: {}", app.total_items),
+    })];
+    if let Some(path) = &app.hovered_path {
+        spans.push(Span::raw(" | "));
+        spans.push(Span::styled(path.as_str(), app.theme.title));
+        if let Some(count) = app
+            .hovered_value_count
+            .as_ref()
+            .filter(|count| count.path == *path)
+        {
+            spans.push(Span::raw(format!(
+                " ({} of {} share this value)",
+                count.count,
+                app.filtered_indices.len()
+            )));
+        }
+    }
     if !app.source_warnings.is_empty() {
         spans.push(Span::raw(" |"));
-        spans.push(Span::styled(
-            " *",
-            Style::default()
-                .fg(ratatui::style::Color::Red)
-                .add_modifier(Modifier::BOLD),
-        ));
+        spans.push(Span::styled(" * Alt+W", app.theme.warning));
     }
 
     let status = Line::from(spans);
@@ -440,6 +1495,25 @@ fn render_status_bar_operational(f: &mut Frame, app: &mut AppState, area: Rect)
     );
 }
 
+/// `Loading nightly: Downloading 42% • Esc cancels` while a version switch runs.
+fn background_load_status(app: &AppState) -> Option<String> {
+    let load = app.background_load.as_ref()?;
+    let stage = app
+        .progress_stages
+        .iter()
+        .find(|stage| !stage.done)
+        .or(app.progress_stages.last())?;
+    let amount = if stage.indeterminate {
+        String::new()
+    } else {
+        format!(" {:.0}%", stage.ratio * 100.0)
+    };
+    Some(format!(
+        "Loading {}: {}{} • Esc cancels",
+        load.version, stage.label, amount
+    ))
+}
+
 fn render_status_bar_versions(f: &mut Frame, app: &mut AppState, area: Rect) {
     let bar_style = app.theme.text.add_modifier(Modifier::DIM);
     let versions = Line::from(format!(
@@ -458,7 +1532,7 @@ fn render_status_bar_versions(f: &mut Frame, app: &mut AppState, area: Rect) {
 fn render_help_overlay(f: &mut Frame, app: &mut AppState) {
     let area = f.area();
     let popup_width = area.width.min(76).saturating_sub(4);
-    let popup_height = 24.min(area.height.saturating_sub(2));
+    let popup_height = 67.min(area.height.saturating_sub(2));
     if popup_width == 0 || popup_height == 0 {
         return;
     }
@@ -501,10 +1575,32 @@ fn render_help_overlay(f: &mut Frame, app: &mut AppState) {
         "Navigation",
         vec![
             ("/", "filter items"),
+            ("' + letters", "jump to name in list"),
+            ("[ | ]", "list: previous | next type"),
             ("Mouse Click", "filter by property"),
+            ("c", "details: filter by hovered property"),
+            ("p | Alt+Click", "details: copy path of hovered value"),
             ("Ctrl+Click", "jump to ID"),
-            ("Ctrl+R", "reload local source"),
-            ("Ctrl+G", "version switcher"),
+            ("Tab | Enter", "details: cycle | follow link"),
+            ("Shift+Arrows | y", "details: select text | copy"),
+            ("Ctrl+P", "pick a type to filter by"),
+            ("Ctrl+R", "reload the dataset"),
+            ("Ctrl+T", "toggle raw source JSON"),
+            ("Alt+T", "toggle path = value table"),
+            ("Ctrl+F", "resolve copy-from inheritance"),
+            ("Ctrl+O", "open item online"),
+            ("Ctrl+E", "edit source file (--source)"),
+            ("Ctrl+K | Ctrl+D", "pin item | diff with pinned/compared"),
+            ("Alt+O", "filter this | other | both versions"),
+            ("Space | Alt+E", "mark item | export marked to JSON"),
+            ("Ctrl+S | Ctrl+V", "bookmark item | list bookmarks"),
+            ("Alt+R", "recently viewed items"),
+            ("Alt+W", "source warnings (--source)"),
+            ("Ctrl+B", "hide/show abstracts"),
+            ("Ctrl+X", "columns view"),
+            ("Ctrl+L", "layout: auto | side | stacked"),
+            ("Ctrl+N", "wrap | scroll long lines"),
+            ("Ctrl+G", "version switcher (Ctrl+D: compare)"),
             ("q", "quit"),
         ],
     );
@@ -526,6 +1622,8 @@ fn render_help_overlay(f: &mut Frame, app: &mut AppState) {
         "Filter",
         vec![
             ("Up | Down", "history"),
+            ("Tab", "accept completion"),
+            ("Ctrl+Y", "search history"),
             ("Ctrl+U", "clear filter"),
             ("Ctrl+W", "delete word"),
             ("Ctrl+A | E", "start | end of line"),
@@ -539,8 +1637,20 @@ fn render_help_overlay(f: &mut Frame, app: &mut AppState) {
             ("zombie", "- generic search in all fields"),
             ("t:gun", "- filter by type (i:id, t:type, c:cat)"),
             ("bash.str_min:30", "- filter by nested field"),
+            ("key:pocket_data", "- objects defining a field"),
             ("'shot'", "- exact match"),
+            ("id:pistol_*", "- wildcards (* any, ? one char)"),
+            ("flags#:>3", "- array length (>, >=, <, <=, =)"),
+            ("volume:>200", "- number, also \"250 ml\" (>, >=, <, <=, =)"),
+            ("(a | b) c", "- either a or b, and c"),
+            ("desc:@missing", "- also @null, @empty"),
+            ("warned:true", "- objects with source warnings"),
+            ("other:changed", "- vs --compare (same, missing...)"),
             ("zombie mom", "- AND logic"),
+            ("count:t:gun", "- only count matches"),
+            ("t:gun limit:50", "- show only the first 50 matches"),
+            ("steel depth:1", "- words in top-level fields only"),
+            ("t:gun sort:-volume", "- order by a field, descending"),
         ],
     ));
 
@@ -550,9 +1660,393 @@ fn render_help_overlay(f: &mut Frame, app: &mut AppState) {
         Span::styled("t:gun ammo:rpg", desc_style),
     ]));
 
+    let cache_size = app
+        .cache_size
+        .map_or_else(|| "unknown".to_string(), crate::format_bytes);
+    combined_lines.push(Line::from(""));
+    combined_lines.extend(format_section(
+        "Maintenance",
+        vec![
+            ("Cache", &format!("{} of downloaded data", cache_size)),
+            (
+                "x",
+                match (&app.cache_clear_result, app.confirm_clear_cache) {
+                    (Some(result), false) => result,
+                    (_, true) => "press x again to clear the download cache",
+                    (None, false) => "clear the download cache",
+                },
+            ),
+            ("l", "lint: list dangling copy-from"),
+            ("a", "about: versions and data source"),
+        ],
+    ));
+
     f.render_widget(Paragraph::new(combined_lines), chunks[2]);
 }
 
+/// Label/value rows of the about overlay; meant to be pasted into bug reports.
+fn about_entries(app: &AppState) -> Vec<(&'static str, String)> {
+    vec![
+        ("cbn-tui", app.app_version.clone()),
+        ("Game version", app.game_version.clone()),
+        ("Version key", app.game_version_key.clone()),
+        ("Objects", app.total_items.to_string()),
+        ("Index built in", format!("{:.1} ms", app.index_time_ms)),
+        ("Data source", app.data_source.clone()),
+    ]
+}
+
+fn render_about_overlay(f: &mut Frame, app: &mut AppState) {
+    let entries = about_entries(app);
+    let area = f.area();
+    let popup_width = area.width.min(76).saturating_sub(4);
+    // Borders, padding and the key hint line.
+    let popup_height = (entries.len() as u16 + 5).min(area.height.saturating_sub(2));
+    if popup_width == 0 || popup_height == 0 {
+        return;
+    }
+    let popup_rect = Rect::new(
+        area.x + (area.width.saturating_sub(popup_width)) / 2,
+        area.y + (area.height.saturating_sub(popup_height)) / 2,
+        popup_width,
+        popup_height,
+    );
+
+    f.render_widget(Clear, popup_rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(app.theme.border_selected)
+        .style(app.theme.text)
+        .title(" About ")
+        .title_bottom(Line::from(" Esc close ").right_aligned())
+        .border_type(ratatui::widgets::BorderType::Double)
+        .title_style(app.theme.title);
+
+    let inner_area = block.inner(popup_rect);
+    f.render_widget(block, popup_rect);
+
+    let lines: Vec<Line> = entries
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{: <16}", label), app.theme.title),
+                Span::styled(value, app.theme.text),
+            ])
+        })
+        .collect();
+    f.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }),
+        inner_area.inner(Margin::new(1, 1)),
+    );
+}
+
+fn render_type_picker(f: &mut Frame, app: &mut AppState) {
+    let items: Vec<ListItem> = app
+        .visible_type_entries()
+        .into_iter()
+        .map(|(item_type, count)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(item_type.clone(), app.theme.text),
+                Span::styled(
+                    format!(" ({})", count),
+                    app.theme.text.add_modifier(Modifier::DIM),
+                ),
+            ]))
+        })
+        .collect();
+
+    render_picker(
+        f,
+        &app.theme,
+        PickerView {
+            title: " Types ",
+            hint: " type to narrow • Enter add t: ",
+            width: 48,
+            query: &app.type_picker_query,
+            items,
+        },
+        &mut app.type_list_state,
+    );
+}
+
+fn render_history_search(f: &mut Frame, app: &mut AppState) {
+    let items: Vec<ListItem> = app
+        .visible_history_matches()
+        .into_iter()
+        .map(|entry| ListItem::new(Line::styled(entry.to_string(), app.theme.text)))
+        .collect();
+
+    render_picker(
+        f,
+        &app.theme,
+        PickerView {
+            title: " Filter History ",
+            hint: " type to search • Enter apply ",
+            width: 64,
+            query: &app.history_search_query,
+            items,
+        },
+        &mut app.history_list_state,
+    );
+}
+
+/// Contents of a centered picker popup: a query line above a selectable list.
+struct PickerView<'a> {
+    title: &'a str,
+    hint: &'a str,
+    /// Maximum popup width
+    width: u16,
+    query: &'a str,
+    items: Vec<ListItem<'a>>,
+}
+
+fn render_picker(
+    f: &mut Frame,
+    theme: &theme::ThemeConfig,
+    view: PickerView,
+    list_state: &mut ratatui::widgets::ListState,
+) {
+    let Some(inner_area) = render_popup_frame(f, theme, view.width, 24, view.title, view.hint)
+    else {
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner_area);
+
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", theme.title),
+            Span::styled(view.query, theme.text),
+        ])),
+        chunks[0],
+    );
+
+    let list = List::new(view.items)
+        .style(theme.list_normal)
+        .highlight_style(theme.list_selected);
+
+    f.render_stateful_widget(list, chunks[1], list_state);
+}
+
+fn render_bookmarks(f: &mut Frame, app: &mut AppState) {
+    // Bookmarks missing from this dataset stay listed, grayed out.
+    let items: Vec<ListItem> = app
+        .bookmarks
+        .ids()
+        .iter()
+        .map(|id| {
+            if app.is_known_id(id) {
+                ListItem::new(Line::styled(id.clone(), app.theme.text))
+            } else {
+                ListItem::new(Line::from(vec![
+                    Span::styled(id.clone(), app.theme.text.add_modifier(Modifier::DIM)),
+                    Span::styled(
+                        " (not in this dataset)",
+                        app.theme
+                            .text
+                            .add_modifier(Modifier::DIM | Modifier::ITALIC),
+                    ),
+                ]))
+            }
+        })
+        .collect();
+
+    render_list_overlay(
+        f,
+        &app.theme,
+        ListOverlay {
+            title: " Bookmarks ",
+            hint: " Enter jump • Del remove ",
+            width: 56,
+            height: 20,
+            empty: "No bookmarks yet. Press Ctrl+S on an object to add one.",
+            items,
+        },
+        &mut app.bookmark_list_state,
+    );
+}
+
+fn render_recent(f: &mut Frame, app: &mut AppState) {
+    // Ids from another dataset stay listed, grayed out, like bookmarks.
+    let items: Vec<ListItem> = app
+        .recent
+        .ids()
+        .iter()
+        .map(|id| {
+            if app.is_known_id(id) {
+                ListItem::new(Line::styled(id.clone(), app.theme.text))
+            } else {
+                ListItem::new(Line::styled(
+                    id.clone(),
+                    app.theme.text.add_modifier(Modifier::DIM),
+                ))
+            }
+        })
+        .collect();
+
+    render_list_overlay(
+        f,
+        &app.theme,
+        ListOverlay {
+            title: " Recently viewed ",
+            hint: " Enter jump ",
+            width: 56,
+            height: 20,
+            empty: "Nothing viewed yet.",
+            items,
+        },
+        &mut app.recent_list_state,
+    );
+}
+
+/// Clears a centered popup of at most `width` x `height` and draws its titled border.
+/// Returns the area inside the border, or `None` if the terminal is too small.
+fn render_popup_frame(
+    f: &mut Frame,
+    theme: &theme::ThemeConfig,
+    width: u16,
+    height: u16,
+    title: &str,
+    hint: &str,
+) -> Option<Rect> {
+    let area = f.area();
+    let popup_width = area.width.min(width).saturating_sub(4);
+    let popup_height = area.height.min(height).saturating_sub(2);
+    if popup_width == 0 || popup_height == 0 {
+        return None;
+    }
+    let popup_rect = Rect::new(
+        area.x + (area.width.saturating_sub(popup_width)) / 2,
+        area.y + (area.height.saturating_sub(popup_height)) / 2,
+        popup_width,
+        popup_height,
+    );
+
+    f.render_widget(Clear, popup_rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border_selected)
+        .style(theme.text)
+        .title(title)
+        .title_style(theme.title)
+        .title_bottom(Line::from(hint).right_aligned());
+
+    let inner_area = block.inner(popup_rect);
+    f.render_widget(block, popup_rect);
+    Some(inner_area)
+}
+
+/// A centered list popup: its frame, its rows, and what to say when there are none.
+struct ListOverlay<'a> {
+    title: &'a str,
+    hint: &'a str,
+    width: u16,
+    height: u16,
+    empty: &'a str,
+    items: Vec<ListItem<'a>>,
+}
+
+fn render_list_overlay(
+    f: &mut Frame,
+    theme: &theme::ThemeConfig,
+    overlay: ListOverlay,
+    list_state: &mut ratatui::widgets::ListState,
+) {
+    let Some(inner_area) = render_popup_frame(
+        f,
+        theme,
+        overlay.width,
+        overlay.height,
+        overlay.title,
+        overlay.hint,
+    ) else {
+        return;
+    };
+
+    if overlay.items.is_empty() {
+        f.render_widget(
+            Paragraph::new(overlay.empty)
+                .style(theme.text.add_modifier(Modifier::DIM))
+                .wrap(Wrap { trim: true }),
+            inner_area.inner(Margin::new(1, 1)),
+        );
+        return;
+    }
+
+    let list = List::new(overlay.items)
+        .style(theme.list_normal)
+        .highlight_style(theme.list_selected);
+
+    f.render_stateful_widget(list, inner_area, list_state);
+}
+
+fn render_lint_report(f: &mut Frame, app: &mut AppState) {
+    let items: Vec<ListItem> = app
+        .lint_entries
+        .iter()
+        .map(|entry| {
+            ListItem::new(crate::lint_line(
+                &app.indexed_items[entry.idx],
+                &entry.parent,
+            ))
+        })
+        .collect();
+
+    let title = format!(" Dangling copy-from ({}) ", app.lint_entries.len());
+    render_list_overlay(
+        f,
+        &app.theme,
+        ListOverlay {
+            title: &title,
+            hint: " Enter jump • Esc close ",
+            width: 88,
+            height: 24,
+            empty: "Every copy-from names an object in this dataset.",
+            items,
+        },
+        &mut app.lint_list_state,
+    );
+}
+
+fn render_warnings(f: &mut Frame, app: &mut AppState) {
+    const WIDTH: u16 = 100;
+    // Messages end with the file path, so they wrap rather than being cut off.
+    // The popup is 4 columns narrower than its width cap, and its border takes 2 more.
+    let width = f.area().width.min(WIDTH).saturating_sub(6).max(1) as usize;
+    let items: Vec<ListItem> = app
+        .source_warnings
+        .iter()
+        .map(|warning| {
+            let chars: Vec<char> = warning.message.chars().collect();
+            let lines: Vec<Line> = chars
+                .chunks(width)
+                .map(|chunk| Line::styled(chunk.iter().collect::<String>(), app.theme.text))
+                .collect();
+            ListItem::new(lines)
+        })
+        .collect();
+
+    let title = format!(" Source warnings ({}) ", app.source_warnings.len());
+    render_list_overlay(
+        f,
+        &app.theme,
+        ListOverlay {
+            title: &title,
+            hint: " Esc close ",
+            width: WIDTH,
+            height: 24,
+            empty: "The last load reported no warnings.",
+            items,
+        },
+        &mut app.warnings_list_state,
+    );
+}
+
 fn render_version_picker(f: &mut Frame, app: &mut AppState) {
     let area = f.area();
     let popup_width = area.width.min(64).saturating_sub(4);
@@ -573,23 +2067,64 @@ fn render_version_picker(f: &mut Frame, app: &mut AppState) {
         .borders(Borders::ALL)
         .border_style(app.theme.border_selected)
         .style(app.theme.text)
-        .title(" Game Versions ")
+        .title(if app.offline {
+            " Game Versions (offline) "
+        } else {
+            " Game Versions "
+        })
         .title_style(app.theme.title);
+    let block = if app.version_picker_query.is_empty() {
+        block.title_bottom(
+            Line::from(" type to narrow • Enter switch • Ctrl+D compare with ").right_aligned(),
+        )
+    } else {
+        block
+            .title(Span::styled(
+                format!("> {} ", app.version_picker_query),
+                app.theme.text,
+            ))
+            .title_bottom(Line::from(" Enter switch • Ctrl+D compare with ").right_aligned())
+    };
 
     let inner_area = block.inner(popup_rect);
     f.render_widget(block, popup_rect);
 
+    if app.version_entries.is_empty() {
+        let hint = if app.offline {
+            "No cached datasets yet. Run once without --offline to download one."
+        } else {
+            "No versions available."
+        };
+        f.render_widget(
+            Paragraph::new(hint)
+                .style(app.theme.text.add_modifier(Modifier::DIM))
+                .wrap(Wrap { trim: true }),
+            inner_area.inner(Margin::new(1, 1)),
+        );
+        return;
+    }
+
     let items: Vec<ListItem> = app
-        .version_entries
-        .iter()
+        .visible_version_entries()
+        .into_iter()
         .map(|entry| {
-            let mut spans = vec![Span::styled(&entry.label, app.theme.text)];
+            let mut spans = vec![Span::styled(entry.label.clone(), app.theme.text)];
+            if let Some(kind) = entry.kind {
+                let style = match kind {
+                    BuildKind::Stable => app.theme.diff_added,
+                    BuildKind::Nightly => app.theme.diff_changed,
+                };
+                spans.push(Span::styled(format!(" ({})", kind.label()), style));
+            }
             if let Some(detail) = &entry.detail {
                 spans.push(Span::styled(
                     format!(" ({})", detail),
                     app.theme.text.add_modifier(Modifier::DIM),
                 ));
             }
+            if app.compare_version() == Some(entry.version.as_str()) {
+                spans.push(Span::styled(" [compared]", app.theme.title));
+            }
             ListItem::new(Line::from(spans))
         })
         .collect();
@@ -602,11 +2137,95 @@ fn render_version_picker(f: &mut Frame, app: &mut AppState) {
     f.render_stateful_widget(list, inner_area, &mut app.version_list_state);
 }
 
+fn render_load_failure_modal(f: &mut Frame, app: &mut AppState) {
+    let Some(failure) = &app.load_failure else {
+        return;
+    };
+    let area = f.area();
+    let popup_width = area.width.min(68).saturating_sub(4);
+    let text_width = popup_width.saturating_sub(4).max(1) as usize;
+    let message_rows = failure
+        .message
+        .lines()
+        .map(|line| line.width().div_ceil(text_width).max(1))
+        .sum::<usize>() as u16;
+    // Borders and padding, the version line, the message and the key hints.
+    let popup_height = area.height.saturating_sub(2).min(message_rows + 8);
+    if popup_width == 0 || popup_height == 0 {
+        return;
+    }
+
+    let popup_rect = Rect::new(
+        area.x + (area.width.saturating_sub(popup_width)) / 2,
+        area.y + (area.height.saturating_sub(popup_height)) / 2,
+        popup_width,
+        popup_height,
+    );
+
+    f.render_widget(Clear, popup_rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(app.theme.border_selected)
+        .style(app.theme.text)
+        .title(if app.offline {
+            " Load failed "
+        } else {
+            " Download failed "
+        })
+        .title_style(app.theme.title);
+
+    let inner_area = block.inner(popup_rect);
+    f.render_widget(block, popup_rect);
+
+    let content_area = inner_area.inner(Margin::new(1, 1));
+    let key_style = app.theme.title;
+    let mut lines = vec![
+        Line::from(format!(
+            "Could not load game data for \"{}\"{}.",
+            failure.version,
+            if failure.compare {
+                " to compare against"
+            } else {
+                ""
+            }
+        )),
+        Line::from(""),
+    ];
+    lines.extend(
+        failure
+            .message
+            .lines()
+            .map(|line| Line::styled(line.to_string(), app.theme.text.add_modifier(Modifier::DIM))),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("r", key_style),
+        Span::raw(" retry   "),
+        Span::styled("Esc", key_style),
+        Span::raw(" dismiss"),
+    ]));
+
+    f.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }),
+        content_area,
+    );
+}
+
 fn render_progress_modal(f: &mut Frame, app: &mut AppState) {
+    app.progress_frame = app.progress_frame.wrapping_add(1);
     let area = f.area();
     let stages_len = app.progress_stages.len().max(1) as u16;
+    let detail_rows = app
+        .progress_stages
+        .iter()
+        .filter(|stage| stage.detail.is_some())
+        .count() as u16;
     let popup_width = area.width.min(68).saturating_sub(4);
-    let popup_height = area.height.saturating_sub(2).min(stages_len + 4);
+    let popup_height = area
+        .height
+        .saturating_sub(2)
+        .min(stages_len + detail_rows + 4);
     if popup_width == 0 || popup_height == 0 {
         return;
     }
@@ -625,6 +2244,7 @@ fn render_progress_modal(f: &mut Frame, app: &mut AppState) {
         .border_style(app.theme.border_selected)
         .style(app.theme.text)
         .title(format!(" {} ", app.progress_title))
+        .title_bottom(Line::from(" Esc to cancel ").right_aligned())
         .title_style(app.theme.title);
 
     let inner_area = block.inner(popup_rect);
@@ -660,12 +2280,21 @@ fn render_progress_modal(f: &mut Frame, app: &mut AppState) {
     }
     let gap = if label_width > 0 { 1 } else { 0 };
 
+    let row_heights: Vec<Constraint> = (0..stages_len as usize)
+        .map(|idx| {
+            let has_detail = app
+                .progress_stages
+                .get(idx)
+                .is_some_and(|stage| stage.detail.is_some());
+            Constraint::Length(if has_detail { 2 } else { 1 })
+        })
+        .collect();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Length(1); stages_len as usize])
+        .constraints(row_heights)
         .split(content_area);
 
-    for (idx, area) in chunks.iter().enumerate() {
+    for (idx, stage_area) in chunks.iter().enumerate() {
         let stage = app
             .progress_stages
             .get(idx)
@@ -674,9 +2303,33 @@ fn render_progress_modal(f: &mut Frame, app: &mut AppState) {
                 label: "Working".to_string(),
                 ratio: 0.0,
                 done: false,
+                indeterminate: false,
+                detail: None,
             });
+        let area = &Rect {
+            height: 1,
+            ..*stage_area
+        };
+        if let Some(detail) = &stage.detail
+            && stage_area.height > 1
+        {
+            f.render_widget(
+                Paragraph::new(detail.as_str())
+                    .style(app.theme.text.add_modifier(Modifier::DIM))
+                    .alignment(Alignment::Right),
+                Rect {
+                    y: stage_area.y + 1,
+                    height: 1,
+                    ..*stage_area
+                },
+            );
+        }
         let ratio = stage.ratio.clamp(0.0, 1.0);
-        let percent_label = format!("{:.0}%", ratio * 100.0);
+        let percent_label = if stage.indeterminate {
+            SPINNER_FRAMES[app.progress_frame % SPINNER_FRAMES.len()].to_string()
+        } else {
+            format!("{:.0}%", ratio * 100.0)
+        };
         let row_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -696,12 +2349,19 @@ fn render_progress_modal(f: &mut Frame, app: &mut AppState) {
             f.render_widget(Paragraph::new(label).style(app.theme.text), row_chunks[0]);
         }
 
-        let gauge = LineGauge::default()
-            .filled_style(app.theme.title)
-            .unfilled_style(app.theme.border)
-            .ratio(ratio)
-            .label("");
-        f.render_widget(gauge, row_chunks[2]);
+        if stage.indeterminate {
+            f.render_widget(
+                indeterminate_bar(row_chunks[2].width, app.progress_frame, &app.theme),
+                row_chunks[2],
+            );
+        } else {
+            let gauge = LineGauge::default()
+                .filled_style(app.theme.title)
+                .unfilled_style(app.theme.border)
+                .ratio(ratio)
+                .label("");
+            f.render_widget(gauge, row_chunks[2]);
+        }
 
         f.render_widget(
             Paragraph::new(percent_label)
@@ -712,8 +2372,44 @@ fn render_progress_modal(f: &mut Frame, app: &mut AppState) {
     }
 }
 
+/// Spinner shown in place of the percentage of a stage with no known size.
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Columns of a `width`-wide indeterminate bar lit at animation step `frame`:
+/// a quarter-width segment bouncing between the ends, one column per step.
+fn indeterminate_segment(width: u16, frame: usize) -> std::ops::Range<u16> {
+    let len = (width / 4).max(1).min(width);
+    let travel = (width - len) as usize;
+    if travel == 0 {
+        return 0..len;
+    }
+    let step = frame % (2 * travel);
+    let start = if step <= travel {
+        step
+    } else {
+        2 * travel - step
+    } as u16;
+    start..start + len
+}
+
+/// A `LineGauge`-like line with a moving segment instead of a fill level.
+fn indeterminate_bar(width: u16, frame: usize, theme: &theme::ThemeConfig) -> Line<'static> {
+    let segment = indeterminate_segment(width, frame);
+    let bar = |len: u16| ratatui::symbols::line::HORIZONTAL.repeat(len as usize);
+    Line::from(vec![
+        Span::styled(bar(segment.start), theme.border),
+        Span::styled(bar(segment.end - segment.start), theme.title),
+        Span::styled(bar(width - segment.end), theme.border),
+    ])
+}
+
 pub(crate) fn display_name_for_item(json: &Value, id: &str, type_: &str) -> String {
     if !id.is_empty() {
+        // Array ids show their first member; the rest are aliases.
+        let aliases = crate::data::string_members(json.get("id")).count();
+        if aliases > 1 {
+            return format!("{} (+{})", id, aliases - 1);
+        }
         return id.to_string();
     }
 
@@ -773,63 +2469,310 @@ fn fallback_display_name(json: &Value, id: &str, type_: &str) -> Option<String>
             }
             None
         }
-        _ => None,
+        "mapgen" => {
+            // om_terrain may be a name, a list of names or a grid of them.
+            if let Some(terrain) = first_nested_str(json.get("om_terrain")) {
+                return Some(format!("om_terrain: {}", terrain));
+            }
+            [
+                ("nested_mapgen_id", "nested"),
+                ("update_mapgen_id", "update"),
+            ]
+            .into_iter()
+            .find_map(|(field, label)| {
+                first_nested_str(json.get(field)).map(|v| format!("{}: {}", label, v))
+            })
+        }
+        "overmap_special" => {
+            let overmap = json
+                .get("overmaps")?
+                .as_array()?
+                .iter()
+                .find_map(|entry| first_nested_str(entry.get("overmap")))?;
+            Some(format!("overmap: {}", overmap))
+        }
+        "effect_on_condition" => {
+            if let Some(event) = first_nested_str(json.get("required_event")) {
+                return Some(format!("event: {}", event));
+            }
+            first_nested_str(json.get("eoc_type")).map(|v| format!("eoc_type: {}", v))
+        }
+        "talk_topic" => {
+            first_nested_str(json.get("dynamic_line")).map(|line| format!("line: {}", line))
+        }
+        _ => None,
+    }
+}
+
+/// The first non-empty string in `value`, looking through nested arrays.
+fn first_nested_str(value: Option<&Value>) -> Option<&str> {
+    match value? {
+        Value::String(s) if !s.is_empty() => Some(s),
+        Value::Array(arr) => arr.iter().find_map(|v| first_nested_str(Some(v))),
+        _ => None,
+    }
+}
+
+/// Readable text of a translatable `name`: a plain string, an object with
+/// `str` (or `str_sp`, or just `str_pl`) next to fields like `ctxt`, or an
+/// array of those, of which the first usable one is taken.
+pub(crate) fn name_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(name) => Some(name.clone()),
+        Value::Array(names) => names.iter().find_map(name_value),
+        Value::Object(fields) => ["str", "str_sp", "str_pl"]
+            .into_iter()
+            .find_map(|key| fields.get(key)?.as_str().filter(|s| !s.is_empty()))
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Applies syntax highlighting to JSON text using theme-consistent colors.
+/// Returns a Text object for ratatui rendering.
+/// Converts a matrix of AnnotatedSpans into a ratatui Text object.
+/// Takes a borrow so callers avoid an expensive clone of the full buffer.
+pub fn annotated_to_text<'a>(
+    annotated: &'a [Vec<AnnotatedSpan>],
+    hovered_span_id: Option<usize>,
+    focused_span_id: Option<usize>,
+    selection: Option<&DetailsSelection>,
+) -> Text<'a> {
+    Text::from(
+        annotated
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                let selected = selection.and_then(|sel| sel.columns_on(idx));
+                let mut column = 0;
+                let mut spans = Vec::with_capacity(line.len());
+                for as_ in line {
+                    let mut style = as_.span.style;
+                    if hovered_span_id.is_some() && as_.span_id == hovered_span_id {
+                        style = style.add_modifier(Modifier::UNDERLINED);
+                    }
+                    if focused_span_id.is_some() && as_.span_id == focused_span_id {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    let content = as_.span.content.as_ref();
+                    match selected {
+                        Some(columns) => {
+                            push_selected_parts(&mut spans, content, style, column, columns)
+                        }
+                        None => spans.push(Span::styled(content, style)),
+                    }
+                    column += as_.span.width();
+                }
+                Line::from(spans)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Pushes `content`, starting at `column`, as runs of spans with the cells in
+/// `columns` (end exclusive) shown as selected.
+fn push_selected_parts<'a>(
+    spans: &mut Vec<Span<'a>>,
+    content: &'a str,
+    style: Style,
+    mut column: usize,
+    (from, to): (usize, usize),
+) {
+    let mut run_start = 0;
+    let mut run_selected = None;
+    for (offset, c) in content.char_indices() {
+        let selected = column >= from && column < to;
+        if run_selected.is_some_and(|prev| prev != selected) {
+            spans.push(selection_part(
+                &content[run_start..offset],
+                style,
+                !selected,
+            ));
+            run_start = offset;
+        }
+        run_selected = Some(selected);
+        column += UnicodeWidthChar::width(c).unwrap_or(0);
+    }
+    if let Some(selected) = run_selected {
+        spans.push(selection_part(&content[run_start..], style, selected));
+    }
+}
+
+fn selection_part(text: &str, style: Style, selected: bool) -> Span<'_> {
+    if selected {
+        Span::styled(text, style.add_modifier(Modifier::REVERSED))
+    } else {
+        Span::styled(text, style)
+    }
+}
+
+/// Patches `style` onto every value span that one of the query `terms` matched.
+///
+/// Classifier terms only apply to spans whose `key_context` is the classified
+/// path, so `t:gun` marks the `type` value but not a `name` that says "gun".
+pub fn highlight_search_matches(
+    lines: &mut [Vec<AnnotatedSpan>],
+    terms: &[SearchTerm],
+    style: Style,
+) {
+    if terms.is_empty() {
+        return;
+    }
+
+    // Lowercase non-exact patterns once, as matches_value expects.
+    let patterns: Vec<(&SearchTerm, String, Option<matcher::Glob>)> = terms
+        .iter()
+        .map(|term| {
+            let pattern = if term.exact {
+                term.pattern.clone()
+            } else {
+                term.pattern.to_lowercase()
+            };
+            (term, pattern, term.glob())
+        })
+        .collect();
+
+    for span in lines.iter_mut().flatten() {
+        if !matches!(
+            span.kind,
+            JsonSpanKind::StringValue | JsonSpanKind::NumberValue | JsonSpanKind::BooleanValue
+        ) {
+            continue;
+        }
+        // Spans hold the JSON token text; parse it back to reuse matches_value.
+        let Ok(value) = serde_json::from_str::<Value>(&span.span.content) else {
+            continue;
+        };
+        let path = span.key_context.as_deref().unwrap_or("");
+        let is_match = patterns.iter().any(|(term, pattern, glob)| {
+            let classifier = term.classifier.as_deref();
+            // Number comparisons only apply to a field, as in the search itself.
+            let number_filter = classifier.and(term.number_filter());
+            classifier.is_none_or(|c| matcher::classifier_targets_path(c, path))
+                && match (number_filter, glob) {
+                    (Some(filter), _) => matcher::matches_value_number(&value, filter),
+                    (None, Some(glob)) => matcher::matches_value_glob(&value, glob),
+                    (None, None) => matcher::matches_value(&value, pattern, term.exact),
+                }
+        });
+        if is_match {
+            span.span.style = span.span.style.patch(style);
+        }
     }
 }
 
-fn name_value(value: &Value) -> Option<String> {
-    if let Some(name_str) = value.as_str() {
-        return Some(name_str.to_string());
-    }
-    if let Some(name_str) = value.get("str").and_then(|v| v.as_str()) {
-        return Some(name_str.to_string());
+/// Inserts thousands separators into an integer literal, e.g. `1500000` → `1,500,000`.
+/// Returns `None` for anything else: fractions, exponents and short numbers stay as written.
+pub fn group_digits(token: &str) -> Option<String> {
+    let (sign, digits) = match token.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", token),
+    };
+    if digits.len() < 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
     }
-    if let Some(name_str) = value.get("str_sp").and_then(|v| v.as_str()) {
-        return Some(name_str.to_string());
+    let mut grouped = String::with_capacity(token.len() + digits.len() / 3);
+    grouped.push_str(sign);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
     }
-    None
+    Some(grouped)
 }
 
-/// Applies syntax highlighting to JSON text using theme-consistent colors.
-/// Returns a Text object for ratatui rendering.
-/// Converts a matrix of AnnotatedSpans into a ratatui Text object.
-/// Takes a borrow so callers avoid an expensive clone of the full buffer.
-pub fn annotated_to_text(
-    annotated: &'_ [Vec<AnnotatedSpan>],
-    hovered_span_id: Option<usize>,
-) -> Text<'_> {
-    Text::from(
-        annotated
-            .iter()
-            .map(|line| {
-                Line::from(
-                    line.iter()
-                        .map(|as_| {
-                            let mut style = as_.span.style;
-                            if hovered_span_id.is_some() && as_.span_id == hovered_span_id {
-                                style = style.add_modifier(Modifier::UNDERLINED);
-                            }
-                            Span::styled(as_.span.content.as_ref(), style)
-                        })
-                        .collect::<Vec<_>>(),
-                )
-            })
-            .collect::<Vec<_>>(),
-    )
+/// Copy of `lines` with digit grouping applied to number spans, for display only.
+/// Span ids and key contexts are kept, so clicks still resolve to the original
+/// text in the ungrouped lines.
+pub fn group_number_spans(lines: &[Vec<AnnotatedSpan>]) -> Vec<Vec<AnnotatedSpan>> {
+    lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|annotated| {
+                    let grouped = (annotated.kind == JsonSpanKind::NumberValue)
+                        .then(|| group_digits(&annotated.span.content))
+                        .flatten();
+                    match grouped {
+                        Some(text) => AnnotatedSpan {
+                            span: Span::styled(text, annotated.span.style),
+                            ..annotated.clone()
+                        },
+                        None => annotated.clone(),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// String values at least this wide are shown unquoted by `unquote_long_strings`.
+const LONG_STRING_WIDTH: usize = 40;
+
+/// Copy of `lines` with the enclosing quotes removed from long string values,
+/// for display only. Span ids are kept, so clicks and `c` still read the quoted
+/// text of the original lines.
+pub fn unquote_long_strings(lines: &[Vec<AnnotatedSpan>]) -> Vec<Vec<AnnotatedSpan>> {
+    lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|annotated| {
+                    let content = &annotated.span.content;
+                    let inner = (annotated.kind == JsonSpanKind::StringValue
+                        && content.width() >= LONG_STRING_WIDTH)
+                        .then(|| content.strip_prefix('"')?.strip_suffix('"'))
+                        .flatten();
+                    match inner {
+                        Some(text) => AnnotatedSpan {
+                            span: Span::styled(text.to_string(), annotated.span.style),
+                            ..annotated.clone()
+                        },
+                        None => annotated.clone(),
+                    }
+                })
+                .collect()
+        })
+        .collect()
 }
 
 /// Wraps a matrix of AnnotatedSpans into lines that fit within the given width.
-/// Performs simple character-level wrapping.
-pub fn wrap_annotated_lines(lines: &[Vec<AnnotatedSpan>], width: u16) -> Vec<Vec<AnnotatedSpan>> {
+/// Performs simple character-level wrapping. Also returns, for each wrapped line,
+/// whether it continues on the next one (a wrap rather than an original line break).
+pub fn wrap_annotated_lines(
+    lines: &[Vec<AnnotatedSpan>],
+    width: u16,
+) -> (Vec<Vec<AnnotatedSpan>>, Vec<bool>) {
+    wrap_lines(lines, width, false)
+}
+
+/// Like `wrap_annotated_lines`, but string values break after a space where
+/// possible, and a word that doesn't fit the rest of a line starts the next one.
+/// Words wider than the whole width still break between characters.
+pub fn wrap_annotated_lines_at_words(
+    lines: &[Vec<AnnotatedSpan>],
+    width: u16,
+) -> (Vec<Vec<AnnotatedSpan>>, Vec<bool>) {
+    wrap_lines(lines, width, true)
+}
+
+fn wrap_lines(
+    lines: &[Vec<AnnotatedSpan>],
+    width: u16,
+    at_words: bool,
+) -> (Vec<Vec<AnnotatedSpan>>, Vec<bool>) {
     let mut wrapped = Vec::new();
+    let mut continued = Vec::new();
     let width = width as usize;
     if width == 0 {
-        return Vec::new();
+        return (Vec::new(), Vec::new());
     }
 
     for line in lines {
         if line.is_empty() {
             wrapped.push(Vec::new());
+            continued.push(false);
             continue;
         }
 
@@ -842,6 +2785,7 @@ pub fn wrap_annotated_lines(lines: &[Vec<AnnotatedSpan>], width: u16) -> Vec<Vec
                 let remaining_width = width.saturating_sub(current_width);
                 if remaining_width == 0 {
                     wrapped.push(current_wrapped_line);
+                    continued.push(true);
                     current_wrapped_line = Vec::new();
                     current_width = 0;
                     continue;
@@ -858,6 +2802,25 @@ pub fn wrap_annotated_lines(lines: &[Vec<AnnotatedSpan>], width: u16) -> Vec<Vec
                     fit_width += w;
                 }
 
+                if at_words
+                    && annotated.kind == JsonSpanKind::StringValue
+                    && fit_len < content.len()
+                {
+                    if let Some(space) = content[..fit_len].rfind(' ') {
+                        fit_len = space + 1;
+                        fit_width = content[..fit_len].width();
+                    } else if current_width > 0
+                        && content.split(' ').next().unwrap_or(content).width() <= width
+                    {
+                        // The word fits on a line of its own: start one.
+                        wrapped.push(current_wrapped_line);
+                        continued.push(true);
+                        current_wrapped_line = Vec::new();
+                        current_width = 0;
+                        continue;
+                    }
+                }
+
                 if fit_len > 0 {
                     let part = &content[..fit_len];
                     current_wrapped_line.push(AnnotatedSpan {
@@ -873,6 +2836,7 @@ pub fn wrap_annotated_lines(lines: &[Vec<AnnotatedSpan>], width: u16) -> Vec<Vec
                     // Push the current line and start a new one.
                     if !current_wrapped_line.is_empty() {
                         wrapped.push(current_wrapped_line);
+                        continued.push(true);
                         current_wrapped_line = Vec::new();
                         current_width = 0;
                     } else {
@@ -889,6 +2853,7 @@ pub fn wrap_annotated_lines(lines: &[Vec<AnnotatedSpan>], width: u16) -> Vec<Vec
                             span_id: annotated.span_id,
                         });
                         wrapped.push(current_wrapped_line);
+                        continued.push(true);
                         current_wrapped_line = Vec::new();
                         current_width = 0;
                         content = &content[first_len..];
@@ -898,9 +2863,13 @@ pub fn wrap_annotated_lines(lines: &[Vec<AnnotatedSpan>], width: u16) -> Vec<Vec
         }
         if !current_wrapped_line.is_empty() {
             wrapped.push(current_wrapped_line);
+            continued.push(false);
+        } else if let Some(last) = continued.last_mut() {
+            // The line ended exactly at a wrap point.
+            *last = false;
         }
     }
-    wrapped
+    (wrapped, continued)
 }
 
 #[derive(Debug, Default)]
@@ -1047,9 +3016,7 @@ pub fn highlight_json_annotated(
                         spans.push(AnnotatedSpan {
                             span: Span::styled(
                                 format!("\"{}\"", quoted),
-                                Style::default()
-                                    .fg(json_style.key)
-                                    .add_modifier(Modifier::BOLD),
+                                json_style.key.add_modifier(Modifier::BOLD),
                             ),
                             kind: JsonSpanKind::Key,
                             key_context: state.current_key(),
@@ -1057,10 +3024,7 @@ pub fn highlight_json_annotated(
                         });
                     } else {
                         spans.push(AnnotatedSpan {
-                            span: Span::styled(
-                                format!("\"{}\"", quoted),
-                                Style::default().fg(json_style.string),
-                            ),
+                            span: Span::styled(format!("\"{}\"", quoted), json_style.string),
                             kind: JsonSpanKind::StringValue,
                             key_context: state.current_key(),
                             span_id: Some(state.next_id()),
@@ -1069,10 +3033,7 @@ pub fn highlight_json_annotated(
                     remaining = &rest[ep + 1..];
                 } else {
                     spans.push(AnnotatedSpan {
-                        span: Span::styled(
-                            remaining.to_string(),
-                            Style::default().fg(json_style.string),
-                        ),
+                        span: Span::styled(remaining.to_string(), json_style.string),
                         kind: JsonSpanKind::StringValue,
                         key_context: state.current_key(),
                         span_id: Some(state.next_id()),
@@ -1090,6 +3051,95 @@ pub fn highlight_json_annotated(
     lines
 }
 
+/// Flattens `value` into `(path, leaf)` pairs in document order. Object keys are
+/// joined with `.` and array elements get `[i]`, e.g. `flags[0]`; empty arrays
+/// and objects are leaves themselves.
+pub fn flatten_json(value: &Value) -> Vec<(String, &Value)> {
+    fn walk<'a>(value: &'a Value, path: &mut String, out: &mut Vec<(String, &'a Value)>) {
+        let len = path.len();
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                    walk(child, path, out);
+                    path.truncate(len);
+                }
+            }
+            Value::Array(items) if !items.is_empty() => {
+                for (idx, child) in items.iter().enumerate() {
+                    path.push_str(&format!("[{}]", idx));
+                    walk(child, path, out);
+                    path.truncate(len);
+                }
+            }
+            _ => out.push((path.clone(), value)),
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(value, &mut String::new(), &mut out);
+    out
+}
+
+/// The details lines of the table view: one `path = value` line per leaf of
+/// [`flatten_json`]. Values carry the path without array indices, the form the
+/// filter understands, so clicking one filters by it as in the JSON view.
+pub fn table_annotated(value: &Value, json_style: &theme::JsonStyle) -> Vec<Vec<AnnotatedSpan>> {
+    flatten_json(value)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (path, leaf))| {
+            let key_context: Rc<str> = Rc::from(without_indices(&path));
+            let (style, kind) = match leaf {
+                Value::String(_) => (json_style.string, JsonSpanKind::StringValue),
+                Value::Number(_) => (json_style.number, JsonSpanKind::NumberValue),
+                Value::Bool(_) | Value::Null => (json_style.boolean, JsonSpanKind::BooleanValue),
+                // `[]` and `{}` are shown, but there's nothing to filter by.
+                _ => (Style::default(), JsonSpanKind::Punctuation),
+            };
+            let span_id = (kind != JsonSpanKind::Punctuation).then_some(idx);
+            vec![
+                AnnotatedSpan {
+                    span: Span::styled(path, json_style.key.add_modifier(Modifier::BOLD)),
+                    kind: JsonSpanKind::Key,
+                    key_context: Some(Rc::clone(&key_context)),
+                    span_id: None,
+                },
+                AnnotatedSpan {
+                    span: Span::raw(" = "),
+                    kind: JsonSpanKind::Punctuation,
+                    key_context: None,
+                    span_id: None,
+                },
+                AnnotatedSpan {
+                    span: Span::styled(leaf.to_string(), style),
+                    kind,
+                    key_context: Some(key_context),
+                    span_id,
+                },
+            ]
+        })
+        .collect()
+}
+
+/// `a[0].b[1]` → `a.b`.
+fn without_indices(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut in_index = false;
+    for c in path.chars() {
+        match c {
+            '[' => in_index = true,
+            ']' => in_index = false,
+            _ if !in_index => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
 fn process_non_quoted(
     content: &str,
     json_style: &theme::JsonStyle,
@@ -1130,7 +3180,7 @@ fn process_non_quoted(
 
         let (styled, kind) = if token == "true" || token == "false" || token == "null" {
             (
-                Span::styled(token.to_string(), Style::default().fg(json_style.boolean)),
+                Span::styled(token.to_string(), json_style.boolean),
                 JsonSpanKind::BooleanValue,
             )
         } else if (token
@@ -1140,7 +3190,7 @@ fn process_non_quoted(
             && token.chars().any(|c| c.is_numeric())
         {
             (
-                Span::styled(token.to_string(), Style::default().fg(json_style.number)),
+                Span::styled(token.to_string(), json_style.number),
                 JsonSpanKind::NumberValue,
             )
         } else if token == ":"
@@ -1197,6 +3247,26 @@ fn process_non_quoted(
 /// Given a click at (column, row), resolves the annotated span under the cursor.
 /// Returns None if the click is outside the details pane.
 pub fn hit_test_details(app: &AppState, column: u16, row: u16) -> Option<&AnnotatedSpan> {
+    let pos = details_position_at(app, column, row)?;
+    let line = app.details_wrapped_annotated.get(pos.line)?;
+    let mut current_x = 0;
+    for annotated in line {
+        let span_width = annotated.span.width();
+        if pos.column >= current_x && pos.column < current_x + span_width {
+            return Some(annotated);
+        }
+        current_x += span_width;
+    }
+    None
+}
+
+/// Maps a screen cell inside the details body to a line and column of the
+/// wrapped details text, accounting for scrolling.
+pub fn details_position_at(app: &AppState, column: u16, row: u16) -> Option<TextPos> {
+    // Diff rows don't map onto the annotated lines.
+    if app.details_diff.is_some() {
+        return None;
+    }
     let area = app.details_content_area?;
     let horizontal_padding = 1;
 
@@ -1216,23 +3286,16 @@ pub fn hit_test_details(app: &AppState, column: u16, row: u16) -> Option<&Annota
     // Ensure rel_y is within [0, area.height) relative to the content area
     let rel_y = row.saturating_sub(area.y);
 
-    // Account for scroll offset
+    // Account for scroll offset (horizontal only matters in no-wrap mode)
     let scroll_offset = app.details_scroll_state.offset();
-    let content_y = (rel_y + scroll_offset.y) as usize;
-
-    // Details pane now uses pre-wrapped lines
-    if let Some(line) = app.details_wrapped_annotated.get(content_y) {
-        let mut current_x = 0;
-        for annotated in line {
-            let span_width = annotated.span.width() as u16;
-            if rel_x >= current_x && rel_x < current_x + span_width {
-                return Some(annotated);
-            }
-            current_x += span_width;
-        }
+    let line = (rel_y + scroll_offset.y) as usize;
+    if line >= app.details_wrapped_annotated.len() {
+        return None;
     }
-
-    None
+    Some(TextPos {
+        line,
+        column: rel_x.saturating_add(scroll_offset.x) as usize,
+    })
 }
 
 /// Calculates the terminal cell width offset for a given character index.
@@ -1255,6 +3318,32 @@ fn filter_viewport_offset(text: &str, cursor: usize, visible_width: u16) -> u16
     cursor_offset.saturating_sub(visible_width.saturating_sub(1))
 }
 
+/// Highlights the filter input by query token. Only styles change, so the
+/// cursor offsets computed from the plain text still line up.
+fn filter_spans<'a>(text: &'a str, theme: &theme::ThemeConfig) -> Vec<Span<'a>> {
+    matcher::tokenize_query(text)
+        .into_iter()
+        .map(|(kind, range)| {
+            let style = match kind {
+                matcher::QueryTokenKind::Classifier => theme.json_style.key,
+                matcher::QueryTokenKind::Exact => theme.json_style.string,
+                matcher::QueryTokenKind::Unterminated => theme.query_error,
+                matcher::QueryTokenKind::Operator => theme.title,
+                matcher::QueryTokenKind::Space | matcher::QueryTokenKind::Pattern => {
+                    Style::default()
+                }
+            };
+            Span::styled(&text[range], style)
+        })
+        .collect()
+}
+
+/// Position shown while cycling filter history, e.g. " history 3/10 ".
+fn history_indicator(history_index: Option<usize>, history_len: usize) -> Option<String> {
+    let idx = history_index?;
+    Some(format!(" history {}/{} ", idx + 1, history_len))
+}
+
 pub fn filter_horizontal_scroll(text: &str, cursor: usize, visible_width: u16) -> u16 {
     filter_viewport_offset(text, cursor, visible_width)
 }
@@ -1275,6 +3364,45 @@ pub fn filter_cursor_for_column(text: &str, target_column: u16) -> usize {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_flatten_json_nested_object_with_arrays() {
+        let value = serde_json::json!({
+            "id": "rifle",
+            "flags": ["A", "B"],
+            "ranged_damage": {"amount": 10, "types": [{"kind": "bullet"}]},
+            "ammo": [],
+            "note": null
+        });
+        let flat: Vec<(String, String)> = flatten_json(&value)
+            .into_iter()
+            .map(|(path, leaf)| (path, leaf.to_string()))
+            .collect();
+        let expected = [
+            ("id", "\"rifle\""),
+            ("flags[0]", "\"A\""),
+            ("flags[1]", "\"B\""),
+            ("ranged_damage.amount", "10"),
+            ("ranged_damage.types[0].kind", "\"bullet\""),
+            ("ammo", "[]"),
+            ("note", "null"),
+        ];
+        assert_eq!(
+            flat,
+            expected.map(|(path, leaf)| (path.to_string(), leaf.to_string()))
+        );
+
+        // Values are linked by the path the filter understands.
+        let lines = table_annotated(&value, &theme::Theme::Dracula.config().json_style);
+        assert_eq!(lines.len(), expected.len());
+        let kind = &lines[4][2];
+        assert_eq!(
+            kind.key_context.as_deref(),
+            Some("ranged_damage.types.kind")
+        );
+        assert!(kind.span_id.is_some());
+        assert!(lines[5][2].span_id.is_none());
+    }
+
     #[test]
     fn test_annotated_spans_key_value_pair() {
         let json_str = r#"  "range": 60"#;
@@ -1359,7 +3487,7 @@ mod tests {
         let json_str = r#"{"id": "test", "num": 123}"#;
         let style = theme::Theme::Dracula.config().json_style;
         let annotated = highlight_json_annotated(json_str, &style);
-        let text = annotated_to_text(&annotated, None);
+        let text = annotated_to_text(&annotated, None, None, None);
 
         // Verification: ensure it still has some styled spans
         let mut has_styles = false;
@@ -1414,11 +3542,333 @@ mod tests {
         assert_eq!(val_x.key_context, Some(Rc::from("arr")));
     }
 
+    #[test]
+    fn test_header_field_values() {
+        let json = serde_json::json!({
+            "type": "GUN",
+            "id": "rifle",
+            "range": 25,
+            "ranged_damage": [{"damage_type": "bullet", "amount": 30}],
+        });
+        let mut fields = BTreeMap::new();
+        fields.insert(
+            "GUN".to_string(),
+            vec![
+                "ranged_damage.amount".to_string(),
+                "range".to_string(),
+                "missing".to_string(),
+            ],
+        );
+
+        assert_eq!(
+            header_field_values(&json, "GUN", &fields),
+            vec![
+                ("ranged_damage.amount", "30".to_string()),
+                ("range", "25".to_string())
+            ]
+        );
+        // Types without a mapping keep the plain four-field header.
+        assert!(header_field_values(&json, "COMESTIBLE", &fields).is_empty());
+    }
+
+    #[test]
+    fn test_details_content_area_below_header_fields() {
+        let mut app = create_test_app();
+        app.list_state.select(Some(0));
+        let area = Rect::new(0, 0, 40, 20);
+        // Border, two header rows, separator, breadcrumb.
+        assert_eq!(compute_details_content_area(&app, area).unwrap().y, 5);
+
+        app.header_fields
+            .insert("t".to_string(), vec!["id".to_string()]);
+        assert_eq!(compute_details_content_area(&app, area).unwrap().y, 6);
+    }
+
+    #[test]
+    fn test_about_entries() {
+        let mut app = create_test_app();
+        app.game_version = "0.9.1".to_string();
+        app.game_version_key = "stable".to_string();
+        app.index_time_ms = 12.34;
+        app.data_source = "source dir /tmp/bn".to_string();
+
+        assert_eq!(
+            about_entries(&app),
+            vec![
+                ("cbn-tui", "v1".to_string()),
+                ("Game version", "0.9.1".to_string()),
+                ("Version key", "stable".to_string()),
+                ("Objects", "1".to_string()),
+                ("Index built in", "12.3 ms".to_string()),
+                ("Data source", "source dir /tmp/bn".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display_name_for_array_id() {
+        let json = serde_json::json!({"id": ["rifle", "rifle_old", "rifle_older"], "type": "GUN"});
+        assert_eq!(display_name_for_item(&json, "rifle", "GUN"), "rifle (+2)");
+        let json = serde_json::json!({"id": "rifle", "type": "GUN"});
+        assert_eq!(display_name_for_item(&json, "rifle", "GUN"), "rifle");
+    }
+
+    #[test]
+    fn test_fallback_display_names() {
+        let name = |json: Value| {
+            let type_ = json["type"].as_str().unwrap_or_default().to_string();
+            display_name_for_item(&json, "", &type_)
+        };
+
+        assert_eq!(
+            name(serde_json::json!({"type": "mapgen", "om_terrain": "house_01"})),
+            "om_terrain: house_01"
+        );
+        assert_eq!(
+            name(
+                serde_json::json!({"type": "mapgen", "om_terrain": [["lab_1", "lab_2"], ["lab_3"]]})
+            ),
+            "om_terrain: lab_1"
+        );
+        assert_eq!(
+            name(serde_json::json!({"type": "mapgen", "nested_mapgen_id": "room_9x9"})),
+            "nested: room_9x9"
+        );
+        assert_eq!(
+            name(serde_json::json!({"type": "mapgen", "update_mapgen_id": "fire_ruins"})),
+            "update: fire_ruins"
+        );
+        assert_eq!(
+            name(serde_json::json!({
+                "type": "overmap_special",
+                "overmaps": [{"point": [0, 0, 0], "overmap": "farm_north"}]
+            })),
+            "overmap: farm_north"
+        );
+        assert_eq!(
+            name(
+                serde_json::json!({"type": "effect_on_condition", "eoc_type": "EVENT", "required_event": "avatar_moves"})
+            ),
+            "event: avatar_moves"
+        );
+        assert_eq!(
+            name(serde_json::json!({"type": "effect_on_condition", "eoc_type": "ACTIVATION"})),
+            "eoc_type: ACTIVATION"
+        );
+        assert_eq!(
+            name(serde_json::json!({"type": "talk_topic", "dynamic_line": "Hello there."})),
+            "line: Hello there."
+        );
+
+        // Unknown types, and known ones without their fields, stay anonymous.
+        assert_eq!(
+            name(serde_json::json!({"type": "mapgen", "method": "json"})),
+            "(?)"
+        );
+        assert_eq!(
+            name(serde_json::json!({"type": "overmap_special", "overmaps": []})),
+            "(?)"
+        );
+        assert_eq!(
+            name(serde_json::json!({"type": "snippet", "text": "x"})),
+            "(?)"
+        );
+    }
+
+    #[test]
+    fn test_name_value_shapes() {
+        let name = |value: Value| name_value(&value);
+        assert_eq!(name(serde_json::json!("rifle")).as_deref(), Some("rifle"));
+        assert_eq!(
+            name(serde_json::json!({"str": "rifle"})).as_deref(),
+            Some("rifle")
+        );
+        assert_eq!(
+            name(serde_json::json!({"ctxt": "weapon", "str": "rifle", "str_pl": "rifles"}))
+                .as_deref(),
+            Some("rifle")
+        );
+        assert_eq!(
+            name(serde_json::json!({"str_sp": "sheep"})).as_deref(),
+            Some("sheep")
+        );
+        assert_eq!(
+            name(serde_json::json!({"ctxt": "plural only", "str_pl": "pants"})).as_deref(),
+            Some("pants")
+        );
+        assert_eq!(
+            name(serde_json::json!([{"ctxt": "x", "str": "first"}, "second"])).as_deref(),
+            Some("first")
+        );
+        assert_eq!(
+            name(serde_json::json!([{}, "second"])).as_deref(),
+            Some("second")
+        );
+
+        assert_eq!(name(serde_json::json!({"ctxt": "no text"})), None);
+        assert_eq!(name(serde_json::json!([])), None);
+        assert_eq!(name(serde_json::json!(3)), None);
+
+        let item = serde_json::json!({"type": "GENERIC", "name": {"ctxt": "x", "str": "rag"}});
+        assert_eq!(display_name_for_item(&item, "", "GENERIC"), "rag");
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits("1500000").as_deref(), Some("1,500,000"));
+        assert_eq!(group_digits("-25000").as_deref(), Some("-25,000"));
+        assert_eq!(group_digits("1000").as_deref(), Some("1,000"));
+        assert_eq!(group_digits("999"), None);
+        assert_eq!(group_digits("1234.5"), None);
+        assert_eq!(group_digits("1e10"), None);
+
+        let style = theme::Theme::Dracula.config().json_style;
+        let annotated = highlight_json_annotated(r#""weight": 12000, "id": "a1000""#, &style);
+        let grouped = group_number_spans(&annotated);
+        let text: String = grouped[0].iter().map(|s| s.span.content.as_ref()).collect();
+        assert_eq!(text, r#""weight": 12,000, "id": "a1000""#);
+    }
+
+    #[test]
+    fn test_selection_text_joins_wrapped_lines() {
+        let style = theme::Theme::Dracula.config().json_style;
+        let annotated = highlight_json_annotated(
+            "{\n  \"description\": \"a very long text\",\n  \"id\": 1\n}",
+            &style,
+        );
+        let (wrapped, continued) = wrap_annotated_lines(&annotated, 16);
+        let lines: Vec<String> = wrapped
+            .iter()
+            .map(|line| line.iter().map(|s| s.span.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "{",
+                "  \"description\":",
+                " \"a very long te",
+                "xt\",",
+                "  \"id\": 1",
+                "}"
+            ]
+        );
+        assert_eq!(continued, [false, true, true, false, false, false]);
+
+        let select = |anchor: (usize, usize), cursor: (usize, usize)| {
+            let selection = DetailsSelection {
+                anchor: TextPos {
+                    line: anchor.0,
+                    column: anchor.1,
+                },
+                cursor: TextPos {
+                    line: cursor.0,
+                    column: cursor.1,
+                },
+            };
+            selection_text(&wrapped, &continued, &selection)
+        };
+        // The string value spans three wrapped lines but is one source line.
+        assert_eq!(select((2, 2), (3, 1)), "a very long text");
+        // Selecting backwards gives the same text.
+        assert_eq!(select((3, 1), (2, 2)), "a very long text");
+        // Real line breaks are kept; columns past a line's end select nothing.
+        assert_eq!(select((3, 0), (4, 40)), "xt\",\n  \"id\": 1");
+        assert_eq!(select((0, 0), (0, 0)), "{");
+    }
+
+    #[test]
+    fn test_word_wrap_keeps_words_whole() {
+        let style = theme::Theme::Dracula.config().json_style;
+        let annotated = highlight_json_annotated(
+            r#""description": "A sturdy leather backpack with plenty of pockets.""#,
+            &style,
+        );
+        let text = |wrapped: &[Vec<AnnotatedSpan>]| -> Vec<String> {
+            wrapped
+                .iter()
+                .map(|line| line.iter().map(|s| s.span.content.as_ref()).collect())
+                .collect()
+        };
+
+        let (chars, chars_continued) = wrap_annotated_lines(&annotated, 24);
+        assert_eq!(
+            text(&chars),
+            [
+                "\"description\": \"A sturdy",
+                " leather backpack with p",
+                "lenty of pockets.\""
+            ]
+        );
+
+        let (words, words_continued) = wrap_annotated_lines_at_words(&annotated, 24);
+        assert_eq!(
+            text(&words),
+            [
+                "\"description\": \"A ",
+                "sturdy leather backpack ",
+                "with plenty of pockets.\""
+            ]
+        );
+        assert_eq!(chars_continued, [true, true, false]);
+        assert_eq!(words_continued, chars_continued);
+        // Only whitespace moves; the text reads the same when joined.
+        assert_eq!(text(&words).concat(), text(&chars).concat());
+
+        // Unquoted, the value keeps its span id for clicks on any wrapped part.
+        let unquoted = unquote_long_strings(&annotated);
+        let (words, _) = wrap_annotated_lines_at_words(&unquoted, 24);
+        assert_eq!(
+            text(&words),
+            [
+                "\"description\": A sturdy ",
+                "leather backpack with ",
+                "plenty of pockets."
+            ]
+        );
+        let value_id = annotated[0][3].span_id;
+        assert!(value_id.is_some());
+        assert!(words[1..].iter().flatten().all(|s| s.span_id == value_id));
+
+        // A word wider than the line still breaks between characters.
+        let (long, _) = wrap_annotated_lines_at_words(&annotated, 8);
+        assert!(text(&long).iter().all(|line| line.width() <= 8));
+        // Short strings keep their quotes.
+        let short = highlight_json_annotated(r#""id": "backpack""#, &style);
+        assert_eq!(text(&unquote_long_strings(&short)), [r#""id": "backpack""#]);
+    }
+
+    #[test]
+    fn test_selection_is_highlighted() {
+        let style = theme::Theme::Dracula.config().json_style;
+        let annotated = highlight_json_annotated(r#""id": "rifle""#, &style);
+        let selection = DetailsSelection {
+            anchor: TextPos { line: 0, column: 7 },
+            cursor: TextPos {
+                line: 0,
+                column: 10,
+            },
+        };
+        let text = annotated_to_text(&annotated, None, None, Some(&selection));
+        let selected: String = text.lines[0]
+            .spans
+            .iter()
+            .filter(|span| span.style.add_modifier.contains(Modifier::REVERSED))
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(selected, "rifl");
+        let all: String = text.lines[0]
+            .spans
+            .iter()
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(all, r#""id": "rifle""#);
+    }
+
     #[test]
     fn test_hit_test_outside_area_returns_none() {
         let style = theme::Theme::Dracula.config().json_style;
         let annotated = highlight_json_annotated(r#"{"id": 1}"#, &style);
-        let wrapped = wrap_annotated_lines(&annotated, 80);
+        let wrapped = wrap_annotated_lines(&annotated, 80).0;
 
         let mut app = create_test_app();
         app.details_wrapped_annotated = wrapped;
@@ -1436,7 +3886,7 @@ mod tests {
     fn test_hit_test_on_key_span() {
         let style = theme::Theme::Dracula.config().json_style;
         let annotated = highlight_json_annotated(r#""id": 1"#, &style);
-        let wrapped = wrap_annotated_lines(&annotated, 80);
+        let wrapped = wrap_annotated_lines(&annotated, 80).0;
 
         let mut app = create_test_app();
         app.details_wrapped_annotated = wrapped;
@@ -1452,7 +3902,7 @@ mod tests {
     fn test_hit_test_on_value_span() {
         let style = theme::Theme::Dracula.config().json_style;
         let annotated = highlight_json_annotated(r#""id": 1"#, &style);
-        let wrapped = wrap_annotated_lines(&annotated, 80);
+        let wrapped = wrap_annotated_lines(&annotated, 80).0;
 
         let mut app = create_test_app();
         app.details_wrapped_annotated = wrapped;
@@ -1467,12 +3917,167 @@ mod tests {
         assert_eq!(span.key_context, Some(Rc::from("id")));
     }
 
+    #[test]
+    fn test_stacked_panes_do_not_overlap() {
+        let area = Rect::new(0, 0, 80, 40);
+        let panes = pane_areas(area, true);
+        let all = [panes.list, panes.details, panes.filter, panes.status];
+        for (i, a) in all.iter().enumerate() {
+            assert!(area.contains(a.as_position()));
+            for b in &all[i + 1..] {
+                assert!(!a.intersects(*b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+        // List on top of details, both full width, filter and status below.
+        assert_eq!(panes.list.width, 80);
+        assert_eq!(panes.details.width, 80);
+        assert!(panes.list.bottom() <= panes.details.y);
+        assert!(panes.details.bottom() <= panes.filter.y);
+        assert!(panes.filter.bottom() <= panes.status.y);
+
+        assert!(is_stacked(LayoutMode::Auto, 80));
+        assert!(!is_stacked(LayoutMode::Auto, 160));
+        assert!(!is_stacked(LayoutMode::SideBySide, 80));
+    }
+
+    #[test]
+    fn test_list_item_line_dims_abstracts() {
+        let theme = theme::Theme::Dracula.config();
+        let row = |display: &str, is_abstract: bool, warned: bool| ListRow {
+            display: display.to_string(),
+            type_prefix: "GUN ".to_string(),
+            is_abstract,
+            warned,
+            name_matches: Vec::new(),
+            columns: Vec::new(),
+        };
+        let abstract_row = row("(abs) gun_base", true, false);
+        let line = list_item_line(&abstract_row, false, &theme);
+        assert_eq!(line.to_string(), "GUN (abs) gun_base");
+        assert!(
+            line.spans[1]
+                .style
+                .add_modifier
+                .contains(Modifier::DIM | Modifier::ITALIC)
+        );
+
+        let rifle = row("rifle", false, false);
+        let line = list_item_line(&rifle, false, &theme);
+        assert!(line.spans[1].style.add_modifier.is_empty());
+
+        let line = list_item_line(&rifle, true, &theme);
+        assert_eq!(line.to_string(), "★ GUN rifle");
+
+        let warned = row("rifle", false, true);
+        let line = list_item_line(&warned, true, &theme);
+        assert_eq!(line.to_string(), "★ ! GUN rifle");
+    }
+
+    #[test]
+    fn test_name_match_ranges() {
+        let terms = matcher::parse_query("ZOM bie t:monster 'exact' mom*");
+        assert_eq!(
+            name_match_ranges("mon_zombie_zom", &terms),
+            vec![4..10, 11..14]
+        );
+        // Classifier-only queries leave the name alone.
+        let terms = matcher::parse_query("t:zombie");
+        assert!(name_match_ranges("mon_zombie", &terms).is_empty());
+
+        let theme = theme::Theme::Dracula.config();
+        let row = ListRow {
+            display: "mon_zombie".to_string(),
+            type_prefix: "MONSTER ".to_string(),
+            is_abstract: false,
+            warned: false,
+            name_matches: name_match_ranges("mon_zombie", &matcher::parse_query("zom")),
+            columns: Vec::new(),
+        };
+        let line = list_item_line(&row, false, &theme);
+        assert_eq!(line.to_string(), "MONSTER mon_zombie");
+        assert_eq!(line.spans[2].content, "zom");
+        assert!(
+            line.spans[2]
+                .style
+                .add_modifier
+                .contains(Modifier::BOLD | Modifier::UNDERLINED)
+        );
+    }
+
+    #[test]
+    fn test_empty_list_hint_mentions_unknown_field() {
+        let mut app = create_test_app();
+        app.filter_text = "typ:gun".to_string();
+        app.update_filter();
+        assert!(app.filtered_indices.is_empty());
+
+        let text: Vec<String> = empty_list_hint(&app)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(text[0], "No matching objects");
+        assert!(text.contains(&"Unknown field: typ".to_string()));
+        assert!(text.iter().any(|line| line.contains("`t:` for type")));
+
+        // Fast-path misses get the generic hint only.
+        app.filter_text = "t:gun".to_string();
+        app.update_filter();
+        let text: Vec<String> = empty_list_hint(&app)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert!(!text.iter().any(|line| line.contains("matched on")));
+    }
+
+    #[test]
+    fn test_empty_list_hint_shows_count() {
+        let mut app = create_test_app();
+        app.filter_text = "count:i:1".to_string();
+        app.update_filter();
+        assert!(app.filtered_indices.is_empty());
+        assert_eq!(app.match_count, Some(1));
+
+        let text: Vec<String> = empty_list_hint(&app)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(text[0], "1");
+        assert_eq!(text[1], "object matches");
+    }
+
+    #[test]
+    fn test_hit_test_with_horizontal_offset() {
+        let style = theme::Theme::Dracula.config().json_style;
+        let annotated =
+            highlight_json_annotated(r#""description": "a very long line", "id": 1"#, &style);
+
+        let mut app = create_test_app();
+        app.details_wrap = false;
+        app.details_wrapped_annotated = wrap_annotated_lines(&annotated, u16::MAX).0;
+        app.details_content_area = Some(Rect::new(0, 0, 20, 10));
+        assert_eq!(app.details_wrapped_annotated.len(), 1);
+
+        // Without scrolling, column 2 is the start of the "description" key.
+        let span = hit_test_details(&app, 2, 0).unwrap();
+        assert_eq!(span.span.content, "\"description\"");
+
+        // `"description": "a very long line", ` is 36 cells, so scrolling by 36
+        // puts the "id" key under column 1 (just past the padding).
+        app.details_scroll_state
+            .set_offset(ratatui::layout::Position::new(36, 0));
+        let span = hit_test_details(&app, 1, 0).unwrap();
+        assert_eq!(span.kind, JsonSpanKind::Key);
+        assert_eq!(span.span.content, "\"id\"");
+    }
+
     fn create_test_app() -> AppState {
         use serde_json::json;
         let indexed_items = vec![crate::data::IndexedItem {
             value: json!({"id": "1"}),
             id: "1".to_string(),
             item_type: "t".to_string(),
+            raw: None,
+            source: None,
         }];
         let search_index = crate::search_index::SearchIndex::build(&indexed_items);
         let theme = theme::Theme::Dracula.config();
@@ -1524,4 +4129,115 @@ mod tests {
         assert_eq!(filter_cursor_for_column("🦀a", 2), 1);
         assert_eq!(filter_cursor_for_column("🦀a", 3), 2);
     }
+
+    #[test]
+    fn test_indeterminate_segment_bounces_between_ends() {
+        // A 20-column bar lights 5 columns and travels 15 columns each way.
+        let starts: Vec<u16> = [0, 1, 14, 15, 16, 29, 30]
+            .into_iter()
+            .map(|frame| indeterminate_segment(20, frame).start)
+            .collect();
+        assert_eq!(starts, [0, 1, 14, 15, 14, 1, 0]);
+        assert_eq!(indeterminate_segment(20, 3), 3..8);
+        assert_eq!(indeterminate_segment(3, 5), 1..2);
+        assert_eq!(indeterminate_segment(1, 5), 0..1);
+        assert_eq!(indeterminate_segment(0, 5), 0..0);
+    }
+
+    #[test]
+    fn test_history_indicator() {
+        assert_eq!(history_indicator(None, 5), None);
+        assert_eq!(
+            history_indicator(Some(0), 5).as_deref(),
+            Some(" history 1/5 ")
+        );
+        assert_eq!(
+            history_indicator(Some(4), 5).as_deref(),
+            Some(" history 5/5 ")
+        );
+    }
+
+    #[test]
+    fn test_filter_spans_cover_text_with_token_styles() {
+        let theme = theme::Theme::Dracula.config();
+        let text = "t:gun name:'m4 carb";
+        let spans = filter_spans(text, &theme);
+
+        let joined: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(joined, text);
+        assert_eq!(spans[0].content, "t:");
+        assert_eq!(spans[0].style, theme.json_style.key);
+        assert_eq!(spans[1].style, Style::default());
+        assert_eq!(spans[4].content, "'m4 carb");
+        assert_eq!(spans[4].style, theme.query_error);
+    }
+
+    #[test]
+    fn test_column_value_extracts_fields() {
+        let item = serde_json::json!({
+            "id": "backpack",
+            "volume": "15 L",
+            "weight": 600,
+            "name": {"str": "backpack"},
+            "flags": ["BELTED", "WATERPROOF"],
+            "pocket_data": [{"max_contains_weight": "30 kg"}, {"max_contains_weight": "2 kg"}],
+            "looks_like": null
+        });
+
+        assert_eq!(column_value(&item, "volume"), "15 L");
+        assert_eq!(column_value(&item, "weight"), "600");
+        assert_eq!(column_value(&item, "name"), "backpack");
+        assert_eq!(column_value(&item, "flags"), "BELTED, WATERPROOF");
+        assert_eq!(
+            column_value(&item, "pocket_data.max_contains_weight"),
+            "30 kg, 2 kg"
+        );
+        assert_eq!(column_value(&item, "looks_like"), "");
+        assert_eq!(column_value(&item, "missing.path"), "");
+    }
+
+    #[test]
+    fn test_column_widths_and_fit_cell() {
+        let row = |display: &str, type_: &str, columns: &[&str]| ListRow {
+            display: display.to_string(),
+            type_prefix: format!("{} ", type_),
+            is_abstract: false,
+            warned: false,
+            name_matches: Vec::new(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+        };
+        let rows = [
+            row("rifle", "GUN", &["3 L"]),
+            row("p", "AMMO", &["a very long volume description here"]),
+        ];
+        let widths = column_widths(&rows, &["volume".to_string()]);
+        assert_eq!(widths, vec![5, 4, MAX_FIELD_COLUMN]);
+
+        assert_eq!(fit_cell("ab", 4), "ab  ");
+        assert_eq!(fit_cell("abcdef", 4), "abc…");
+        assert_eq!(fit_cell("🦀🦀🦀", 4), "🦀… ");
+    }
+
+    #[test]
+    fn test_minimap_cells_cover_every_line_once() {
+        // 100 lines on 8 cells: 12 or 13 lines each, in order, with no gaps.
+        let ranges: Vec<Range<usize>> = (0..8).map(|cell| minimap_lines(cell, 8, 100)).collect();
+        assert_eq!(ranges[0], 0..12);
+        assert_eq!(ranges[1], 12..25);
+        assert_eq!(ranges[7], 87..100);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        assert!(ranges.iter().all(|range| (12..=13).contains(&range.len())));
+
+        // Each line maps back into the cell that covers it.
+        for line in 0..100 {
+            let cell = minimap_cell(line, 8, 100);
+            assert!(minimap_lines(cell, 8, 100).contains(&line));
+        }
+        // Lines past the end stay on the last cell.
+        assert_eq!(minimap_cell(500, 8, 100), 7);
+        assert_eq!(minimap_lines(0, 0, 100), 0..0);
+        assert_eq!(minimap_cell(0, 8, 0), 0);
+    }
 }
@@ -0,0 +1,46 @@
+use serde_json::Value;
+use std::process::Command;
+
+const FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/headless_all.json"
+);
+
+fn run(query: &str) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_cbn-tui"))
+        .args(["--file", FIXTURE, "--query", query, "--json-only"])
+        .output()
+        .expect("failed to run cbn-tui")
+}
+
+#[test]
+fn json_only_prints_matches() {
+    let output = run("t:gun ammo:rpg");
+    assert!(output.status.success());
+
+    let items: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], "rifle");
+}
+
+#[test]
+fn json_only_fails_without_matches() {
+    let output = run("t:MONSTER");
+    assert!(!output.status.success());
+
+    let items: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(items.is_empty());
+}
+
+#[test]
+fn stats_reports_object_and_index_counts() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cbn-tui"))
+        .args(["--file", FIXTURE, "--stats"])
+        .output()
+        .expect("failed to run cbn-tui");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Objects:      3\n"));
+    assert!(stdout.contains("  ids                3 distinct,         3 postings\n"));
+}
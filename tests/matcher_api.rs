@@ -0,0 +1,87 @@
+use cbn_tui::data::IndexedItem;
+use cbn_tui::matcher::{self, Query, SearchTerm};
+use cbn_tui::search_index::SearchIndex;
+use serde_json::json;
+
+fn sample_items() -> Vec<IndexedItem> {
+    [
+        json!({"id": "rifle", "type": "GUN", "ammo": ["rpg"], "bash": {"str_min": 30}}),
+        json!({"id": "pistol", "type": "GUN", "ammo": ["9mm"]}),
+        json!({"abstract": "gun_base", "type": "GUN"}),
+        json!({"id": "rpg", "type": "AMMO", "category": "ammo"}),
+    ]
+    .into_iter()
+    .map(IndexedItem::from_value)
+    .collect()
+}
+
+#[test]
+fn query_parses_terms() {
+    let query = Query::parse("t:GUN snippet:'exact phrase' zombie");
+    assert_eq!(
+        query.terms(),
+        &[
+            SearchTerm {
+                classifier: Some("t".to_string()),
+                pattern: "GUN".to_string(),
+                exact: false,
+            },
+            SearchTerm {
+                classifier: Some("snippet".to_string()),
+                pattern: "exact phrase".to_string(),
+                exact: true,
+            },
+            SearchTerm {
+                classifier: None,
+                pattern: "zombie".to_string(),
+                exact: false,
+            },
+        ]
+    );
+    assert!(Query::parse("   ").is_empty());
+}
+
+#[test]
+fn query_matches_agree_with_indexed_search() {
+    let items = sample_items();
+    let index = SearchIndex::build(&items);
+
+    for text in [
+        "t:gun",
+        "t:'GUN' ammo:rpg",
+        "i:gun_base",
+        "bash.str_min:'30'",
+        "c:ammo",
+        "rpg",
+        "t:AMMO pistol",
+        "count:t:gun",
+        "t:gun (ammo:rpg | ammo:9mm)",
+        "c:ammo | i:pistol",
+    ] {
+        let query = Query::parse(text);
+        let unindexed: Vec<usize> = items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| query.matches(&item.value))
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(
+            unindexed,
+            matcher::find_matches(text, &items, &index),
+            "query {:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn value_and_field_helpers() {
+    let json = json!({"bash": {"str_min": 30}, "flags": ["EMITTER"]});
+    assert!(matcher::matches_field(&json, "bash.str_min", "30", true));
+    assert!(!matcher::matches_field(&json, "bash.str_min", "3", true));
+    assert!(matcher::matches_value(&json, "emit", false));
+    assert_eq!(
+        matcher::parse_search_term("id:'wouldn\\'t'").pattern,
+        "wouldn't"
+    );
+}